@@ -27,7 +27,10 @@
 //! `sync` | Whether to wait for command to exit or not. | `false`
 //! `update` | Whether to update the block on click. | `false`
 
+mod logind;
 mod prelude;
+mod sway_tree;
+mod upower;
 
 use futures::future::FutureExt;
 use futures::stream::FuturesUnordered;
@@ -143,64 +146,135 @@ macro_rules! define_blocks {
 }
 
 define_blocks!(
+    air_quality,
     amd_gpu,
     #[deprecated(
         since = "0.33.0",
         note = "The block has been deprecated in favor of the the packages block"
     )]
     apt,
+    aur,
     backlight,
+    backup,
     battery,
+    binding_mode,
     bluetooth,
+    bluetooth_audio,
+    btrfs,
+    clipboard,
     cpu,
+    crypto,
+    cups,
     custom,
     custom_dbus,
+    dbus_watch,
+    ddc,
     disk_space,
+    displays,
+    entropy,
     #[deprecated(
         since = "0.33.0",
         note = "The block has been deprecated in favor of the the packages block"
     )]
     dnf,
+    dns,
+    dock,
     docker,
+    dropbox,
+    energy_price,
+    exchange_rate,
     external_ip,
+    failed_units,
     focused_window,
+    gamemode,
     github,
+    gitlab,
+    home_assistant,
+    http,
     hueshift,
+    idle_inhibit,
+    jira,
+    journal_errors,
     kdeconnect,
+    kernel,
+    kubernetes,
+    libvirt,
     load,
     #[cfg(feature = "maildir")]
     maildir,
+    mdstat,
     menu,
     memory,
+    mpd,
     music,
     net,
+    network_mounts,
+    networkmanager,
+    nextcloud,
+    nightscout,
     notify,
     #[cfg(feature = "notmuch")]
     notmuch,
+    ntp,
     nvidia_gpu,
+    #[cfg(feature = "websocket")]
+    obs,
+    openvpn,
     packages,
     #[deprecated(
         since = "0.33.0",
         note = "The block has been deprecated in favor of the the packages block"
     )]
     pacman,
+    peripherals,
+    ping,
+    podman,
+    power_profile,
     pomodoro,
+    pressure,
     privacy,
+    process,
+    prometheus,
+    public_transport,
     rofication,
+    rss,
+    scratchpad,
+    screen_recording,
     service_status,
     sound,
+    snap,
     speedtest,
+    spotify,
+    ssh_sessions,
+    stocks,
+    stopwatch,
+    systemd_timers,
+    tailscale,
     keyboard_layout,
     taskwarrior,
     temperature,
+    throttling,
     time,
+    tlp,
     tea_timer,
+    toggl,
     toggle,
+    top_process,
+    torrent,
+    ups,
     uptime,
+    users,
+    uv_index,
+    vagrant,
     vpn,
     watson,
     weather,
+    #[cfg(feature = "websocket")]
+    websocket,
+    wireguard,
+    workspace_windows,
     xrandr,
+    zfs,
 );
 
 /// An error which originates from a block
@@ -0,0 +1,334 @@
+//! Air quality index
+//!
+//! This block displays the air quality index (AQI) for a location. The index is reported on the
+//! US EPA scale, which goes from 0 (clean air) to 500 (hazardous). The block's state is determined
+//! by the AQI category: "Good" is shown with the good color, "Moderate" with the info color,
+//! "Unhealthy for Sensitive Groups" with the warning color and everything above with the critical color.
+//!
+//! Configuring this block requires configuring an air quality service, which requires an API key.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `service` | The configuration of an air quality service (see below). | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $aqi "`
+//! `interval` | Update interval, in seconds. | `1800`
+//! `autolocate` | Gets your location using the ipapi.co IP location service (no API key required). If the API call fails then the block will fallback to `coordinates`. | `false`
+//! `autolocate_interval` | Update interval for `autolocate` in seconds or "once" | `interval`
+//! `coordinates` | GPS latitude longitude coordinates as a tuple, example: `["39.2362","9.3317"]` | Required if `autolocate = false`
+//!
+//! # World Air Quality Index Options
+//!
+//! A free token can be requested [here](https://aqicn.org/data-platform/token/).
+//!
+//! Key | Values | Required | Default
+//! ----|--------|----------|--------
+//! `name` | `waqi`. | Yes | None
+//! `token` | Your WAQI token. | Yes | None
+//!
+//! The `token` option can be omitted from configuration, in which case it must be provided in the
+//! environment variable `WAQI_TOKEN`.
+//!
+//! # OpenWeatherMap Options
+//!
+//! OpenWeatherMap only reports pollutant concentrations, so the AQI is calculated from the PM2.5
+//! and PM10 values.
+//!
+//! Key | Values | Required | Default
+//! ----|--------|----------|--------
+//! `name` | `openweathermap`. | Yes | None
+//! `api_key` | Your OpenWeatherMap API key. | Yes | None
+//!
+//! The `api_key` option can be omitted from configuration, in which case it must be provided in the
+//! environment variable `OPENWEATHERMAP_API_KEY`.
+//!
+//! Placeholder | Value                                                          | Type   | Unit
+//! ------------|----------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                  | Icon   | -
+//! `aqi`       | Air quality index (US EPA scale)                               | Number | -
+//! `category`  | Name of the AQI category, e.g. "Moderate"                      | Text   | -
+//! `pm25`      | PM2.5 concentration (µg/m³ for OpenWeatherMap, AQI for WAQI)   | Number | -
+//! `pm10`      | PM10 concentration (µg/m³ for OpenWeatherMap, AQI for WAQI)    | Number | -
+//! `location`  | Name of the measuring station (only provided by WAQI)          | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "air_quality"
+//! format = " $icon $aqi ($category) "
+//! coordinates = ["52.5200", "13.4050"]
+//! [block.service]
+//! name = "waqi"
+//! token = "XXX"
+//! ```
+//!
+//! # Icons Used
+//! - `air_quality`
+
+use super::prelude::*;
+use super::weather::resolve_coordinates;
+
+const WAQI_URL: &str = "https://api.waqi.info/feed";
+const WAQI_TOKEN_ENV: &str = "WAQI_TOKEN";
+const OWM_URL: &str = "https://api.openweathermap.org/data/2.5/air_pollution";
+const OWM_API_KEY_ENV: &str = "OPENWEATHERMAP_API_KEY";
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default = "default_interval")]
+    pub interval: Seconds,
+    pub service: AirQualityService,
+    #[serde(default)]
+    pub autolocate: bool,
+    pub autolocate_interval: Option<Seconds>,
+    pub coordinates: Option<(String, String)>,
+}
+
+fn default_interval() -> Seconds {
+    Seconds::new(1800)
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "name", rename_all = "lowercase")]
+pub enum AirQualityService {
+    Waqi {
+        #[serde(default = "getenv_waqi_token")]
+        token: Option<String>,
+    },
+    OpenWeatherMap {
+        #[serde(default = "getenv_openweathermap_api_key")]
+        api_key: Option<String>,
+    },
+}
+
+fn getenv_waqi_token() -> Option<String> {
+    std::env::var(WAQI_TOKEN_ENV).ok()
+}
+
+fn getenv_openweathermap_api_key() -> Option<String> {
+    std::env::var(OWM_API_KEY_ENV).ok()
+}
+
+struct AirQuality {
+    aqi: f64,
+    pm25: Option<f64>,
+    pm10: Option<f64>,
+    location: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $aqi ")?;
+
+    let autolocate_interval = config.autolocate_interval.unwrap_or(config.interval);
+    let mut timer = config.interval.timer();
+
+    loop {
+        let (lat, lon) = resolve_coordinates(
+            config.autolocate,
+            autolocate_interval.0,
+            config.coordinates.as_ref(),
+        )
+        .await?;
+
+        let fetch = || get_air_quality(&config.service, &lat, &lon);
+        let air = fetch.retry(&ExponentialBuilder::default()).await?;
+        let (category, state) = aqi_category(air.aqi);
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = state;
+        widget.set_values(map! {
+            "icon" => Value::icon("air_quality"),
+            "aqi" => Value::number(air.aqi),
+            "category" => Value::text(category.into()),
+            [if let Some(pm25) = air.pm25] "pm25" => Value::number(pm25),
+            [if let Some(pm10) = air.pm10] "pm10" => Value::number(pm10),
+            [if let Some(location) = air.location] "location" => Value::text(location),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn get_air_quality(service: &AirQualityService, lat: &str, lon: &str) -> Result<AirQuality> {
+    match service {
+        AirQualityService::Waqi { token } => {
+            let token = token.as_ref().or_error(|| {
+                format!("missing key 'service.token' and environment variable {WAQI_TOKEN_ENV}")
+            })?;
+            get_waqi(token, lat, lon).await
+        }
+        AirQualityService::OpenWeatherMap { api_key } => {
+            let api_key = api_key.as_ref().or_error(|| {
+                format!("missing key 'service.api_key' and environment variable {OWM_API_KEY_ENV}")
+            })?;
+            get_owm(api_key, lat, lon).await
+        }
+    }
+}
+
+async fn get_waqi(token: &str, lat: &str, lon: &str) -> Result<AirQuality> {
+    #[derive(Deserialize)]
+    #[serde(tag = "status", content = "data", rename_all = "lowercase")]
+    enum ApiResponse {
+        Ok(Data),
+        Error(String),
+    }
+
+    #[derive(Deserialize)]
+    struct Data {
+        // "-" if the station has no current reading
+        aqi: serde_json::Value,
+        city: City,
+        #[serde(default)]
+        iaqi: HashMap<String, Measurement>,
+    }
+
+    #[derive(Deserialize)]
+    struct City {
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Measurement {
+        v: f64,
+    }
+
+    let response: ApiResponse = REQWEST_CLIENT
+        .get(format!("{WAQI_URL}/geo:{lat};{lon}/?token={token}"))
+        .send()
+        .await
+        .error("Failed to send request")?
+        .json()
+        .await
+        .error("Failed to parse JSON")?;
+
+    match response {
+        ApiResponse::Ok(data) => Ok(AirQuality {
+            aqi: data.aqi.as_f64().error("The station has no AQI reading")?,
+            pm25: data.iaqi.get("pm25").map(|m| m.v),
+            pm10: data.iaqi.get("pm10").map(|m| m.v),
+            location: Some(data.city.name),
+        }),
+        ApiResponse::Error(message) => Err(Error::new(format!("API error: {message}"))),
+    }
+}
+
+async fn get_owm(api_key: &str, lat: &str, lon: &str) -> Result<AirQuality> {
+    #[derive(Deserialize)]
+    struct ApiResponse {
+        list: Vec<Entry>,
+    }
+
+    #[derive(Deserialize)]
+    struct Entry {
+        components: Components,
+    }
+
+    #[derive(Deserialize)]
+    struct Components {
+        pm2_5: f64,
+        pm10: f64,
+    }
+
+    let response: ApiResponse = REQWEST_CLIENT
+        .get(format!("{OWM_URL}?lat={lat}&lon={lon}&appid={api_key}"))
+        .send()
+        .await
+        .error("Failed to send request")?
+        .json()
+        .await
+        .error("Failed to parse JSON")?;
+
+    let components = response
+        .list
+        .into_iter()
+        .next()
+        .error("No air pollution data available")?
+        .components;
+
+    Ok(AirQuality {
+        aqi: pm25_to_aqi(components.pm2_5).max(pm10_to_aqi(components.pm10)),
+        pm25: Some(components.pm2_5),
+        pm10: Some(components.pm10),
+        location: None,
+    })
+}
+
+/// Concentration breakpoints and the corresponding AQI ranges, as published by the US EPA
+const PM25_BREAKPOINTS: [(f64, f64, f64, f64); 6] = [
+    (0.0, 9.0, 0.0, 50.0),
+    (9.1, 35.4, 51.0, 100.0),
+    (35.5, 55.4, 101.0, 150.0),
+    (55.5, 125.4, 151.0, 200.0),
+    (125.5, 225.4, 201.0, 300.0),
+    (225.5, 325.4, 301.0, 500.0),
+];
+
+const PM10_BREAKPOINTS: [(f64, f64, f64, f64); 6] = [
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 154.0, 51.0, 100.0),
+    (155.0, 254.0, 101.0, 150.0),
+    (255.0, 354.0, 151.0, 200.0),
+    (355.0, 424.0, 201.0, 300.0),
+    (425.0, 604.0, 301.0, 500.0),
+];
+
+fn concentration_to_aqi(breakpoints: &[(f64, f64, f64, f64)], concentration: f64) -> f64 {
+    breakpoints
+        .iter()
+        .find(|(_, c_hi, _, _)| concentration <= *c_hi)
+        .map(|(c_lo, c_hi, i_lo, i_hi)| {
+            ((i_hi - i_lo) / (c_hi - c_lo) * (concentration - c_lo) + i_lo).round()
+        })
+        .unwrap_or(500.0)
+}
+
+fn pm25_to_aqi(pm25: f64) -> f64 {
+    concentration_to_aqi(&PM25_BREAKPOINTS, (pm25 * 10.0).trunc() / 10.0)
+}
+
+fn pm10_to_aqi(pm10: f64) -> f64 {
+    concentration_to_aqi(&PM10_BREAKPOINTS, pm10.trunc())
+}
+
+fn aqi_category(aqi: f64) -> (&'static str, State) {
+    match aqi {
+        x if x <= 50.0 => ("Good", State::Good),
+        x if x <= 100.0 => ("Moderate", State::Info),
+        x if x <= 150.0 => ("Unhealthy for Sensitive Groups", State::Warning),
+        x if x <= 200.0 => ("Unhealthy", State::Critical),
+        x if x <= 300.0 => ("Very Unhealthy", State::Critical),
+        _ => ("Hazardous", State::Critical),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pm25_to_aqi() {
+        assert_eq!(pm25_to_aqi(0.0), 0.0);
+        assert_eq!(pm25_to_aqi(9.0), 50.0);
+        assert_eq!(pm25_to_aqi(9.09), 50.0);
+        assert_eq!(pm25_to_aqi(35.4), 100.0);
+        assert_eq!(pm25_to_aqi(55.5), 151.0);
+        assert_eq!(pm25_to_aqi(1000.0), 500.0);
+    }
+
+    #[test]
+    fn test_pm10_to_aqi() {
+        assert_eq!(pm10_to_aqi(54.9), 50.0);
+        assert_eq!(pm10_to_aqi(154.0), 100.0);
+        assert_eq!(pm10_to_aqi(604.0), 500.0);
+    }
+}
@@ -0,0 +1,128 @@
+//! Pending updates of AUR packages
+//!
+//! The versions of the installed foreign packages (`pacman -Qm`) are compared with the ones
+//! reported by the [AUR RPC](https://wiki.archlinux.org/title/Aurweb_RPC_interface), so no AUR
+//! helper is required. Packages which are not in the AUR are skipped.
+//!
+//! To respect the AUR's rate limits, the RPC is queried at most once every 30 minutes, regardless
+//! of `interval` and of manual refreshes.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `interval` | Update interval in seconds. | `1800`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count.eng(w:1) "`
+//! `format_singular` | Same as `format`, but for when exactly one update is available. | `" $icon $count.eng(w:1) "`
+//! `format_up_to_date` | Same as `format`, but for when no updates are available. | `" $icon $count.eng(w:1) "`
+//! `warning_updates_regex` | Display block as warning if updates matching regex are available. | `None`
+//! `critical_updates_regex` | Display block as critical if updates matching regex are available. | `None`
+//! `ignore` | Names of foreign packages which are not checked against the AUR | `[]`
+//!
+//! Placeholder | Value                       | Type   | Unit
+//! ------------|-----------------------------|--------|-----
+//! `icon`      | A static icon               | Icon   | -
+//! `count`     | Number of updates available | Number | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "aur"
+//! format = " $icon $count.eng(w:1) AUR updates "
+//! format_singular = " $icon One AUR update "
+//! format_up_to_date = ""
+//! ignore = ["my-local-package"]
+//! ```
+//!
+//! # Icons Used
+//!
+//! - `update`
+
+use regex::Regex;
+
+use super::{
+    packages::{has_matching_update, pacman::Aur, Backend},
+    prelude::*,
+};
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default(1800.into())]
+    pub interval: Seconds,
+    pub format: FormatConfig,
+    pub format_singular: FormatConfig,
+    pub format_up_to_date: FormatConfig,
+    pub warning_updates_regex: Option<String>,
+    pub critical_updates_regex: Option<String>,
+    pub ignore: Vec<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $count.eng(w:1) ")?;
+    let format_singular = config
+        .format_singular
+        .with_default(" $icon $count.eng(w:1) ")?;
+    let format_up_to_date = config
+        .format_up_to_date
+        .with_default(" $icon $count.eng(w:1) ")?;
+
+    let warning_updates_regex = config
+        .warning_updates_regex
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .error("invalid warning updates regex")?;
+    let critical_updates_regex = config
+        .critical_updates_regex
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .error("invalid critical updates regex")?;
+
+    let backend = Aur::new(None, config.ignore.clone());
+
+    loop {
+        let mut widget = Widget::new();
+
+        let updates = backend.get_updates_list().await?;
+        let count = updates.len();
+
+        widget.set_format(match count {
+            0 => format_up_to_date.clone(),
+            1 => format_singular.clone(),
+            _ => format.clone(),
+        });
+        widget.set_values(map!(
+            "icon" => Value::icon("update"),
+            "count" => Value::number(count)
+        ));
+
+        let warning = warning_updates_regex
+            .as_ref()
+            .is_some_and(|regex| has_matching_update(&updates, regex));
+        let critical = critical_updates_regex
+            .as_ref()
+            .is_some_and(|regex| has_matching_update(&updates, regex));
+        widget.state = match count {
+            0 => State::Idle,
+            _ => {
+                if critical {
+                    State::Critical
+                } else if warning {
+                    State::Warning
+                } else {
+                    State::Info
+                }
+            }
+        };
+
+        api.set_widget(widget)?;
+
+        select! {
+            _ = sleep(config.interval.0) => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
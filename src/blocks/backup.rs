@@ -0,0 +1,229 @@
+//! Age of the latest backup
+//!
+//! This block shows how long ago the latest snapshot of a [restic](https://restic.net) or
+//! [borg](https://www.borgbackup.org) repository was made, and is set to the warning or critical
+//! state once it is older than `warning` or `critical` hours.
+//!
+//! Listing the snapshots may take a while and may require the repository's disk to be mounted, so
+//! it is only done every `interval` seconds, while the age is updated every minute. If listing the
+//! snapshots fails later on, the time of the latest snapshot seen is kept. The password of the
+//! repository must be provided by the environment, e.g. using `RESTIC_PASSWORD_COMMAND` or
+//! `BORG_PASSCOMMAND`. The repository isn't locked while listing the snapshots.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `driver` | `"restic"` or `"borg"` | `"restic"`
+//! `repository` | The repository to check | `$RESTIC_REPOSITORY` or `$BORG_REPO`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$age\|never} \"</code>
+//! `interval` | How often to list the snapshots, in seconds | `3600`
+//! `warning` | Age in hours above which the state is set to warning | `26`
+//! `critical` | Age in hours above which the state is set to critical | `74`
+//!
+//! Placeholder   | Value                                                                  | Type     | Unit
+//! --------------|------------------------------------------------------------------------|----------|-----
+//! `icon`        | A static icon                                                          | Icon     | -
+//! `age`         | The age of the latest snapshot, e.g. `2d 3h` (absent if there is none) | Text     | -
+//! `time`        | The time of the latest snapshot (absent if there is none)              | Datetime | -
+//! `unreachable` | Present if the snapshots could not be listed the last time             | Flag     | -
+//!
+//! If the repository has no snapshots, the block is set to the critical state.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "backup"
+//! driver = "borg"
+//! repository = "ssh://backup@nas/./laptop"
+//! format = " $icon {$age|never}{$unreachable (offline)|} "
+//! warning = 48
+//! ```
+//!
+//! # Icons Used
+//! - `backup`
+
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use tokio::process::Command;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub driver: Driver,
+    pub repository: Option<String>,
+    pub format: FormatConfig,
+    #[default(3600.into())]
+    pub interval: Seconds,
+    #[default(26)]
+    pub warning: u64,
+    #[default(74)]
+    pub critical: u64,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Driver {
+    #[default]
+    Restic,
+    Borg,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon {$age|never} ")?;
+    let mut timer = config.interval.timer();
+
+    let mut latest = latest_snapshot(config).await?;
+    let mut unreachable = false;
+
+    loop {
+        let age = latest.map(|time| (Utc::now() - time).num_seconds().max(0) as u64);
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match age {
+            None => State::Critical,
+            Some(age) if age >= config.critical * 3600 => State::Critical,
+            Some(age) if age >= config.warning * 3600 => State::Warning,
+            Some(_) => State::Idle,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("backup"),
+            [if let Some(age) = age] "age" => Value::text(format_age(age)),
+            [if let Some(time) = latest] "time" => Value::datetime(time, None),
+            [if unreachable] "unreachable" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        let refresh = select! {
+            _ = timer.tick() => true,
+            _ = api.wait_for_update_request() => true,
+            _ = sleep(Duration::from_secs(60)) => false,
+        };
+        if refresh {
+            match latest_snapshot(config).await {
+                Ok(time) => {
+                    latest = time;
+                    unreachable = false;
+                }
+                // A repository on a disk which isn't mounted is not an error
+                Err(_) if latest.is_some() => unreachable = true,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Returns the time of the latest snapshot, or `None` if there are no snapshots
+async fn latest_snapshot(config: &Config) -> Result<Option<DateTime<Utc>>> {
+    let (name, mut cmd) = match config.driver {
+        Driver::Restic => {
+            let mut cmd = Command::new("restic");
+            cmd.args(["snapshots", "--json", "--no-lock", "--latest", "1"]);
+            if let Some(repo) = &config.repository {
+                cmd.args(["--repo", repo]);
+            }
+            ("restic", cmd)
+        }
+        Driver::Borg => {
+            let mut cmd = Command::new("borg");
+            cmd.args(["list", "--json", "--bypass-lock", "--last", "1"]);
+            if let Some(repo) = &config.repository {
+                cmd.arg(repo);
+            }
+            ("borg", cmd)
+        }
+    };
+    let output = cmd
+        .output()
+        .await
+        .or_error(|| format!("Failed to run {name}"))?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "Failed to list snapshots: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    match config.driver {
+        Driver::Restic => parse_restic(&output.stdout),
+        Driver::Borg => parse_borg(&output.stdout),
+    }
+}
+
+fn parse_restic(json: &[u8]) -> Result<Option<DateTime<Utc>>> {
+    #[derive(Deserialize)]
+    struct Snapshot {
+        time: String,
+    }
+
+    let snapshots: Vec<Snapshot> =
+        serde_json::from_slice(json).error("Failed to parse restic output")?;
+    // With --latest, the latest snapshot of each host and path set is returned
+    Ok(snapshots
+        .iter()
+        .filter_map(|s| DateTime::parse_from_rfc3339(&s.time).ok())
+        .map(|time| time.with_timezone(&Utc))
+        .max())
+}
+
+fn parse_borg(json: &[u8]) -> Result<Option<DateTime<Utc>>> {
+    #[derive(Deserialize)]
+    struct List {
+        archives: Vec<Archive>,
+    }
+
+    #[derive(Deserialize)]
+    struct Archive {
+        // In local time, without an offset
+        start: String,
+    }
+
+    let list: List = serde_json::from_slice(json).error("Failed to parse borg output")?;
+    Ok(list
+        .archives
+        .iter()
+        .filter_map(|a| a.start.parse::<NaiveDateTime>().ok())
+        .filter_map(|start| start.and_local_timezone(Local).earliest())
+        .map(|time| time.with_timezone(&Utc))
+        .max())
+}
+
+/// Formats an age in seconds with its two largest units, e.g. `2d 3h`
+fn format_age(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let hours = seconds % 86_400 / 3_600;
+    let minutes = seconds % 3_600 / 60;
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_restic() {
+        let json = br#"[
+            {"time":"2024-03-01T10:00:00.123456789+01:00","hostname":"a","id":"1"},
+            {"time":"2024-03-02T10:00:00+01:00","hostname":"b","id":"2"}
+        ]"#;
+        assert_eq!(
+            parse_restic(json).unwrap(),
+            Some("2024-03-02T09:00:00Z".parse().unwrap())
+        );
+        assert_eq!(parse_restic(b"[]").unwrap(), None);
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(59), "0m");
+        assert_eq!(format_age(3 * 3600 + 120), "3h 2m");
+        assert_eq!(format_age(2 * 86_400 + 3 * 3600 + 120), "2d 3h");
+    }
+}
@@ -5,6 +5,7 @@ use zvariant::ObjectPath;
 
 use super::{BatteryDevice, BatteryInfo, BatteryStatus, DeviceName};
 use crate::blocks::prelude::*;
+use crate::blocks::upower::{DeviceAddedStream, DeviceProxy, DeviceRemovedStream, UPowerProxy};
 use crate::util::new_system_dbus_connection;
 
 const DISPLAY_DEVICE_PATH: ObjectPath =
@@ -207,59 +208,3 @@ impl BatteryDevice for Device {
         Ok(())
     }
 }
-
-#[zbus::proxy(
-    interface = "org.freedesktop.UPower.Device",
-    default_service = "org.freedesktop.UPower"
-)]
-trait Device {
-    #[zbus(property)]
-    fn energy_rate(&self) -> zbus::Result<f64>;
-
-    #[zbus(property)]
-    fn is_present(&self) -> zbus::Result<bool>;
-
-    #[zbus(property)]
-    fn native_path(&self) -> zbus::Result<String>;
-
-    #[zbus(property)]
-    fn model(&self) -> zbus::Result<String>;
-
-    #[zbus(property)]
-    fn online(&self) -> zbus::Result<bool>;
-
-    #[zbus(property)]
-    fn percentage(&self) -> zbus::Result<f64>;
-
-    #[zbus(property)]
-    fn state(&self) -> zbus::Result<u32>;
-
-    #[zbus(property)]
-    fn time_to_empty(&self) -> zbus::Result<i64>;
-
-    #[zbus(property)]
-    fn time_to_full(&self) -> zbus::Result<i64>;
-
-    #[zbus(property, name = "Type")]
-    fn type_(&self) -> zbus::Result<u32>;
-}
-
-#[zbus::proxy(
-    interface = "org.freedesktop.UPower",
-    default_service = "org.freedesktop.UPower",
-    default_path = "/org/freedesktop/UPower"
-)]
-trait UPower {
-    fn enumerate_devices(&self) -> zbus::Result<Vec<zvariant::OwnedObjectPath>>;
-
-    fn get_display_device(&self) -> zbus::Result<zvariant::OwnedObjectPath>;
-
-    #[zbus(signal)]
-    fn device_added(&self, device: zvariant::OwnedObjectPath) -> zbus::Result<()>;
-
-    #[zbus(signal)]
-    fn device_removed(&self, device: zvariant::OwnedObjectPath) -> zbus::Result<()>;
-
-    #[zbus(property)]
-    fn on_battery(&self) -> zbus::Result<bool>;
-}
@@ -0,0 +1,77 @@
+//! Current binding mode of sway or i3
+//!
+//! This block shows the name of the current binding mode, e.g. `resize`, and is hidden in the
+//! default mode.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $mode "`
+//! `state` | [`State`] (color) of this block while a mode is active | [warning][State::Warning]
+//!
+//! Placeholder | Value                        | Type | Unit
+//! ------------|------------------------------|------|-----
+//! `mode`      | The name of the current mode | Text | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "binding_mode"
+//! format = " mode: $mode "
+//! state = "critical"
+//! ```
+
+use swayipc_async::{Connection, Event, EventType};
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(State::Warning)]
+    pub state: State,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $mode ")?;
+
+    let mut connection = Connection::new()
+        .await
+        .error("Failed to open swayipc connection")?;
+    let mut mode = connection
+        .get_binding_state()
+        .await
+        .error("Failed to get the binding state")?;
+    let mut events = connection
+        .subscribe([EventType::Mode])
+        .await
+        .error("Failed to subscribe to events")?;
+
+    loop {
+        if mode == "default" {
+            api.hide()?;
+        } else {
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = config.state;
+            widget.set_values(map! {
+                "mode" => Value::text(mode.clone()),
+            });
+            api.set_widget(widget)?;
+        }
+
+        loop {
+            let event = events
+                .next()
+                .await
+                .error("swayipc channel closed")?
+                .error("bad event")?;
+            if let Event::Mode(e) = event {
+                mode = e.change;
+                break;
+            }
+        }
+    }
+}
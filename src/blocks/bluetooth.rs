@@ -29,10 +29,13 @@
 //! `percentage`   | Device's battery level (may be absent if the device is not supported) | Number | %
 //! `battery_icon` | Battery icon (may be absent if the device is not supported)           | Icon   | -
 //! `available`    | Present if the device is available                                    | Flag   | -
+//! `connected`    | Present if the device is connected                                    | Flag   | -
 //!
-//! Action   | Default button
-//! ---------|---------------
-//! `toggle` | Right
+//! Action       | Default button
+//! -------------|---------------
+//! `toggle`     | Right
+//! `connect`    | -
+//! `disconnect` | -
 //!
 //! # Examples
 //!
@@ -50,6 +53,25 @@
 //! "71..100" = "good"
 //! ```
 //!
+//! The block can be used once per device, e.g. for headphones and a mouse:
+//!
+//! ```toml
+//! [[block]]
+//! block = "bluetooth"
+//! mac = "A0:8A:F5:E3:30:3D"
+//! format = " $icon{ $percentage|} "
+//! disconnected_format = " $icon "
+//! [[block.click]]
+//! button = "left"
+//! action = "connect"
+//!
+//! [[block]]
+//! block = "bluetooth"
+//! mac = "F4:73:35:12:0A:B1"
+//! format = " $icon{ $percentage|} "
+//! disconnected_format = ""
+//! ```
+//!
 //! # Icons Used
 //! - `headphones` for bluetooth devices identifying as "audio-card", "audio-headset" or "audio-headphones"
 //! - `joystick` for bluetooth devices identifying as "input-gaming"
@@ -111,6 +133,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
                     "icon" => Value::icon(device.icon),
                     "name" => Value::text(device.name),
                     "available" => Value::flag(),
+                    [if device.connected] "connected" => Value::flag(),
                     [if let Some(p) = device.battery_percentage] "percentage" => Value::percents(p),
                     [if let Some(p) = device.battery_percentage]
                         "battery_icon" => Value::icon_progression("bat", p as f64 / 100.0),
@@ -158,6 +181,18 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
                             }
                         }
                     }
+                    "connect" => {
+                        if let Some(dev) = &monitor.device {
+                            let _ = dev.device.connect().await;
+                            break;
+                        }
+                    }
+                    "disconnect" => {
+                        if let Some(dev) = &monitor.device {
+                            let _ = dev.device.disconnect().await;
+                            break;
+                        }
+                    }
                     _ => (),
                 }
             }
@@ -0,0 +1,305 @@
+//! Bluetooth audio device profile and codec
+//!
+//! This block shows the profile and the negotiated codec of the connected Bluetooth audio device,
+//! along with its battery level. Clicking the block switches between the high fidelity playback
+//! (A2DP) and headset (HSP/HFP) profiles, which is useful to find out why a call sounds bad.
+//!
+//! The profile and codec are read using `pactl`, so PulseAudio or PipeWire (with
+//! `pipewire-pulse`) is required. The battery level is read from BlueZ. The block is hidden if no
+//! Bluetooth audio device is connected.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $profile{ $codec\|}{ $percentage\|} \"</code>
+//! `interval` | Update interval in seconds | `5`
+//!
+//! Placeholder  | Value                                                        | Type   | Unit
+//! -------------|--------------------------------------------------------------|--------|-----
+//! `icon`       | A static icon                                                | Icon   | -
+//! `name`       | The name of the device                                       | Text   | -
+//! `profile`    | `A2DP`, `HFP` or `off`                                       | Text   | -
+//! `codec`      | The codec in use, e.g. `AAC` or `mSBC` (absent if unknown)   | Text   | -
+//! `percentage` | The battery level (absent if not supported by the device)    | Number | %
+//! `headset`    | Present if the headset profile is active                     | Flag   | -
+//!
+//! The block is set to the warning state while the headset profile is active.
+//!
+//! Action           | Description                                  | Default button
+//! -----------------|----------------------------------------------|---------------
+//! `toggle_profile` | Switch between the A2DP and headset profiles | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "bluetooth_audio"
+//! format = " $icon $name.str(max_w:10) $profile{ ($codec)|} "
+//! ```
+//!
+//! # Icons Used
+//! - `headphones`
+
+use super::prelude::*;
+use tokio::process::Command;
+use zbus::fdo::ObjectManagerProxy;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "toggle_profile")])?;
+
+    let format = config
+        .format
+        .with_default(" $icon $profile{ $codec|}{ $percentage|} ")?;
+    let mut timer = config.interval.timer();
+
+    let dbus_conn = new_system_dbus_connection().await?;
+    let manager_proxy = ObjectManagerProxy::builder(&dbus_conn)
+        .destination("org.bluez")
+        .and_then(|x| x.path("/"))
+        .unwrap()
+        .build()
+        .await
+        .error("Failed to create ObjectManagerProxy")?;
+
+    loop {
+        let card = get_cards().await?.into_iter().next();
+
+        match &card {
+            Some(card) => {
+                let profile = card.profile_kind(&card.active_profile);
+                let codec = card
+                    .profiles
+                    .get(&card.active_profile)
+                    .and_then(|p| parse_codec(&p.description));
+                let percentage = match card.address() {
+                    Some(address) => battery_percentage(&manager_proxy, address).await,
+                    None => None,
+                };
+
+                let mut widget = Widget::new().with_format(format.clone());
+                if profile == ProfileKind::Headset {
+                    widget.state = State::Warning;
+                }
+                widget.set_values(map! {
+                    "icon" => Value::icon("headphones"),
+                    "name" => Value::text(card.description().to_string()),
+                    "profile" => Value::text(profile.to_string()),
+                    [if let Some(codec) = codec] "codec" => Value::text(codec),
+                    [if let Some(p) = percentage] "percentage" => Value::percents(p),
+                    [if profile == ProfileKind::Headset] "headset" => Value::flag(),
+                });
+                api.set_widget(widget)?;
+            }
+            None => api.hide()?,
+        }
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "toggle_profile" => {
+                        if let Some(card) = &card {
+                            card.toggle_profile().await?;
+                            break;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Card {
+    name: String,
+    #[serde(default)]
+    properties: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+    #[serde(default)]
+    active_profile: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Profile {
+    description: String,
+    #[serde(default = "default_available")]
+    available: bool,
+}
+
+fn default_available() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProfileKind {
+    A2dp,
+    Headset,
+    Off,
+}
+
+impl std::fmt::Display for ProfileKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::A2dp => "A2DP",
+            Self::Headset => "HFP",
+            Self::Off => "off",
+        })
+    }
+}
+
+impl Card {
+    fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key)?.as_str()
+    }
+
+    fn description(&self) -> &str {
+        self.property("device.description").unwrap_or(&self.name)
+    }
+
+    /// PipeWire and PulseAudio use different properties for the address
+    fn address(&self) -> Option<&str> {
+        self.property("api.bluez5.address")
+            .or_else(|| self.property("device.string"))
+    }
+
+    fn profile_kind(&self, profile: &str) -> ProfileKind {
+        // PipeWire uses e.g. `a2dp-sink-aac` and `headset-head-unit-msbc`, PulseAudio `a2dp_sink`
+        // and `handsfree_head_unit`
+        if profile.starts_with("a2dp") {
+            ProfileKind::A2dp
+        } else if profile.starts_with("headset") || profile.starts_with("handsfree") {
+            ProfileKind::Headset
+        } else {
+            ProfileKind::Off
+        }
+    }
+
+    async fn toggle_profile(&self) -> Result<()> {
+        let target = match self.profile_kind(&self.active_profile) {
+            ProfileKind::A2dp => ProfileKind::Headset,
+            ProfileKind::Headset | ProfileKind::Off => ProfileKind::A2dp,
+        };
+        let mut profiles: Vec<&String> = self
+            .profiles
+            .iter()
+            .filter(|(name, profile)| profile.available && self.profile_kind(name) == target)
+            .map(|(name, _)| name)
+            .collect();
+        // Prefer the profile without an explicit codec, which lets the server choose the best one
+        profiles.sort_by_key(|name| name.len());
+        let profile = profiles
+            .first()
+            .or_error(|| format!("No {target} profile available"))?;
+
+        let status = Command::new("pactl")
+            .args(["set-card-profile", &self.name, profile])
+            .status()
+            .await
+            .error("Failed to run pactl")?;
+        if !status.success() {
+            return Err(Error::new("Failed to set the card profile"));
+        }
+        Ok(())
+    }
+}
+
+async fn get_cards() -> Result<Vec<Card>> {
+    let output = Command::new("pactl")
+        .args(["--format=json", "list", "cards"])
+        .output()
+        .await
+        .error("Failed to run pactl")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "pactl list cards failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let cards: Vec<Card> =
+        serde_json::from_slice(&output.stdout).error("Failed to parse pactl output")?;
+    Ok(cards
+        .into_iter()
+        .filter(|card| card.name.starts_with("bluez_card."))
+        .collect())
+}
+
+/// Extracts the codec from a profile description such as
+/// `High Fidelity Playback (A2DP Sink, codec AAC)`
+fn parse_codec(description: &str) -> Option<String> {
+    let codec = description.split("codec ").nth(1)?;
+    Some(codec.trim_end_matches(')').trim().to_string())
+}
+
+async fn battery_percentage(manager_proxy: &ObjectManagerProxy<'_>, address: &str) -> Option<u8> {
+    let objects = manager_proxy.get_managed_objects().await.ok()?;
+    objects.values().find_map(|interfaces| {
+        let device_address: &str = interfaces
+            .get("org.bluez.Device1")?
+            .get("Address")?
+            .downcast_ref()
+            .ok()?;
+        if !device_address.eq_ignore_ascii_case(address) {
+            return None;
+        }
+        interfaces
+            .get("org.bluez.Battery1")?
+            .get("Percentage")?
+            .downcast_ref()
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codec() {
+        assert_eq!(
+            parse_codec("High Fidelity Playback (A2DP Sink, codec AAC)").as_deref(),
+            Some("AAC")
+        );
+        assert_eq!(
+            parse_codec("Headset Head Unit (HSP/HFP, codec mSBC)").as_deref(),
+            Some("mSBC")
+        );
+        assert_eq!(parse_codec("High Fidelity Playback (A2DP Sink)"), None);
+    }
+
+    #[test]
+    fn test_profile_kind() {
+        let card: Card = serde_json::from_str(
+            r#"{
+                "name": "bluez_card.A0_8A_F5_E3_30_3D",
+                "properties": { "api.bluez5.address": "A0:8A:F5:E3:30:3D" },
+                "profiles": {},
+                "active_profile": "a2dp-sink-aac"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(card.address(), Some("A0:8A:F5:E3:30:3D"));
+        assert_eq!(card.profile_kind(&card.active_profile), ProfileKind::A2dp);
+        assert_eq!(
+            card.profile_kind("headset-head-unit-msbc"),
+            ProfileKind::Headset
+        );
+        assert_eq!(
+            card.profile_kind("handsfree_head_unit"),
+            ProfileKind::Headset
+        );
+        assert_eq!(card.profile_kind("off"), ProfileKind::Off);
+    }
+}
@@ -0,0 +1,219 @@
+//! Btrfs device errors, scrub and balance status
+//!
+//! This block shows the device error counters of a Btrfs filesystem, summed over all of its
+//! devices, and whether an exclusive operation (such as a balance) or a scrub is running. Error
+//! counters are read from sysfs, which requires Linux 5.14 or later. The UUID of the filesystem
+//! is looked up using `findmnt`.
+//!
+//! Scrub progress is read using `btrfs scrub status`, which may require root privileges. If the
+//! command fails, the scrub placeholders are simply absent.
+//!
+//! The block is set to the critical state if any error counter is not zero and to the info state
+//! while a scrub or an exclusive operation is running.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `path` | A path on the filesystem, e.g. its mount point | `"/"`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $errors{ $operation\|}{ scrub $scrub_progress\|} "`
+//! `interval` | Update interval in seconds | `60`
+//!
+//! Placeholder       | Value                                                            | Type   | Unit
+//! ------------------|------------------------------------------------------------------|--------|-----
+//! `icon`            | A static icon                                                    | Icon   | -
+//! `errors`          | The sum of all error counters                                    | Number | -
+//! `write_errs`      | Failed writes                                                    | Number | -
+//! `read_errs`       | Failed reads                                                     | Number | -
+//! `flush_errs`      | Failed flushes                                                   | Number | -
+//! `corruption_errs` | Checksum errors                                                  | Number | -
+//! `generation_errs` | Blocks with a wrong generation                                   | Number | -
+//! `operation`       | The running exclusive operation, e.g. `balance` (absent if none) | Text   | -
+//! `scrub_progress`  | The progress of a running scrub (absent if none)                 | Number | %
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "btrfs"
+//! path = "/home"
+//! format = " $icon $corruption_errs/$errors "
+//! ```
+//!
+//! # Icons Used
+//! - `disk_drive`
+
+use super::prelude::*;
+use crate::util::read_file;
+use tokio::process::Command;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("/".into())]
+    pub path: ShellString,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+const COUNTERS: [&str; 5] = [
+    "write_errs",
+    "read_errs",
+    "flush_errs",
+    "corruption_errs",
+    "generation_errs",
+];
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $errors{ $operation|}{ scrub $scrub_progress|} ")?;
+    let mut timer = config.interval.timer();
+
+    let path = config.path.expand()?;
+    let output = Command::new("findmnt")
+        .args(["-no", "UUID", "--target", &path])
+        .output()
+        .await
+        .error("Failed to run findmnt")?;
+    let uuid = String::from_utf8(output.stdout).error("findmnt produced non-UTF8 output")?;
+    let uuid = uuid.trim();
+    if uuid.is_empty() {
+        return Err(Error::new(format!("Failed to find the UUID of {path}")));
+    }
+    let sysfs_dir = format!("/sys/fs/btrfs/{uuid}");
+
+    loop {
+        let mut counters = HashMap::new();
+        let mut devices = tokio::fs::read_dir(format!("{sysfs_dir}/devinfo"))
+            .await
+            .error("Failed to read devinfo")?;
+        while let Some(device) = devices.next_entry().await.error("Failed to read devinfo")? {
+            let stats = read_file(device.path().join("error_stats"))
+                .await
+                .error("Failed to read error_stats")?;
+            for (name, value) in parse_error_stats(&stats) {
+                *counters.entry(name).or_insert(0) += value;
+            }
+        }
+        let errors: u64 = counters.values().sum();
+
+        let operation = read_file(format!("{sysfs_dir}/exclusive_operation"))
+            .await
+            .ok()
+            .filter(|op| op != "none");
+
+        let scrub_progress = match Command::new("btrfs")
+            .args(["scrub", "status", &path])
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => {
+                parse_scrub_progress(&String::from_utf8_lossy(&output.stdout))
+            }
+            _ => None,
+        };
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if errors > 0 {
+            State::Critical
+        } else if operation.is_some() || scrub_progress.is_some() {
+            State::Info
+        } else {
+            State::Idle
+        };
+        let mut values = map! {
+            "icon" => Value::icon("disk_drive"),
+            "errors" => Value::number(errors),
+            [if let Some(op) = operation] "operation" => Value::text(op),
+            [if let Some(p) = scrub_progress] "scrub_progress" => Value::percents(p),
+        };
+        for name in COUNTERS {
+            values.insert(
+                name.into(),
+                Value::number(counters.get(name).copied().unwrap_or(0)),
+            );
+        }
+        widget.set_values(values);
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Parses the `<name> <value>` lines of `error_stats`
+fn parse_error_stats(stats: &str) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+    stats.lines().filter_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        let name = COUNTERS.into_iter().find(|c| *c == name)?;
+        Some((name, value.trim().parse().ok()?))
+    })
+}
+
+/// Finds the progress of a running scrub in the output of `btrfs scrub status`
+fn parse_scrub_progress(output: &str) -> Option<f64> {
+    let mut running = false;
+    let mut progress = None;
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "Status" => running = value.trim() == "running",
+            // e.g. "Bytes scrubbed:   50.00GiB  (32.00%)"
+            "Bytes scrubbed" => {
+                progress = value
+                    .split_once('(')
+                    .and_then(|(_, p)| p.split_once("%)"))
+                    .and_then(|(p, _)| p.trim().parse().ok());
+            }
+            _ => (),
+        }
+    }
+    if running {
+        Some(progress.unwrap_or(0.0))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_stats() {
+        let stats = "write_errs 0\nread_errs 2\nflush_errs 0\ncorruption_errs 5\ngeneration_errs 0";
+        let counters: Vec<_> = parse_error_stats(stats).collect();
+        assert_eq!(counters[1], ("read_errs", 2));
+        assert_eq!(counters[3], ("corruption_errs", 5));
+        assert_eq!(counters.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_scrub_progress() {
+        let output = "UUID:             1234
+Scrub started:    Sun Oct 11 10:00:00 2026
+Status:           running
+Duration:         0:10:00
+Time left:        0:21:15
+ETA:              Sun Oct 11 10:31:15 2026
+Total to scrub:   156.25GiB
+Bytes scrubbed:   50.00GiB  (32.00%)
+Rate:             85.33MiB/s
+Error summary:    no errors found";
+        assert_eq!(parse_scrub_progress(output), Some(32.0));
+        let output = "UUID:             1234
+Scrub started:    Sun Oct 11 10:00:00 2026
+Status:           finished
+Duration:         0:31:15
+Total to scrub:   156.25GiB
+Rate:             85.33MiB/s
+Error summary:    no errors found";
+        assert_eq!(parse_scrub_progress(output), None);
+    }
+}
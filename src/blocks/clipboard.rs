@@ -0,0 +1,205 @@
+//! Clipboard preview
+//!
+//! This block shows the beginning of the text in the clipboard. On Wayland, `wl-paste` is used to
+//! watch the clipboard for changes. On X11, `xclip` is used to read it every `interval` seconds.
+//! The block is hidden while the clipboard is empty or contains something else than text.
+//!
+//! If `hide_secrets` is enabled, the contents are not shown if they look like a password, that is
+//! a single word of 8 to 128 characters containing at least three kinds of characters out of lower
+//! case letters, upper case letters, digits and symbols.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `driver` | `"wayland"` or `"x11"` | `"wayland"` if `$WAYLAND_DISPLAY` is set, `"x11"` otherwise
+//! `hide_secrets` | Don't show contents which look like a password | `true`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$text.str(max_w:30)\|***} \"</code>
+//! `interval` | Update interval in seconds (X11 only) | `2`
+//!
+//! Placeholder | Value                                                                      | Type | Unit
+//! ------------|----------------------------------------------------------------------------|------|-----
+//! `icon`      | A static icon                                                              | Icon | -
+//! `text`      | The contents of the clipboard on one line (absent if a secret)             | Text | -
+//! `secret`    | Present if the contents look like a password and `hide_secrets` is enabled | Flag | -
+//!
+//! Action  | Description         | Default button
+//! --------|---------------------|---------------
+//! `clear` | Clear the clipboard | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "clipboard"
+//! format = " $icon {$text.str(max_w:20,rot_interval:0.5)|hidden} "
+//! ```
+//!
+//! # Icons Used
+//! - `clipboard`
+
+use std::process::Stdio;
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub driver: Option<Driver>,
+    #[default(true)]
+    pub hide_secrets: bool,
+    pub format: FormatConfig,
+    #[default(2.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Driver {
+    Wayland,
+    X11,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "clear")])?;
+
+    let format = config
+        .format
+        .with_default(" $icon {$text.str(max_w:30)|***} ")?;
+    let mut timer = config.interval.timer();
+
+    let driver = config.driver.unwrap_or_else(|| {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            Driver::Wayland
+        } else {
+            Driver::X11
+        }
+    });
+
+    // `wl-paste --watch` runs the given command each time the clipboard changes
+    let mut watcher = match driver {
+        Driver::Wayland => Some(
+            Command::new("wl-paste")
+                .args(["--watch", "echo"])
+                .stdout(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()
+                .error("Failed to run wl-paste")?,
+        ),
+        Driver::X11 => None,
+    };
+    let watching = watcher.is_some();
+    let mut changes = watcher
+        .as_mut()
+        .map(|child| BufReader::new(child.stdout.take().unwrap()).lines());
+
+    loop {
+        let contents = read_clipboard(driver).await?;
+        let text = contents.split_whitespace().collect::<Vec<_>>().join(" ");
+        let secret = config.hide_secrets && looks_like_password(&contents);
+
+        if text.is_empty() {
+            api.hide()?;
+        } else {
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.set_values(map! {
+                "icon" => Value::icon("clipboard"),
+                [if !secret] "text" => Value::text(text),
+                [if secret] "secret" => Value::flag(),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            line = async { changes.as_mut().unwrap().next_line().await }, if watching => {
+                line.error("Failed to read wl-paste output")?
+                    .error("wl-paste exited unexpectedly")?;
+            }
+            _ = timer.tick(), if !watching => (),
+            _ = api.wait_for_update_request() => (),
+            Some(action) = actions.recv() => match action.as_ref() {
+                "clear" => clear_clipboard(driver).await?,
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Returns the text in the clipboard, or an empty string if it contains something else
+async fn read_clipboard(driver: Driver) -> Result<String> {
+    let mut cmd = match driver {
+        Driver::Wayland => {
+            let mut cmd = Command::new("wl-paste");
+            cmd.args(["--no-newline", "--type", "text"]);
+            cmd
+        }
+        Driver::X11 => {
+            let mut cmd = Command::new("xclip");
+            cmd.args(["-selection", "clipboard", "-out", "-target", "UTF8_STRING"]);
+            cmd
+        }
+    };
+    let output = cmd
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .error("Failed to read the clipboard")?;
+    // Both tools fail if the clipboard is empty or has no text
+    if !output.status.success() {
+        return Ok(String::new());
+    }
+    Ok(String::from_utf8(output.stdout).unwrap_or_default())
+}
+
+async fn clear_clipboard(driver: Driver) -> Result<()> {
+    let mut cmd = match driver {
+        Driver::Wayland => {
+            let mut cmd = Command::new("wl-copy");
+            cmd.arg("--clear");
+            cmd
+        }
+        Driver::X11 => {
+            let mut cmd = Command::new("xclip");
+            cmd.args(["-selection", "clipboard", "-in", "/dev/null"]);
+            cmd
+        }
+    };
+    cmd.stdin(Stdio::null())
+        .status()
+        .await
+        .error("Failed to clear the clipboard")?;
+    Ok(())
+}
+
+fn looks_like_password(text: &str) -> bool {
+    let text = text.trim();
+    if !(8..=128).contains(&text.chars().count()) || text.contains(char::is_whitespace) {
+        return false;
+    }
+    let kinds = [
+        text.contains(|c: char| c.is_lowercase()),
+        text.contains(|c: char| c.is_uppercase()),
+        text.contains(|c: char| c.is_ascii_digit()),
+        text.contains(|c: char| !c.is_alphanumeric()),
+    ];
+    kinds.into_iter().filter(|&k| k).count() >= 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_password() {
+        assert!(looks_like_password("hunter2-Secret"));
+        assert!(looks_like_password("Xk9vQ2mLp0"));
+        assert!(!looks_like_password("hello world, this is text"));
+        assert!(!looks_like_password("https://example.com/page"));
+        assert!(!looks_like_password("Ab1!"));
+    }
+}
@@ -10,6 +10,7 @@
 //! `info_cpu` | Percentage of CPU usage, where state is set to info | `30.0`
 //! `warning_cpu` | Percentage of CPU usage, where state is set to warning | `60.0`
 //! `critical_cpu` | Percentage of CPU usage, where state is set to critical | `90.0`
+//! `governor_command` | Command to switch the scaling governor, the name of which is appended to it | `"pkexec cpupower frequency-set -g"`
 //!
 //! Placeholder      | Value                                                                | Type   | Unit
 //! -----------------|----------------------------------------------------------------------|--------|---------------
@@ -21,10 +22,17 @@
 //! `frequency<N>`   | Frequency of Nth logical CPU (may be absent if CPU is not supported) | Number | Hz
 //! `max_frequency`  | Max frequency of all logical CPUs                                    | Number | Hz
 //! `boost`          | CPU turbo boost status (may be absent if CPU is not supported)       | Text   | -
+//! `governor`       | Scaling governor of the first CPU (may be absent if not supported)   | Text   | -
 //!
-//! Action          | Description                               | Default button
-//! ----------------|-------------------------------------------|---------------
-//! `toggle_format` | Toggles between `format` and `format_alt` | Left
+//! Action          | Description                                       | Default button
+//! ----------------|---------------------------------------------------|---------------
+//! `toggle_format` | Toggles between `format` and `format_alt`         | Left
+//! `next_governor` | Switch to the next available scaling governor     | -
+//! `prev_governor` | Switch to the previous available scaling governor | -
+//!
+//! Switching the governor requires root, so the governor actions are not bound to any button by
+//! default. By default, `pkexec` is used, so a polkit agent asks for the password, unless a polkit
+//! rule allows running `cpupower` without one.
 //!
 //! # Example
 //!
@@ -33,10 +41,16 @@
 //! block = "cpu"
 //! interval = 1
 //! format = " $icon $barchart $utilization "
-//! format_alt = " $icon $frequency{ $boost|} "
+//! format_alt = " $icon $frequency{ $boost|}{ $governor|} "
 //! info_cpu = 20
 //! warning_cpu = 50
 //! critical_cpu = 90
+//! [[block.click]]
+//! button = "up"
+//! action = "next_governor"
+//! [[block.click]]
+//! button = "down"
+//! action = "prev_governor"
 //! ```
 //!
 //! # Icons Used
@@ -50,10 +64,14 @@ use tokio::fs::File;
 use tokio::io::{AsyncBufReadExt, BufReader};
 
 use super::prelude::*;
+use crate::subprocess::spawn_shell;
 use crate::util::read_file;
 
 const CPU_BOOST_PATH: &str = "/sys/devices/system/cpu/cpufreq/boost";
 const CPU_NO_TURBO_PATH: &str = "/sys/devices/system/cpu/intel_pstate/no_turbo";
+const CPU_GOVERNOR_PATH: &str = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor";
+const CPU_AVAILABLE_GOVERNORS_PATH: &str =
+    "/sys/devices/system/cpu/cpu0/cpufreq/scaling_available_governors";
 
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
@@ -68,6 +86,8 @@ pub struct Config {
     pub warning_cpu: f64,
     #[default(90.0)]
     pub critical_cpu: f64,
+    #[default("pkexec cpupower frequency-set -g".into())]
+    pub governor_command: String,
 }
 
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
@@ -118,12 +138,15 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             false => "cpu_boost_off",
         });
 
+        let governor = read_file(CPU_GOVERNOR_PATH).await.ok();
+
         let mut values = map!(
             "icon" => Value::icon_progression("cpu", utilization_avg),
             "barchart" => Value::text(barchart),
             "utilization" => Value::percents(utilization_avg * 100.),
             [if !freqs.is_empty()] "frequency" => Value::hertz(freqs.iter().sum::<f64>() / (freqs.len() as f64)),
             [if !freqs.is_empty()] "max_frequency" => Value::hertz(freqs.iter().copied().max_by(f64::total_cmp).unwrap()),
+            [if let Some(g) = &governor] "governor" => Value::text(g.clone()),
         );
         boost.map(|b| values.insert("boost".into(), Value::icon(b)));
         for (i, freq) in freqs.iter().enumerate() {
@@ -157,6 +180,12 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
                             break;
                         }
                     }
+                    "next_governor" | "prev_governor" => {
+                        if let Some(governor) = &governor {
+                            let step = if action.as_ref() == "next_governor" { 1 } else { -1 };
+                            switch_governor(config, governor, step).await?;
+                        }
+                    }
                     _ => (),
                 }
             }
@@ -164,6 +193,22 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     }
 }
 
+/// Switches to the governor `step` places away from `current` in the list of available governors
+async fn switch_governor(config: &Config, current: &str, step: isize) -> Result<()> {
+    let available = read_file(CPU_AVAILABLE_GOVERNORS_PATH)
+        .await
+        .error("Failed to read the available governors")?;
+    let governors: Vec<&str> = available.split_whitespace().collect();
+    let Some(index) = governors.iter().position(|g| *g == current) else {
+        return Ok(());
+    };
+    let next = governors[(index as isize + step).rem_euclid(governors.len() as isize) as usize];
+    // Don't wait for the command, the password prompt may stay open for a while. The new
+    // governor is shown on the next update.
+    spawn_shell(&format!("{} {next}", config.governor_command))
+        .error("Failed to run governor_command")
+}
+
 // Read frequencies (read in MHz, store in Hz)
 async fn read_frequencies() -> Result<Vec<f64>> {
     let mut freqs = Vec::with_capacity(32);
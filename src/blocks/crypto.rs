@@ -0,0 +1,204 @@
+//! Cryptocurrency prices
+//!
+//! This block shows the prices of cryptocurrencies, fetched from the [CoinGecko](https://www.coingecko.com/)
+//! API. If more than one coin is configured, the block shows one at a time; the mouse wheel
+//! switches between them.
+//!
+//! The public API is rate limited, so `interval` can't be lower than 60 seconds, and manual
+//! refreshes are delayed until 60 seconds have passed since the previous request. If the rate limit
+//! is hit anyway, the block keeps showing the last prices and waits as long as the API asks to
+//! before trying again.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `coins` | List of CoinGecko coin IDs, e.g. `["bitcoin", "ethereum"]` | `["bitcoin"]`
+//! `currency` | The currency to show the prices in | `"usd"`
+//! `api_key` | A CoinGecko demo API key | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $coin $price.eng(w:5){ $change.eng(w:3)\|} \"</code>
+//! `interval` | Update interval in seconds | `300`
+//!
+//! Placeholder | Value                                                     | Type   | Unit
+//! ------------|-----------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                             | Icon   | -
+//! `coin`      | The ID of the coin                                        | Text   | -
+//! `currency`  | The currency of the price, in upper case                  | Text   | -
+//! `price`     | The price of the coin                                     | Number | -
+//! `change`    | The price change in the last 24 hours (absent if unknown) | Number | %
+//!
+//! The block is set to the good state if the price went up in the last 24 hours and to the warning
+//! state if it went down.
+//!
+//! Action | Description                | Default button
+//! -------|----------------------------|---------------
+//! `next` | Show the next coin         | Wheel Up
+//! `prev` | Show the previous coin     | Wheel Down
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "crypto"
+//! coins = ["bitcoin", "ethereum", "monero"]
+//! currency = "eur"
+//! format = " $icon $coin $price.eng(w:5)€{ $change.eng(w:3)|} "
+//! interval = 600
+//! ```
+//!
+//! # Icons Used
+//! - `crypto`
+
+use super::prelude::*;
+
+const MIN_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default(vec!["bitcoin".into()])]
+    pub coins: Vec<String>,
+    #[default("usd".into())]
+    pub currency: String,
+    pub api_key: Option<String>,
+    pub format: FormatConfig,
+    #[default(300.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Debug)]
+struct Price {
+    coin: String,
+    price: f64,
+    change: Option<f64>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::WheelUp, None, "next"),
+        (MouseButton::WheelDown, None, "prev"),
+    ])?;
+
+    let format = config
+        .format
+        .with_default(" $icon $coin $price.eng(w:5){ $change.eng(w:3)|} ")?;
+
+    if config.coins.is_empty() {
+        return Err(Error::new("`coins` is empty"));
+    }
+    let currency = config.currency.to_lowercase();
+    let interval = config.interval.0.max(MIN_INTERVAL);
+
+    let mut prices = Vec::new();
+    let mut index = 0;
+
+    loop {
+        let fetch = || get_prices(config, &currency);
+        let (wait, min_wait) = match fetch.retry(&ExponentialBuilder::default()).await? {
+            Fetched::Prices(new_prices) => {
+                prices = new_prices;
+                (interval, MIN_INTERVAL)
+            }
+            Fetched::RateLimited(retry_after) => {
+                let wait = retry_after.unwrap_or(interval).max(MIN_INTERVAL);
+                (wait, wait)
+            }
+        };
+        let now = tokio::time::Instant::now();
+        let mut deadline = now + wait;
+        // Manual refreshes only bring the next request forward as far as the rate limit allows
+        let earliest = now + min_wait;
+
+        loop {
+            let mut widget = Widget::new().with_format(format.clone());
+            if let Some(price) = prices.get(index % prices.len().max(1)) {
+                widget.state = match price.change {
+                    Some(change) if change > 0.0 => State::Good,
+                    Some(change) if change < 0.0 => State::Warning,
+                    _ => State::Idle,
+                };
+                widget.set_values(map! {
+                    "icon" => Value::icon("crypto"),
+                    "coin" => Value::text(price.coin.clone()),
+                    "currency" => Value::text(currency.to_uppercase()),
+                    "price" => Value::number(price.price),
+                    [if let Some(change) = price.change] "change" => Value::percents(change),
+                });
+            } else {
+                // Only happens if the very first request was rate limited
+                return Err(Error::new(format!(
+                    "No price for '{}'",
+                    config.coins[index % config.coins.len()]
+                )));
+            }
+            api.set_widget(widget)?;
+
+            select! {
+                _ = tokio::time::sleep_until(deadline) => break,
+                _ = api.wait_for_update_request() => deadline = deadline.min(earliest),
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "next" => index = (index + 1) % prices.len().max(1),
+                    "prev" => index = (index + prices.len().max(1) - 1) % prices.len().max(1),
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+enum Fetched {
+    Prices(Vec<Price>),
+    /// Contains the value of the `Retry-After` header, if any
+    RateLimited(Option<Duration>),
+}
+
+async fn get_prices(config: &Config, currency: &str) -> Result<Fetched> {
+    // https://docs.coingecko.com/reference/simple-price
+    let mut request = REQWEST_CLIENT
+        .get("https://api.coingecko.com/api/v3/simple/price")
+        .query(&[
+            ("ids", config.coins.join(",").as_str()),
+            ("vs_currencies", currency),
+            ("include_24hr_change", "true"),
+        ]);
+    if let Some(api_key) = &config.api_key {
+        request = request.header("x-cg-demo-api-key", api_key);
+    }
+    let response = request.send().await.error("Failed to send request")?;
+
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs);
+        return Ok(Fetched::RateLimited(retry_after));
+    }
+
+    let mut response: HashMap<String, HashMap<String, Option<f64>>> = response
+        .error_for_status()
+        .error("Failed to get prices")?
+        .json()
+        .await
+        .error("Failed to parse JSON")?;
+
+    let change_key = format!("{currency}_24h_change");
+    let mut prices = Vec::with_capacity(config.coins.len());
+    for coin in &config.coins {
+        let mut values = response
+            .remove(coin)
+            .or_error(|| format!("Unknown coin '{coin}'"))?;
+        let price = values
+            .remove(currency)
+            .flatten()
+            .or_error(|| format!("No price of '{coin}' in '{currency}'"))?;
+        prices.push(Price {
+            coin: coin.clone(),
+            price,
+            change: values.remove(&change_key).flatten(),
+        });
+    }
+    Ok(Fetched::Prices(prices))
+}
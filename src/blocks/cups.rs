@@ -0,0 +1,149 @@
+//! CUPS print queue
+//!
+//! This block shows the number of queued print jobs and the printers that are in an error state,
+//! as reported by `lpstat`. It is hidden when the queue is empty and all printers are fine.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $jobs{ $printer\|} \"</code>
+//! `url` | The page of the CUPS web interface opened by the `open` action | `"http://localhost:631/jobs/"`
+//! `interval` | Update interval in seconds | `10`
+//!
+//! Placeholder | Value                                                   | Type   | Unit
+//! ------------|---------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                           | Icon   | -
+//! `jobs`      | The number of queued jobs                               | Number | -
+//! `errors`    | The number of disabled printers                         | Number | -
+//! `printer`   | The name of the first disabled printer, if any          | Text   | -
+//!
+//! The block is set to the critical state if a printer is disabled, e.g. because it ran out of
+//! paper, and to the info state if there are queued jobs.
+//!
+//! Action       | Description                              | Default button
+//! -------------|------------------------------------------|---------------
+//! `open`       | Open `url` using `xdg-open`              | Left
+//! `cancel_all` | Cancel all queued jobs using `cancel -a` | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "cups"
+//! [[block.click]]
+//! button = "right"
+//! action = "cancel_all"
+//! ```
+//!
+//! # Icons Used
+//! - `printer`
+
+use tokio::process::Command;
+
+use super::prelude::*;
+use crate::subprocess::spawn_process;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default("http://localhost:631/jobs/".into())]
+    pub url: String,
+    #[default(10.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "open")])?;
+
+    let format = config.format.with_default(" $icon $jobs{ $printer|} ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let jobs = lpstat("-o")
+            .await?
+            .lines()
+            .filter(|l| !l.is_empty())
+            .count();
+        let disabled = disabled_printers(&lpstat("-p").await?);
+
+        if jobs == 0 && disabled.is_empty() {
+            api.hide()?;
+        } else {
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = if !disabled.is_empty() {
+                State::Critical
+            } else {
+                State::Info
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("printer"),
+                "jobs" => Value::number(jobs),
+                "errors" => Value::number(disabled.len()),
+                [if let Some(printer) = disabled.first()] "printer" => Value::text(printer.clone()),
+            });
+            api.set_widget(widget)?;
+        }
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "open" => {
+                        spawn_process("xdg-open", &[&config.url]).error("Failed to run xdg-open")?;
+                    }
+                    "cancel_all" => {
+                        Command::new("cancel")
+                            .arg("-a")
+                            .status()
+                            .await
+                            .error("Failed to run cancel")?;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+async fn lpstat(arg: &str) -> Result<String> {
+    let output = Command::new("lpstat")
+        .arg(arg)
+        .env("LC_ALL", "C")
+        .output()
+        .await
+        .error("Failed to run lpstat")?;
+    // lpstat fails if there are no printers at all, which is not an error for this block
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the names of the disabled printers in the output of `lpstat -p`
+fn disabled_printers(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, status) = line.strip_prefix("printer ")?.split_once(' ')?;
+            status.starts_with("disabled").then(|| name.to_owned())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_printers() {
+        let output = "\
+printer HP_LaserJet is idle.  enabled since Mon 01 Jan 2024 10:00:00 AM CET
+printer Brother_HL disabled since Mon 01 Jan 2024 10:05:00 AM CET -
+\tMedia empty or jammed
+printer Canon now printing Canon-12.  enabled since Mon 01 Jan 2024 10:06:00 AM CET
+";
+        assert_eq!(disabled_printers(output), ["Brother_HL"]);
+    }
+}
@@ -15,6 +15,7 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\"{ $icon\|} $text.pango-str() \"</code>
 //! `command` | Shell command to execute & display | `None`
 //! `persistent` | Run command in the background; update display for each output line of the command | `false`
+//! `actions_to_stdin` | In `persistent` mode, write the names of actions to the standard input of the command | `false`
 //! `cycle` | Commands to execute and change when the button is clicked | `None`
 //! `interval` | Update interval in seconds (or "once" to update only once) | `10`
 //! `json` | Use JSON from command output to format the block. If the JSON is not valid, the block will error out. | `false`
@@ -32,6 +33,12 @@
 //! --------|---------------
 //! `cycle` | Left
 //!
+//! Clicks can be passed through to the command by binding a button to any other action name with
+//! `[[block.click]]`. Unless `persistent` is set, the command is then run again with the
+//! `BLOCK_ACTION` environment variable set to the name of the action. In `persistent` mode with
+//! `actions_to_stdin` set, the name of every action is written to the standard input of the
+//! command, one per line.
+//!
 //! # Examples
 //!
 //! Display temperature, update every 10 seconds:
@@ -82,6 +89,21 @@
 //! interval = "once"
 //! ```
 //!
+//! Pass clicks through to the script, which can read the clicked button from `$BLOCK_ACTION`:
+//!
+//! ```toml
+//! [[block]]
+//! block = "custom"
+//! command = "~/bin/status.sh"
+//! interval = 30
+//! [[block.click]]
+//! button = "right"
+//! action = "right"
+//! [[block.click]]
+//! button = "up"
+//! action = "up"
+//! ```
+//!
 //! Update block when one or more specified files are modified:
 //!
 //! ```toml
@@ -109,6 +131,7 @@ pub struct Config {
     pub format: FormatConfig,
     pub command: Option<String>,
     pub persistent: bool,
+    pub actions_to_stdin: bool,
     pub cycle: Option<Vec<String>>,
     #[default(10.into())]
     pub interval: Seconds,
@@ -200,7 +223,11 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
                     .error("'command' must be specified when 'persistent' is set")?,
             ])
             .stdout(Stdio::piped())
-            .stdin(Stdio::null())
+            .stdin(if config.actions_to_stdin {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
             .kill_on_drop(true)
             .spawn()
             .error("failed to run command")?;
@@ -211,6 +238,22 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             .expect("child did not have a handle to stdout");
         let mut reader = BufReader::new(stdout).lines();
 
+        if let Some(mut stdin) = process.stdin.take() {
+            let mut actions = api.get_actions()?;
+            // A command which doesn't read its stdin must not stop the block from updating
+            tokio::spawn(async move {
+                while let Some(action) = actions.recv().await {
+                    if stdin
+                        .write_all(format!("{action}\n").as_bytes())
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+
         tokio::spawn(async move {
             let _ = process.wait().await;
         });
@@ -241,15 +284,16 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             .into_iter()
             .cycle();
         let mut cmd = cycle.next().unwrap();
+        let mut block_action: Option<BlockAction> = None;
 
         loop {
             // Run command
-            let output = Command::new(&shell)
-                .args(["-c", &cmd])
-                .stdin(Stdio::null())
-                .output()
-                .await
-                .error("failed to run command")?;
+            let mut command = Command::new(&shell);
+            command.args(["-c", &cmd]).stdin(Stdio::null());
+            if let Some(action) = block_action.take() {
+                command.env("BLOCK_ACTION", &*action);
+            }
+            let output = command.output().await.error("failed to run command")?;
             let stdout = std::str::from_utf8(&output.stdout)
                 .error("the output of command is invalid UTF-8")?
                 .trim();
@@ -263,18 +307,13 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             )
             .await?;
 
-            loop {
-                select! {
-                    _ = timer.tick() => break,
-                    _ = file_updates.next() => break,
-                    _ = api.wait_for_update_request() => break,
-                    Some(action) = actions.recv() => match action.as_ref() {
-                        "cycle" => {
-                            cmd = cycle.next().unwrap();
-                            break;
-                        }
-                        _ => (),
-                    }
+            select! {
+                _ = timer.tick() => (),
+                _ = file_updates.next() => (),
+                _ = api.wait_for_update_request() => (),
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "cycle" => cmd = cycle.next().unwrap(),
+                    _ => block_action = Some(action),
                 }
             }
         }
@@ -0,0 +1,203 @@
+//! Display a D-Bus property or signal
+//!
+//! This block watches an arbitrary D-Bus property or signal and renders its value. Properties are
+//! read once on startup and then updated whenever the object emits `PropertiesChanged`. Signals
+//! are displayed as soon as they are received; the block is hidden until the first emission.
+//!
+//! Exactly one of `property` and `signal` must be set.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $value "`
+//! `bus` | Either `"session"` or `"system"` | `"session"`
+//! `service` | The bus name of the object's owner, e.g. `"org.freedesktop.UPower"` | **Required**
+//! `path` | The object path | **Required**
+//! `interface` | The interface containing the property or signal | **Required**
+//! `property` | The name of the property to watch | `None`
+//! `signal` | The name of the signal to watch | `None`
+//!
+//! Placeholder | Value                                                  | Type           | Unit
+//! ------------|--------------------------------------------------------|----------------|-----
+//! `value`     | The value of the property or the first signal argument | Number or Text | -
+//! `argN`      | The N-th argument of the signal, starting from `arg0`  | Number or Text | -
+//!
+//! Numeric D-Bus values are exposed as numbers, everything else is displayed as text.
+//!
+//! # Examples
+//!
+//! Display whether the laptop lid is closed:
+//!
+//! ```toml
+//! [[block]]
+//! block = "dbus_watch"
+//! bus = "system"
+//! service = "org.freedesktop.UPower"
+//! path = "/org/freedesktop/UPower"
+//! interface = "org.freedesktop.UPower"
+//! property = "LidIsClosed"
+//! format = " Lid closed: $value "
+//! ```
+//!
+//! Display the name of the last started systemd unit:
+//!
+//! ```toml
+//! [[block]]
+//! block = "dbus_watch"
+//! bus = "system"
+//! service = "org.freedesktop.systemd1"
+//! path = "/org/freedesktop/systemd1"
+//! interface = "org.freedesktop.systemd1.Manager"
+//! signal = "JobNew"
+//! format = " $arg2 "
+//! ```
+
+use super::prelude::*;
+use zbus::message::Body;
+use zbus::zvariant::{self, OwnedValue, Structure};
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default)]
+    pub bus: BusType,
+    pub service: String,
+    pub path: String,
+    pub interface: String,
+    pub property: Option<String>,
+    pub signal: Option<String>,
+}
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(rename_all = "lowercase")]
+pub enum BusType {
+    #[default]
+    Session,
+    System,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $value ")?;
+
+    let dbus_conn = match config.bus {
+        BusType::Session => new_dbus_connection().await?,
+        BusType::System => new_system_dbus_connection().await?,
+    };
+    let proxy = zbus::Proxy::new(
+        &dbus_conn,
+        config.service.as_str(),
+        config.path.as_str(),
+        config.interface.as_str(),
+    )
+    .await
+    .error("Failed to create proxy")?;
+
+    match (&config.property, &config.signal) {
+        (Some(property), None) => {
+            let mut changes = proxy.receive_property_changed::<OwnedValue>(property).await;
+            loop {
+                let value: OwnedValue = proxy
+                    .get_property(property)
+                    .await
+                    .error("Failed to get property")?;
+                let mut widget = Widget::new().with_format(format.clone());
+                widget.set_values(map! {
+                    "value" => to_value(&value),
+                });
+                api.set_widget(widget)?;
+
+                if changes.next().await.is_none() {
+                    return Err(Error::new("Property stream ended unexpectedly"));
+                }
+            }
+        }
+        (None, Some(signal)) => {
+            let mut signals = proxy
+                .receive_signal(signal.as_str())
+                .await
+                .error("Failed to subscribe to signal")?;
+            api.hide()?;
+            while let Some(message) = signals.next().await {
+                let body = message.body();
+                let args = signal_args(&body)?;
+                let mut values: HashMap<_, _> = args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, arg)| (format!("arg{i}").into(), to_value(arg)))
+                    .collect();
+                if let Some(first) = args.first() {
+                    values.insert("value".into(), to_value(first));
+                }
+                let mut widget = Widget::new().with_format(format.clone());
+                widget.set_values(values);
+                api.set_widget(widget)?;
+            }
+            Err(Error::new("Signal stream ended unexpectedly"))
+        }
+        _ => Err(Error::new(
+            "Exactly one of 'property' and 'signal' must be set",
+        )),
+    }
+}
+
+/// Returns the arguments of a signal. The body is parsed as a structure, which zbus only does on its
+/// own if there are at least two arguments.
+fn signal_args(body: &Body) -> Result<Vec<zvariant::Value<'_>>> {
+    let signature = match body.signature() {
+        Some(signature) if !signature.is_empty() => signature,
+        _ => return Ok(Vec::new()),
+    };
+    let (args, _): (Structure, _) = body
+        .data()
+        .deserialize_for_dynamic_signature(format!("({signature})"))
+        .error("Failed to parse signal")?;
+    Ok(args.into_fields())
+}
+
+fn to_value(value: &zvariant::Value) -> Value {
+    use zvariant::Value as V;
+    match value {
+        V::U8(x) => Value::number(*x),
+        V::I16(x) => Value::number(*x),
+        V::U16(x) => Value::number(*x),
+        V::I32(x) => Value::number(*x),
+        V::U32(x) => Value::number(*x),
+        V::I64(x) => Value::number(*x),
+        V::U64(x) => Value::number(*x),
+        V::F64(x) => Value::number(*x),
+        V::Str(x) => Value::text(x.to_string()),
+        V::Value(x) => to_value(x),
+        other => Value::text(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args_of<B>(body: &B) -> Vec<String>
+    where
+        B: serde::Serialize + zvariant::DynamicType,
+    {
+        let message = zbus::Message::signal("/org/example", "org.example.Iface", "Changed")
+            .unwrap()
+            .build(body)
+            .unwrap();
+        signal_args(&message.body())
+            .unwrap()
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_signal_args() {
+        assert!(args_of(&()).is_empty());
+        assert_eq!(args_of(&("only",)), ["\"only\""]);
+        assert_eq!(args_of(&(1i32, "two")), ["1", "\"two\""]);
+        assert_eq!(args_of(&((1i32, 2i32),)), ["(1, 2)"]);
+    }
+}
@@ -0,0 +1,203 @@
+//! The brightness of an external monitor via DDC/CI
+//!
+//! This block uses [`ddcutil`](https://www.ddcutil.com/) to show and adjust the brightness of an
+//! external monitor. Unlike the `backlight` block, it doesn't need the `ddcci` kernel module, but
+//! your user needs access to the `/dev/i2c-*` devices (usually by being in the `i2c` group).
+//!
+//! Talking to a monitor over I2C is slow, so the brightness is read once at start and then only
+//! every `interval` seconds. Changes made by scrolling are shown right away and written to the monitor
+//! once scrolling stops. Use one instance of this block per monitor.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `display` | The display number, as listed by `ddcutil detect` | `None`
+//! `bus` | The I2C bus number of the monitor, as listed by `ddcutil detect`. Faster than `display`, since `ddcutil` doesn't need to probe all monitors. | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $brightness "`
+//! `step_width` | The brightness increment to use when scrolling, in percent | `5`
+//! `minimum` | The minimum brightness that can be scrolled down to | `0`
+//! `maximum` | The maximum brightness that can be scrolled up to | `100`
+//! `sleep_multiplier` | [See ddcutil documentation](https://www.ddcutil.com/performance_options/#option-sleep-multiplier) | `None`
+//! `interval` | How often to read the brightness from the monitor, in seconds | `60`
+//!
+//! If neither `display` nor `bus` is set, the first monitor found by `ddcutil` is used.
+//!
+//! Placeholder  | Value                                     | Type   | Unit
+//! -------------|-------------------------------------------|--------|------
+//! `icon`       | Icon based on the brightness              | Icon   | -
+//! `brightness` | Current brightness                        | Number | %
+//!
+//! Action            | Description                           | Default button
+//! ------------------|---------------------------------------|---------------
+//! `brightness_up`   | Increase the brightness               | Wheel Up
+//! `brightness_down` | Decrease the brightness               | Wheel Down
+//! `refresh`         | Read the brightness from the monitor  | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "ddc"
+//! bus = 4
+//! step_width = 10
+//!
+//! [[block]]
+//! block = "ddc"
+//! bus = 5
+//! format = " DP-2 $brightness "
+//! ```
+//!
+//! # Icons Used
+//! - `backlight` (as a progression)
+
+use tokio::process::Command;
+
+use super::prelude::*;
+
+/// The VCP feature code of the luminance
+const VCP_BRIGHTNESS: &str = "10";
+
+/// How long to wait for more scroll events before writing the brightness
+const WRITE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub display: Option<u32>,
+    pub bus: Option<u32>,
+    pub format: FormatConfig,
+    #[default(5.0)]
+    pub step_width: f64,
+    #[default(0.0)]
+    pub minimum: f64,
+    #[default(100.0)]
+    pub maximum: f64,
+    pub sleep_multiplier: Option<f64>,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "refresh"),
+        (MouseButton::WheelUp, None, "brightness_up"),
+        (MouseButton::WheelDown, None, "brightness_down"),
+    ])?;
+
+    let format = config.format.with_default(" $icon $brightness ")?;
+    let mut timer = config.interval.timer();
+
+    let (mut current, max) = get_brightness(config).await?;
+    // Whether the brightness was changed by scrolling but not written to the monitor yet
+    let mut pending_write = false;
+
+    loop {
+        let brightness = current as f64 / max as f64;
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.set_values(map! {
+            "icon" => Value::icon_progression("backlight", brightness),
+            "brightness" => Value::percents((brightness * 100.0).round()),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick(), if !pending_write => {
+                (current, _) = get_brightness(config).await?;
+            }
+            _ = api.wait_for_update_request(), if !pending_write => {
+                (current, _) = get_brightness(config).await?;
+            }
+            _ = sleep(WRITE_DELAY), if pending_write => {
+                set_brightness(config, current).await?;
+                pending_write = false;
+            }
+            Some(action) = actions.recv() => {
+                let to_raw = |percent: f64| (percent / 100.0 * max as f64).round() as i64;
+                let step = match action.as_ref() {
+                    "refresh" => {
+                        (current, _) = get_brightness(config).await?;
+                        continue;
+                    }
+                    "brightness_up" => to_raw(config.step_width),
+                    "brightness_down" => -to_raw(config.step_width),
+                    _ => continue,
+                };
+                current = (current as i64 + step)
+                    .clamp(to_raw(config.minimum), to_raw(config.maximum)) as u32;
+                pending_write = true;
+            }
+        }
+    }
+}
+
+fn ddcutil(config: &Config) -> Command {
+    let mut cmd = Command::new("ddcutil");
+    if let Some(bus) = config.bus {
+        cmd.args(["--bus", &bus.to_string()]);
+    } else if let Some(display) = config.display {
+        cmd.args(["--display", &display.to_string()]);
+    }
+    if let Some(multiplier) = config.sleep_multiplier {
+        cmd.args(["--sleep-multiplier", &multiplier.to_string()]);
+    }
+    cmd
+}
+
+/// Returns the current and the maximum brightness
+async fn get_brightness(config: &Config) -> Result<(u32, u32)> {
+    let output = ddcutil(config)
+        .args(["getvcp", VCP_BRIGHTNESS, "--brief"])
+        .output()
+        .await
+        .error("Failed to run ddcutil")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "ddcutil getvcp failed: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        )));
+    }
+    parse_getvcp(&String::from_utf8_lossy(&output.stdout))
+        .error("Failed to parse the output of ddcutil")
+}
+
+async fn set_brightness(config: &Config, value: u32) -> Result<()> {
+    let output = ddcutil(config)
+        .args(["setvcp", VCP_BRIGHTNESS, &value.to_string(), "--noverify"])
+        .output()
+        .await
+        .error("Failed to run ddcutil")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "ddcutil setvcp failed: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Parses the output of `ddcutil getvcp 10 --brief`, e.g. `VCP 10 C 70 100`, where the last two
+/// fields are the current and the maximum value of a continuous feature.
+fn parse_getvcp(output: &str) -> Option<(u32, u32)> {
+    let line = output.lines().find(|l| l.starts_with("VCP "))?;
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields.as_slice() {
+        ["VCP", _, "C", current, max] => {
+            let max = max.parse().ok().filter(|&m| m > 0)?;
+            Some((current.parse().ok()?, max))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_getvcp() {
+        assert_eq!(parse_getvcp("VCP 10 C 70 100\n"), Some((70, 100)));
+        assert_eq!(parse_getvcp("VCP 10 ERR\n"), None);
+    }
+}
@@ -0,0 +1,232 @@
+//! Connected displays
+//!
+//! This block shows the number and names of the connected outputs, read from sway's IPC or from
+//! `xrandr`. If a connected output is not in use, the display configuration was probably not
+//! applied (e.g. autorandr or kanshi has no matching profile), so the block is set to the warning
+//! state. Clicking the block runs `command`, e.g. to apply a profile.
+//!
+//! With sway, the block is updated as soon as an output changes. With xrandr, it is updated every
+//! `interval` seconds.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `driver` | `"sway"` or `"xrandr"` | `"sway"` if `$SWAYSOCK` is set, `"xrandr"` otherwise
+//! `command` | A shell command run by the `apply` action, e.g. `"autorandr --change"` or `"kanshictl reload"` | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count "`
+//! `interval` | Update interval in seconds (xrandr only) | `5`
+//!
+//! Placeholder | Value                                                          | Type   | Unit
+//! ------------|----------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                  | Icon   | -
+//! `count`     | The number of connected outputs                                | Number | -
+//! `active`    | The number of outputs in use                                   | Number | -
+//! `outputs`   | The names of the outputs in use, separated by commas           | Text   | -
+//! `unused`    | The names of the connected outputs that are not in use         | Text   | -
+//! `mismatch`  | Present if a connected output is not in use                    | Flag   | -
+//!
+//! Action  | Description   | Default button
+//! --------|---------------|---------------
+//! `apply` | Run `command` | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "displays"
+//! format = " $icon $outputs{ (unused: $unused)|} "
+//! command = "autorandr --change"
+//! ```
+//!
+//! # Icons Used
+//! - `xrandr`
+
+use futures::future::pending;
+use swayipc_async::{Connection, EventType};
+use tokio::process::Command;
+
+use super::prelude::*;
+use crate::subprocess::spawn_shell;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub driver: Option<Driver>,
+    pub command: Option<String>,
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Driver {
+    Sway,
+    Xrandr,
+}
+
+#[derive(Debug, PartialEq)]
+struct Output {
+    name: String,
+    active: bool,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "apply")])?;
+
+    let format = config.format.with_default(" $icon $count ")?;
+    let mut timer = config.interval.timer();
+
+    let driver = config.driver.unwrap_or_else(|| {
+        if std::env::var_os("SWAYSOCK").is_some() {
+            Driver::Sway
+        } else {
+            Driver::Xrandr
+        }
+    });
+    let mut events = match driver {
+        Driver::Sway => Some(
+            Connection::new()
+                .await
+                .error("Failed to open swayipc connection")?
+                .subscribe([EventType::Output])
+                .await
+                .error("Failed to subscribe to events")?,
+        ),
+        Driver::Xrandr => None,
+    };
+
+    loop {
+        let outputs = match driver {
+            Driver::Sway => sway_outputs().await?,
+            Driver::Xrandr => xrandr_outputs().await?,
+        };
+        let names = |active: bool| {
+            outputs
+                .iter()
+                .filter(|o| o.active == active)
+                .map(|o| o.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let active = names(true);
+        let unused = names(false);
+        let mismatch = !unused.is_empty();
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if mismatch {
+            widget.state = State::Warning;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon("xrandr"),
+            "count" => Value::number(outputs.len()),
+            "active" => Value::number(outputs.iter().filter(|o| o.active).count()),
+            "outputs" => Value::text(active),
+            [if mismatch] "unused" => Value::text(unused),
+            [if mismatch] "mismatch" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        let polling = events.is_none();
+        let output_event = async {
+            match &mut events {
+                Some(events) => {
+                    events.next().await;
+                }
+                None => pending().await,
+            }
+        };
+        tokio::pin!(output_event);
+
+        loop {
+            select! {
+                _ = timer.tick(), if polling => break,
+                _ = &mut output_event => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "apply" => {
+                        if let Some(cmd) = &config.command {
+                            spawn_shell(cmd).or_error(|| format!("Failed to run '{cmd}'"))?;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+async fn sway_outputs() -> Result<Vec<Output>> {
+    Ok(Connection::new()
+        .await
+        .error("Failed to open swayipc connection")?
+        .get_outputs()
+        .await
+        .error("Failed to get outputs")?
+        .into_iter()
+        .map(|o| Output {
+            name: o.name,
+            active: o.active,
+        })
+        .collect())
+}
+
+async fn xrandr_outputs() -> Result<Vec<Output>> {
+    let output = Command::new("xrandr")
+        .arg("--query")
+        .output()
+        .await
+        .error("Failed to run xrandr")?;
+    Ok(parse_xrandr(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parses the output of `xrandr --query`. Outputs in use have a geometry such as `1920x1080+0+0`.
+fn parse_xrandr(output: &str) -> Vec<Output> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let name = words.next()?;
+            if words.next()? != "connected" {
+                return None;
+            }
+            let active = words.any(|w| regex!(r"^\d+x\d+\+\d+\+\d+$").is_match(w));
+            Some(Output {
+                name: name.to_owned(),
+                active,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xrandr() {
+        let output = "\
+Screen 0: minimum 320 x 200, current 3840 x 1080, maximum 16384 x 16384
+eDP-1 connected primary 1920x1080+0+0 (normal left inverted right x axis y axis) 309mm x 174mm
+   1920x1080     60.01*+
+HDMI-1 disconnected (normal left inverted right x axis y axis)
+DP-1 connected (normal left inverted right x axis y axis)
+   2560x1440     59.95 +
+";
+        assert_eq!(
+            parse_xrandr(output),
+            [
+                Output {
+                    name: "eDP-1".into(),
+                    active: true,
+                },
+                Output {
+                    name: "DP-1".into(),
+                    active: false,
+                },
+            ]
+        );
+    }
+}
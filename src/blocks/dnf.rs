@@ -80,7 +80,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         .transpose()
         .error("invalid critical updates regex")?;
 
-    let backend = Dnf::new();
+    let backend = Dnf::new(false);
 
     loop {
         let mut widget = Widget::new();
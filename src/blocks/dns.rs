@@ -0,0 +1,176 @@
+//! DNS resolver health
+//!
+//! This block resolves a test name and shows how long it took, along with the DNS server in use.
+//! It goes critical if the name can't be resolved, which helps catching broken DNS setups, e.g.
+//! after connecting to a VPN.
+//!
+//! If `systemd-resolved` is running, the name is resolved through it, bypassing its cache, and the
+//! current DNS server is read from it. Otherwise the system resolver is used and the first
+//! `nameserver` in `/etc/resolv.conf` is shown. Note that the system resolver may cache results.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `name` | The name to resolve | `"example.com"`
+//! `timeout` | How long to wait for an answer, in seconds | `5`
+//! `warning` | Latency, in milliseconds, above which the state is set to warning | `200`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" DNS $latency \"</code>
+//! `format_failed` | Same as `format` but for when the name can't be resolved | <code>\" DNS failed{ ($resolver)\|} \"</code>
+//! `interval` | Update interval in seconds | `60`
+//!
+//! Placeholder | Value                                         | Type   | Unit
+//! ------------|-----------------------------------------------|--------|--------
+//! `latency`   | How long it took to resolve `name`            | Number | Seconds
+//! `resolver`  | The DNS server in use (absent if unknown)     | Text   | -
+//! `address`   | The first address `name` resolved to          | Text   | -
+//! `error`     | Why `name` couldn't be resolved               | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "dns"
+//! name = "intranet.example.com"
+//! format = " DNS $latency.eng(w:3,u:s,p:m) $resolver "
+//! ```
+
+use std::net::IpAddr;
+use std::time::Instant;
+
+use super::prelude::*;
+use crate::util::read_file;
+
+/// Makes systemd-resolved skip its cache
+const SD_RESOLVED_NO_CACHE: u64 = 1 << 12;
+const AF_UNSPEC: i32 = 0;
+
+/// The interface index, the address family and the raw bytes of an address
+type ResolvedAddress = (i32, i32, Vec<u8>);
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("example.com".into())]
+    pub name: String,
+    #[default(5.into())]
+    pub timeout: Seconds<false>,
+    #[default(200.0)]
+    pub warning: f64,
+    pub format: FormatConfig,
+    pub format_failed: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" DNS $latency ")?;
+    let format_failed = config
+        .format_failed
+        .with_default(" DNS failed{ ($resolver)|} ")?;
+    let mut timer = config.interval.timer();
+
+    let dbus_conn = new_system_dbus_connection().await?;
+    let resolved = ResolvedProxy::builder(&dbus_conn)
+        .cache_properties(zbus::CacheProperties::No)
+        .build()
+        .await
+        .error("Failed to create ResolvedProxy")?;
+
+    loop {
+        // Fails if systemd-resolved is not running
+        let current_server = resolved.current_dns_server().await;
+        let use_resolved = current_server.is_ok();
+        let resolver = match current_server {
+            Ok((_, _, address)) => ip_from_bytes(&address),
+            Err(_) => nameserver_from_resolv_conf().await,
+        };
+
+        let start = Instant::now();
+        let address = tokio::time::timeout(config.timeout.0, async {
+            if use_resolved {
+                let (addresses, _, _) = resolved
+                    .resolve_hostname(0, &config.name, AF_UNSPEC, SD_RESOLVED_NO_CACHE)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                addresses
+                    .first()
+                    .and_then(|address| ip_from_bytes(&address.2))
+                    .ok_or_else(|| "no address".to_string())
+            } else {
+                tokio::net::lookup_host((config.name.as_str(), 0))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .next()
+                    .map(|address| address.ip())
+                    .ok_or_else(|| "no address".to_string())
+            }
+        })
+        .await
+        .unwrap_or_else(|_| Err("timed out".to_string()));
+        let latency = start.elapsed();
+
+        let mut widget = Widget::new();
+        match address {
+            Ok(address) => {
+                widget.set_format(format.clone());
+                if latency.as_secs_f64() * 1e3 > config.warning {
+                    widget.state = State::Warning;
+                }
+                widget.set_values(map! {
+                    "latency" => Value::seconds(latency.as_secs_f64()),
+                    "address" => Value::text(address.to_string()),
+                    [if let Some(r) = resolver] "resolver" => Value::text(r.to_string()),
+                });
+            }
+            Err(error) => {
+                widget.set_format(format_failed.clone());
+                widget.state = State::Critical;
+                widget.set_values(map! {
+                    "error" => Value::text(error),
+                    [if let Some(r) = resolver] "resolver" => Value::text(r.to_string()),
+                });
+            }
+        }
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+fn ip_from_bytes(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => Some(IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => Some(IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => None,
+    }
+}
+
+async fn nameserver_from_resolv_conf() -> Option<IpAddr> {
+    read_file("/etc/resolv.conf")
+        .await
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("nameserver")?.trim().parse().ok())
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.resolve1.Manager",
+    default_service = "org.freedesktop.resolve1",
+    default_path = "/org/freedesktop/resolve1"
+)]
+trait Resolved {
+    fn resolve_hostname(
+        &self,
+        ifindex: i32,
+        name: &str,
+        family: i32,
+        flags: u64,
+    ) -> zbus::Result<(Vec<ResolvedAddress>, String, u64)>;
+
+    #[zbus(property, name = "CurrentDNSServer")]
+    fn current_dns_server(&self) -> zbus::Result<ResolvedAddress>;
+}
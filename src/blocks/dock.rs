@@ -0,0 +1,205 @@
+//! Lid and dock state
+//!
+//! This block shows whether the lid of a laptop is open and whether the laptop is docked. It is
+//! mainly meant as a reminder: if the laptop is docked and an external display is connected but
+//! not in use, the display profile was probably not applied, so the block is set to the warning
+//! state and `command` is run.
+//!
+//! The laptop is considered docked if an ACPI dock station reports so, or if a USB device listed
+//! in `usb_ids` (such as the hub of a USB-C dock) is connected. External displays are read from
+//! the DRM connectors in sysfs, so this works regardless of the window manager. See the
+//! [`displays`](super::displays) block for a block based on the outputs of sway or xrandr.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `usb_ids` | USB devices which indicate that the laptop is docked, as `"vendor:product"` ids shown by `lsusb` | `[]`
+//! `command` | A shell command to apply the display profile, run when the reminder is shown and by the `apply` action | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon{ $lid\|}{ $pending displays not set up\|} \"</code>
+//! `interval` | Update interval in seconds | `5`
+//!
+//! Placeholder | Value                                                               | Type   | Unit
+//! ------------|---------------------------------------------------------------------|--------|-----
+//! `icon`      | `dock` while docked, `laptop` otherwise                             | Icon   | -
+//! `lid`       | `open` or `closed` (absent if there is no lid)                      | Text   | -
+//! `docked`    | Present while docked                                                | Flag   | -
+//! `displays`  | The number of connected external displays                           | Number | -
+//! `pending`   | Present while docked and a connected external display is not in use | Flag   | -
+//!
+//! Action  | Description   | Default button
+//! --------|---------------|---------------
+//! `apply` | Run `command` | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "dock"
+//! usb_ids = ["17ef:30b4"]
+//! command = "autorandr --change"
+//! ```
+//!
+//! # Icons Used
+//! - `laptop`
+//! - `dock`
+
+use std::path::Path;
+
+use super::prelude::*;
+use crate::subprocess::spawn_shell;
+use crate::util::read_file;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub usb_ids: Vec<String>,
+    pub command: Option<String>,
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "apply")])?;
+
+    let format = config
+        .format
+        .with_default(" $icon{ $lid|}{ $pending displays not set up|} ")?;
+    let mut timer = config.interval.timer();
+
+    let mut was_pending = false;
+
+    loop {
+        let lid = lid_state().await;
+        let docked = acpi_docked().await || usb_docked(&config.usb_ids).await;
+        let (displays, unused) = external_displays().await;
+        let pending = docked && unused > 0;
+
+        // Only run the command once, when the reminder appears
+        if pending && !was_pending {
+            run_command(config)?;
+        }
+        was_pending = pending;
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if pending {
+            widget.state = State::Warning;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon(if docked { "dock" } else { "laptop" }),
+            [if let Some(lid) = lid] "lid" => Value::text(lid),
+            [if docked] "docked" => Value::flag(),
+            "displays" => Value::number(displays),
+            [if pending] "pending" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "apply" => run_command(config)?,
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+fn run_command(config: &Config) -> Result<()> {
+    if let Some(cmd) = &config.command {
+        spawn_shell(cmd).or_error(|| format!("Failed to run '{cmd}'"))?;
+    }
+    Ok(())
+}
+
+/// Reads the state of the first lid switch, e.g. from `/proc/acpi/button/lid/LID0/state`
+async fn lid_state() -> Option<String> {
+    let mut dir = tokio::fs::read_dir("/proc/acpi/button/lid").await.ok()?;
+    let entry = dir.next_entry().await.ok()??;
+    let state = read_file(entry.path().join("state")).await.ok()?;
+    // The file looks like "state:      open"
+    state.split_whitespace().last().map(Into::into)
+}
+
+/// Whether any ACPI dock station reports that the laptop is docked
+async fn acpi_docked() -> bool {
+    let Ok(mut dir) = tokio::fs::read_dir("/sys/devices/platform").await else {
+        return false;
+    };
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        if entry.file_name().to_string_lossy().starts_with("dock.")
+            && read_file(entry.path().join("docked"))
+                .await
+                .is_ok_and(|docked| docked == "1")
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether any USB device with one of the given `vendor:product` ids is connected
+async fn usb_docked(usb_ids: &[String]) -> bool {
+    if usb_ids.is_empty() {
+        return false;
+    }
+    let Ok(mut dir) = tokio::fs::read_dir("/sys/bus/usb/devices").await else {
+        return false;
+    };
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        let path = entry.path();
+        let (Ok(vendor), Ok(product)) = (
+            read_file(path.join("idVendor")).await,
+            read_file(path.join("idProduct")).await,
+        ) else {
+            continue;
+        };
+        let id = format!("{vendor}:{product}");
+        if usb_ids.iter().any(|i| i.eq_ignore_ascii_case(&id)) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns the number of connected external displays, and how many of them are not in use
+async fn external_displays() -> (usize, usize) {
+    let (mut connected, mut unused) = (0, 0);
+    let Ok(mut dir) = tokio::fs::read_dir("/sys/class/drm").await else {
+        return (connected, unused);
+    };
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        // Connectors are named e.g. "card1-HDMI-A-1"
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some((_card, connector)) = name.split_once('-') else {
+            continue;
+        };
+        if is_internal(connector) || !is_connected(&entry.path()).await {
+            continue;
+        }
+        connected += 1;
+        if read_file(entry.path().join("enabled"))
+            .await
+            .is_ok_and(|enabled| enabled != "enabled")
+        {
+            unused += 1;
+        }
+    }
+    (connected, unused)
+}
+
+async fn is_connected(connector: &Path) -> bool {
+    read_file(connector.join("status"))
+        .await
+        .is_ok_and(|status| status == "connected")
+}
+
+fn is_internal(connector: &str) -> bool {
+    ["eDP", "LVDS", "DSI"]
+        .iter()
+        .any(|prefix| connector.starts_with(prefix))
+}
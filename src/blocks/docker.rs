@@ -1,5 +1,8 @@
 //! Local docker daemon status
 //!
+//! Resource usage of the running containers (`cpu` and `memory` placeholders) is only queried if
+//! it is used in `format`, because it requires an additional request per container.
+//!
 //! # Configuration
 //!
 //! Key | Values | Default
@@ -8,16 +11,18 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $running.eng(w:1) "`
 //! `socket_path` | The path to the docker socket. Supports path expansions e.g. `~`. | `"/var/run/docker.sock"`
 //!
-//! Key       | Value                          | Type   | Unit
-//! ----------|--------------------------------|--------|-----
-//! `icon`    | A static icon                  | Icon   | -
-//! `total`   | Total containers on the host   | Number | -
-//! `running` | Containers running on the host | Number | -
-//! `stopped` | Containers stopped on the host | Number | -
-//! `paused`  | Containers paused on the host  | Number | -
-//! `images`  | Total images on the host       | Number | -
+//! Key       | Value                                              | Type   | Unit
+//! ----------|----------------------------------------------------|--------|-----
+//! `icon`    | A static icon                                      | Icon   | -
+//! `total`   | Total containers on the host                       | Number | -
+//! `running` | Containers running on the host                     | Number | -
+//! `stopped` | Containers stopped on the host                     | Number | -
+//! `paused`  | Containers paused on the host                      | Number | -
+//! `images`  | Total images on the host                           | Number | -
+//! `cpu`     | Total CPU usage of the running containers          | Number | %
+//! `memory`  | Total memory usage of the running containers       | Number | Bytes
 //!
-//! # Example
+//! # Examples
 //!
 //! ```toml
 //! [[block]]
@@ -26,13 +31,25 @@
 //! format = " $icon $running/$total "
 //! ```
 //!
+//! Show resource usage and open `lazydocker` on click:
+//!
+//! ```toml
+//! [[block]]
+//! block = "docker"
+//! interval = 10
+//! format = " $icon $running $cpu $memory "
+//! [[block.click]]
+//! button = "left"
+//! cmd = "alacritty -e lazydocker"
+//! ```
+//!
 //! # Icons Used
 //!
 //! - `docker`
 
 use super::prelude::*;
+use crate::util::get_over_unix_socket;
 use std::path::Path;
-use tokio::net::UnixStream;
 
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
@@ -47,9 +64,15 @@ pub struct Config {
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let format = config.format.with_default(" $icon $running.eng(w:1) ")?;
     let socket_path = config.socket_path.expand()?;
+    let need_usage = format.contains_key("cpu") || format.contains_key("memory");
 
     loop {
         let status = Status::new(&*socket_path).await?;
+        let usage = if need_usage {
+            Some(Usage::new(&*socket_path).await?)
+        } else {
+            None
+        };
 
         let mut widget = Widget::new().with_format(format.clone());
         widget.set_values(map! {
@@ -59,6 +82,8 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             "paused" =>  Value::number(status.paused),
             "stopped" => Value::number(status.stopped),
             "images" =>  Value::number(status.images),
+            [if let Some(usage) = &usage] "cpu" => Value::percents(usage.cpu),
+            [if let Some(usage) = &usage] "memory" => Value::bytes(usage.memory),
         });
         api.set_widget(widget)?;
 
@@ -85,26 +110,91 @@ struct Status {
 
 impl Status {
     async fn new(socket_path: impl AsRef<Path>) -> Result<Self> {
-        let socket = UnixStream::connect(socket_path)
-            .await
-            .error("Failed to connect to socket")?;
-        let (mut request_sender, connection) = hyper::client::conn::handshake(socket)
-            .await
-            .error("Failed to create request sender")?;
-        tokio::spawn(connection);
-        let request = hyper::Request::builder()
-            .header("Host", "localhost")
-            .uri("http://api/info")
-            .method("GET")
-            .body(hyper::Body::empty())
-            .error("Failed to create request")?;
-        let response = request_sender
-            .send_request(request)
-            .await
-            .error("Failed to get response")?;
-        let bytes = hyper::body::to_bytes(response.into_body())
-            .await
-            .error("Failed to get response bytes")?;
-        serde_json::from_slice::<Self>(&bytes).error("Failed to deserialize JSON")
+        get_over_unix_socket(socket_path, "/info").await
+    }
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    cpu: f64,
+    memory: f64,
+}
+
+impl Usage {
+    async fn new(socket_path: impl AsRef<Path>) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Container {
+            #[serde(rename = "Id")]
+            id: String,
+        }
+
+        #[derive(Deserialize)]
+        struct Stats {
+            cpu_stats: CpuStats,
+            precpu_stats: CpuStats,
+            memory_stats: MemoryStats,
+        }
+
+        #[derive(Deserialize)]
+        struct CpuStats {
+            cpu_usage: CpuUsage,
+            system_cpu_usage: Option<u64>,
+            online_cpus: Option<u64>,
+        }
+
+        #[derive(Deserialize)]
+        struct CpuUsage {
+            total_usage: u64,
+        }
+
+        #[derive(Deserialize)]
+        struct MemoryStats {
+            usage: Option<u64>,
+            #[serde(default)]
+            stats: HashMap<String, u64>,
+        }
+
+        let socket_path = socket_path.as_ref();
+        let containers: Vec<Container> =
+            get_over_unix_socket(socket_path, "/containers/json").await?;
+
+        let mut usage = Self::default();
+        for container in containers {
+            let stats: Stats = get_over_unix_socket(
+                socket_path,
+                &format!("/containers/{}/stats?stream=false", container.id),
+            )
+            .await?;
+
+            let cpu_delta = stats
+                .cpu_stats
+                .cpu_usage
+                .total_usage
+                .saturating_sub(stats.precpu_stats.cpu_usage.total_usage);
+            let system_delta = stats
+                .cpu_stats
+                .system_cpu_usage
+                .unwrap_or_default()
+                .saturating_sub(stats.precpu_stats.system_cpu_usage.unwrap_or_default());
+            if system_delta > 0 {
+                let cpus = stats.cpu_stats.online_cpus.unwrap_or(1);
+                usage.cpu += cpu_delta as f64 / system_delta as f64 * cpus as f64 * 100.0;
+            }
+
+            // Same as `docker stats`: page cache is not counted as used memory
+            let cache = stats
+                .memory_stats
+                .stats
+                .get("inactive_file")
+                .or_else(|| stats.memory_stats.stats.get("total_inactive_file"))
+                .copied()
+                .unwrap_or_default();
+            usage.memory += stats
+                .memory_stats
+                .usage
+                .unwrap_or_default()
+                .saturating_sub(cache) as f64;
+        }
+        Ok(usage)
     }
 }
@@ -0,0 +1,136 @@
+//! Dropbox sync status
+//!
+//! This block shows the status of the Dropbox daemon, such as `Up to date` or `Syncing 12 files`,
+//! which is the same as the output of `dropbox status`. It is hidden while Dropbox isn't running.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `socket_path` | The path to the command socket of the daemon. Supports path expansions e.g. `~`. | `"~/.dropbox/command_socket"`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $status "`
+//! `interval` | Update interval in seconds | `5`
+//!
+//! Placeholder | Value                                                     | Type   | Unit
+//! ------------|-----------------------------------------------------------|--------|-----
+//! `icon`      | `sync` while syncing, `cloud` otherwise                   | Icon   | -
+//! `status`    | The status reported by the daemon                         | Text   | -
+//! `syncing`   | Present while syncing                                     | Flag   | -
+//! `paused`    | Present while syncing is paused                           | Flag   | -
+//! `remaining` | The number of files left to sync (absent if not reported) | Number | -
+//!
+//! The block is set to the info state while syncing, and to the warning state while paused.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "dropbox"
+//! format = " $icon{$syncing $remaining|}{$paused paused|} "
+//! ```
+//!
+//! # Icons Used
+//! - `cloud`
+//! - `sync`
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("~/.dropbox/command_socket".into())]
+    pub socket_path: ShellString,
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $status ")?;
+    let mut timer = config.interval.timer();
+    let socket_path = config.socket_path.expand()?;
+
+    loop {
+        match get_status(&socket_path).await? {
+            None => api.hide()?,
+            Some(status) => {
+                let paused = status.to_lowercase().contains("paused");
+                let syncing = !paused && status != "Up to date";
+                let remaining = remaining_files(&status);
+
+                let mut widget = Widget::new().with_format(format.clone());
+                widget.state = if paused {
+                    State::Warning
+                } else if syncing {
+                    State::Info
+                } else {
+                    State::Idle
+                };
+                widget.set_values(map! {
+                    "icon" => Value::icon(if syncing { "sync" } else { "cloud" }),
+                    "status" => Value::text(status),
+                    [if syncing] "syncing" => Value::flag(),
+                    [if paused] "paused" => Value::flag(),
+                    [if let Some(r) = remaining] "remaining" => Value::number(r),
+                });
+                api.set_widget(widget)?;
+            }
+        }
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Returns the status of the daemon, or `None` if it isn't running
+async fn get_status(socket_path: &str) -> Result<Option<String>> {
+    let Ok(stream) = UnixStream::connect(socket_path).await else {
+        return Ok(None);
+    };
+    let (reader, mut writer) = stream.into_split();
+    writer
+        .write_all(b"get_dropbox_status\ndone\n")
+        .await
+        .error("Failed to write to socket")?;
+
+    // The response looks like "ok\nstatus\tSyncing 3 files\tDownloading...\ndone\n"
+    let mut lines = BufReader::new(reader).lines();
+    let mut status = None;
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .error("Failed to read from socket")?
+    {
+        match line.split_once('\t') {
+            Some(("status", values)) => {
+                status = values.split('\t').next().map(str::to_owned);
+            }
+            _ if line == "done" => break,
+            _ => (),
+        }
+    }
+    status.error("Dropbox did not report its status").map(Some)
+}
+
+/// Parses the number of remaining files from a status such as `Syncing 1,234 files • 5 mins`
+fn remaining_files(status: &str) -> Option<u64> {
+    let captures = regex!(r"([\d,]+) files?\b").captures(status)?;
+    captures[1].replace(',', "").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_files() {
+        assert_eq!(remaining_files("Syncing 1,234 files • 5 mins"), Some(1234));
+        assert_eq!(remaining_files("Uploading 1 file..."), Some(1));
+        assert_eq!(remaining_files("Up to date"), None);
+    }
+}
@@ -0,0 +1,174 @@
+//! Electricity spot price
+//!
+//! This block shows the current day-ahead spot price of electricity, colored by price band, so
+//! you know when it's a good time to charge the car or run the dryer. Prices are shown in cents per
+//! kWh and don't include grid fees or taxes. Besides every `interval`, the block updates whenever
+//! a new price period starts.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `service` | The configuration of a price provider (see below). | `{ name = "awattar" }`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $price.eng(w:4) ct/kWh "`
+//! `interval` | Update interval in seconds | `900`
+//! `good` | Maximum price, in ct/kWh, where state is set to good | `10.0`
+//! `warning` | Minimum price, in ct/kWh, where state is set to warning | `25.0`
+//! `critical` | Minimum price, in ct/kWh, where state is set to critical | `35.0`
+//!
+//! # aWATTar Options
+//!
+//! Uses the [aWATTar](https://www.awattar.de/services/api) market data API, which doesn't
+//! require an API key.
+//!
+//! Key | Values | Required | Default
+//! ----|--------|----------|--------
+//! `name` | `awattar`. | Yes | None
+//! `country` | `de` or `at` | No | `de`
+//!
+//! # Energy-Charts Options
+//!
+//! Uses the [Energy-Charts](https://api.energy-charts.info/) API by Fraunhofer ISE, which covers
+//! most European bidding zones (e.g. `DE-LU`, `FR`, `NL`, `NO1`, `SE3`, `DK1`) and doesn't require
+//! an API key.
+//!
+//! Key | Values | Required | Default
+//! ----|--------|----------|--------
+//! `name` | `energy_charts`. | Yes | None
+//! `bidding_zone` | The bidding zone | No | `DE-LU`
+//!
+//! # Available Format Keys
+//!
+//! Placeholder   | Value                                                       | Type     | Unit
+//! --------------|-------------------------------------------------------------|----------|-----
+//! `icon`        | A static icon                                               | Icon     | -
+//! `price`       | The current price in ct/kWh                                 | Number   | -
+//! `next`        | The price of the next period in ct/kWh (absent if unknown)  | Number   | -
+//! `min`         | The lowest known upcoming price in ct/kWh                   | Number   | -
+//! `max`         | The highest known upcoming price in ct/kWh                  | Number   | -
+//! `avg`         | The average of the known upcoming prices in ct/kWh          | Number   | -
+//! `cheapest_at` | The start of the period with the lowest upcoming price      | Datetime | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "energy_price"
+//! format = " $icon $price.eng(w:4) ct (min $min.eng(w:4) at $cheapest_at.datetime(f:'%R')) "
+//! good = 15
+//! [block.service]
+//! name = "energy_charts"
+//! bidding_zone = "NL"
+//! ```
+//!
+//! # Icons Used
+//! - `electricity`
+
+pub mod awattar;
+pub mod energy_charts;
+
+use chrono::{DateTime, Utc};
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub service: PriceService,
+    pub format: FormatConfig,
+    #[default(900.into())]
+    pub interval: Seconds,
+    #[default(10.0)]
+    pub good: f64,
+    #[default(25.0)]
+    pub warning: f64,
+    #[default(35.0)]
+    pub critical: f64,
+}
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum PriceService {
+    #[default]
+    Awattar(awattar::Config),
+    EnergyCharts(energy_charts::Config),
+}
+
+#[async_trait]
+trait PriceProvider {
+    /// Returns the prices of the current and as many upcoming periods as known, sorted by time
+    async fn get_prices(&self) -> Result<Vec<PricePeriod>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PricePeriod {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    /// The price in ct/kWh
+    price: f64,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $price.eng(w:4) ct/kWh ")?;
+
+    let provider: Box<dyn PriceProvider + Send + Sync> = match &config.service {
+        PriceService::Awattar(service_config) => Box::new(awattar::Service::new(service_config)?),
+        PriceService::EnergyCharts(service_config) => {
+            Box::new(energy_charts::Service::new(service_config))
+        }
+    };
+
+    loop {
+        let fetch = || provider.get_prices();
+        let prices = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let now = Utc::now();
+        let upcoming: Vec<PricePeriod> = prices.into_iter().filter(|p| p.end > now).collect();
+        let current = upcoming
+            .iter()
+            .find(|p| p.start <= now)
+            .error("No price available for the current period")?;
+        let next = upcoming.iter().find(|p| p.start >= current.end);
+        let cheapest = upcoming
+            .iter()
+            .min_by(|a, b| a.price.total_cmp(&b.price))
+            .unwrap_or(current);
+        let max = upcoming
+            .iter()
+            .map(|p| p.price)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let avg = upcoming.iter().map(|p| p.price).sum::<f64>() / upcoming.len() as f64;
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match current.price {
+            x if x >= config.critical => State::Critical,
+            x if x >= config.warning => State::Warning,
+            x if x <= config.good => State::Good,
+            _ => State::Idle,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("electricity"),
+            "price" => Value::number(current.price),
+            [if let Some(next) = next] "next" => Value::number(next.price),
+            "min" => Value::number(cheapest.price),
+            "max" => Value::number(max),
+            "avg" => Value::number(avg),
+            "cheapest_at" => Value::datetime(cheapest.start, None),
+        });
+        api.set_widget(widget)?;
+
+        // Also update as soon as the next period starts
+        let until_end = (current.end - now).to_std().unwrap_or_default();
+        select! {
+            _ = sleep(until_end.min(config.interval.0)) => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Converts a price in EUR/MWh to ct/kWh
+fn eur_per_mwh_to_ct_per_kwh(price: f64) -> f64 {
+    price / 10.0
+}
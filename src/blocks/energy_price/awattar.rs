@@ -0,0 +1,98 @@
+use super::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("de".into())]
+    country: String,
+}
+
+pub(super) struct Service {
+    url: String,
+}
+
+impl Service {
+    pub(super) fn new(config: &Config) -> Result<Self> {
+        let country = config.country.to_lowercase();
+        if country != "de" && country != "at" {
+            return Err(Error::new(format!(
+                "Unsupported country '{}', expected 'de' or 'at'",
+                config.country
+            )));
+        }
+        Ok(Self {
+            url: format!("https://api.awattar.{country}/v1/marketdata"),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    data: Vec<MarketData>,
+}
+
+#[derive(Deserialize)]
+struct MarketData {
+    start_timestamp: i64,
+    end_timestamp: i64,
+    /// The price in EUR/MWh
+    marketprice: f64,
+}
+
+#[async_trait]
+impl PriceProvider for Service {
+    async fn get_prices(&self) -> Result<Vec<PricePeriod>> {
+        // Without parameters, the prices from the current hour on are returned
+        let response: ApiResponse = REQWEST_CLIENT
+            .get(&self.url)
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to get prices")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+        parse_prices(response)
+    }
+}
+
+fn parse_prices(response: ApiResponse) -> Result<Vec<PricePeriod>> {
+    let mut prices = response
+        .data
+        .into_iter()
+        .map(|d| {
+            Ok(PricePeriod {
+                start: DateTime::from_timestamp_millis(d.start_timestamp)
+                    .error("Invalid timestamp")?,
+                end: DateTime::from_timestamp_millis(d.end_timestamp).error("Invalid timestamp")?,
+                price: eur_per_mwh_to_ct_per_kwh(d.marketprice),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    prices.sort_by_key(|p| p.start);
+    Ok(prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prices() {
+        let json = r#"{
+            "object": "list",
+            "data": [
+                {"start_timestamp": 1749549600000, "end_timestamp": 1749553200000, "marketprice": 12.5, "unit": "Eur/MWh"},
+                {"start_timestamp": 1749546000000, "end_timestamp": 1749549600000, "marketprice": 95.3, "unit": "Eur/MWh"}
+            ],
+            "url": "/at/v1/marketdata"
+        }"#;
+        let prices = parse_prices(serde_json::from_str(json).unwrap()).unwrap();
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[0].start.timestamp(), 1749546000);
+        assert_eq!(prices[0].end.timestamp(), 1749549600);
+        assert!((prices[0].price - 9.53).abs() < 1e-9);
+        assert!((prices[1].price - 1.25).abs() < 1e-9);
+    }
+}
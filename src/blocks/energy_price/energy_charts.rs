@@ -0,0 +1,105 @@
+use super::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("DE-LU".into())]
+    bidding_zone: String,
+}
+
+pub(super) struct Service {
+    bidding_zone: String,
+}
+
+impl Service {
+    pub(super) fn new(config: &Config) -> Self {
+        Self {
+            bidding_zone: config.bidding_zone.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    unix_seconds: Vec<i64>,
+    /// The prices in EUR/MWh, `null` where not yet known
+    price: Vec<Option<f64>>,
+}
+
+#[async_trait]
+impl PriceProvider for Service {
+    async fn get_prices(&self) -> Result<Vec<PricePeriod>> {
+        // https://api.energy-charts.info/#/prices/day_ahead_price_price_get
+        let now = Utc::now();
+        let response: ApiResponse = REQWEST_CLIENT
+            .get("https://api.energy-charts.info/price")
+            .query(&[
+                ("bzn", self.bidding_zone.as_str()),
+                ("start", &now.date_naive().to_string()),
+                (
+                    "end",
+                    &(now.date_naive() + chrono::Days::new(1)).to_string(),
+                ),
+            ])
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to get prices")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+        parse_prices(response)
+    }
+}
+
+/// The API only returns the start of each period, so the end is the start of the following one.
+/// The last period is assumed to be as long as the one before it.
+fn parse_prices(response: ApiResponse) -> Result<Vec<PricePeriod>> {
+    let starts: Vec<DateTime<Utc>> = response
+        .unix_seconds
+        .iter()
+        .map(|&s| DateTime::from_timestamp(s, 0).error("Invalid timestamp"))
+        .collect::<Result<_>>()?;
+
+    let mut prices = Vec::with_capacity(starts.len());
+    for (i, (start, price)) in starts.iter().zip(response.price).enumerate() {
+        let end = match (starts.get(i + 1), i.checked_sub(1).map(|j| starts[j])) {
+            (Some(&next), _) => next,
+            (None, Some(prev)) => *start + (*start - prev),
+            (None, None) => *start + Duration::from_secs(3600),
+        };
+        if let Some(price) = price {
+            prices.push(PricePeriod {
+                start: *start,
+                end,
+                price: eur_per_mwh_to_ct_per_kwh(price),
+            });
+        }
+    }
+    Ok(prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prices() {
+        let json = r#"{
+            "license_info": "CC BY 4.0",
+            "unix_seconds": [1749506400, 1749507300, 1749508200],
+            "price": [80.0, null, -5.0],
+            "unit": "EUR / MWh",
+            "deprecated": false
+        }"#;
+        let prices = parse_prices(serde_json::from_str(json).unwrap()).unwrap();
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[0].start.timestamp(), 1749506400);
+        assert_eq!(prices[0].end.timestamp(), 1749507300);
+        assert_eq!(prices[0].price, 8.0);
+        assert_eq!(prices[1].start.timestamp(), 1749508200);
+        assert_eq!(prices[1].end.timestamp(), 1749509100);
+        assert_eq!(prices[1].price, -0.5);
+    }
+}
@@ -0,0 +1,97 @@
+//! Available entropy of the kernel random number generator
+//!
+//! This block shows the amount of entropy available in the kernel's entropy pool, which is useful
+//! on older kernels and embedded boards where entropy starvation can stall services reading
+//! `/dev/random`. Since Linux 5.18 the pool size is fixed at 256 bits and the reported entropy is
+//! always 256 once the RNG is initialized.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $entropy "`
+//! `interval` | Update interval in seconds | `10`
+//! `warning` | Entropy, in bits, below which the state is set to warning | `128`
+//! `critical` | Entropy, in bits, below which the state is set to critical | `64`
+//!
+//! Placeholder  | Value                                                           | Type   | Unit
+//! -------------|-----------------------------------------------------------------|--------|-----
+//! `icon`       | A static icon                                                   | Icon   | -
+//! `entropy`    | The available entropy, in bits                                  | Number | -
+//! `pool_size`  | The size of the entropy pool, in bits                           | Number | -
+//! `percentage` | The available entropy relative to the pool size                 | Number | %
+//! `hw_rng`     | The hardware RNG in use (absent if there is none)               | Text   | -
+//! `jitter`     | Present if the jitter entropy RNG is loaded                     | Flag   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "entropy"
+//! format = " $icon $entropy/$pool_size{ $hw_rng|} "
+//! ```
+//!
+//! # Icons Used
+//! - `random`
+
+use super::prelude::*;
+use crate::util::read_file;
+use std::path::Path;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+    #[default(128)]
+    pub warning: u32,
+    #[default(64)]
+    pub critical: u32,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $entropy ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let entropy = read_number("/proc/sys/kernel/random/entropy_avail").await?;
+        let pool_size = read_number("/proc/sys/kernel/random/poolsize").await?;
+        let hw_rng = read_file("/sys/class/misc/hw_random/rng_current")
+            .await
+            .ok()
+            .filter(|rng| rng != "none");
+        let jitter = Path::new("/sys/module/jitterentropy_rng").exists();
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if entropy < config.critical {
+            State::Critical
+        } else if entropy < config.warning {
+            State::Warning
+        } else {
+            State::Idle
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("random"),
+            "entropy" => Value::number(entropy),
+            "pool_size" => Value::number(pool_size),
+            "percentage" => Value::percents(entropy as f64 / pool_size.max(1) as f64 * 100.0),
+            [if let Some(rng) = hw_rng] "hw_rng" => Value::text(rng),
+            [if jitter] "jitter" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn read_number(path: &str) -> Result<u32> {
+    read_file(path)
+        .await
+        .or_error(|| format!("Failed to read {path}"))?
+        .parse()
+        .or_error(|| format!("{path} has invalid content"))
+}
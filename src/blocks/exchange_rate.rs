@@ -0,0 +1,148 @@
+//! Currency exchange rates
+//!
+//! This block shows exchange rates between fiat currencies, using the [reference rates](https://www.ecb.europa.eu/stats/policy_and_exchange_rates/euro_reference_exchange_rates/html/index.en.html)
+//! published by the European Central Bank once per working day. No API key is required.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `from` | The currency to convert from | `"EUR"`
+//! `to` | List of currencies to convert to | `["USD"]`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $rate.eng(w:4) "`
+//! `interval` | Update interval in seconds | `21600` (6 hours)
+//!
+//! Placeholder   | Value                                                   | Type   | Unit
+//! --------------|---------------------------------------------------------|--------|-----
+//! `icon`        | A static icon                                           | Icon   | -
+//! `rate`        | The rate of the first currency in `to`                  | Number | -
+//! `<currency>`  | The rate of `<currency>` in lower case, e.g. `usd`      | Number | -
+//! `date`        | The date the rates were published on                    | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "exchange_rate"
+//! from = "USD"
+//! to = ["EUR", "GBP"]
+//! format = " $icon $eur.eng(w:4)€ $gbp.eng(w:4)£ "
+//! ```
+//!
+//! # Icons Used
+//! - `currency`
+
+use super::prelude::*;
+
+const ECB_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("EUR".into())]
+    pub from: String,
+    #[default(vec!["USD".into()])]
+    pub to: Vec<String>,
+    pub format: FormatConfig,
+    #[default(21600.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $rate.eng(w:4) ")?;
+    let mut timer = config.interval.timer();
+
+    if config.to.is_empty() {
+        return Err(Error::new("`to` is empty"));
+    }
+    let from = config.from.to_uppercase();
+
+    loop {
+        let fetch = || async {
+            REQWEST_CLIENT
+                .get(ECB_URL)
+                .send()
+                .await
+                .error("Failed to send request")?
+                .error_for_status()
+                .error("Failed to get exchange rates")?
+                .text()
+                .await
+                .error("Failed to get response body")
+        };
+        let xml = fetch.retry(&ExponentialBuilder::default()).await?;
+        let (date, rates) = parse_rates(&xml)?;
+
+        let rate_of = |currency: &str| -> Result<f64> {
+            rates
+                .get(currency)
+                .copied()
+                .or_error(|| format!("Unknown currency '{currency}'"))
+        };
+        let from_rate = rate_of(&from)?;
+
+        let mut values = map! {
+            "icon" => Value::icon("currency"),
+            "date" => Value::text(date),
+        };
+        for (i, to) in config.to.iter().enumerate() {
+            let rate = rate_of(&to.to_uppercase())? / from_rate;
+            if i == 0 {
+                values.insert("rate".into(), Value::number(rate));
+            }
+            values.insert(to.to_lowercase().into(), Value::number(rate));
+        }
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.set_values(values);
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Returns the date and the euro exchange rates, including the euro itself
+fn parse_rates(xml: &str) -> Result<(String, HashMap<String, f64>)> {
+    let time_regex = regex!(r#"<Cube\s+time=['"]([^'"]+)['"]"#);
+    let rate_regex = regex!(r#"<Cube\s+currency=['"]([A-Z]{3})['"]\s+rate=['"]([0-9.]+)['"]"#);
+
+    let date = time_regex
+        .captures(xml)
+        .error("Failed to find the date of the rates")?[1]
+        .to_string();
+    let mut rates: HashMap<String, f64> = rate_regex
+        .captures_iter(xml)
+        .filter_map(|c| Some((c[1].to_string(), c[2].parse().ok()?)))
+        .collect();
+    rates.insert("EUR".into(), 1.0);
+    Ok((date, rates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rates() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<Cube>
+		<Cube time='2025-06-10'>
+			<Cube currency='USD' rate='1.1420'/>
+			<Cube currency='JPY' rate='165.53'/>
+			<Cube currency='GBP' rate='0.84475'/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>"#;
+        let (date, rates) = parse_rates(xml).unwrap();
+        assert_eq!(date, "2025-06-10");
+        assert_eq!(rates.len(), 4);
+        assert_eq!(rates["USD"], 1.142);
+        assert_eq!(rates["GBP"], 0.84475);
+        assert_eq!(rates["EUR"], 1.0);
+    }
+}
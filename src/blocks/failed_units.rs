@@ -0,0 +1,114 @@
+//! The number of failed systemd units
+//!
+//! This block watches systemd over D-Bus and shows the number of units in the `failed` state. It
+//! is hidden if no unit has failed.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count "`
+//! `user` | Watch the user's service manager instead of the system one | `false`
+//!
+//! Placeholder | Value                                       | Type   | Unit
+//! ------------|---------------------------------------------|--------|-----
+//! `icon`      | A static icon                               | Icon   | -
+//! `count`     | The number of failed units                  | Number | -
+//! `names`     | A comma separated list of the failed units  | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "failed_units"
+//! format = " $icon $names "
+//! [[block.click]]
+//! button = "left"
+//! cmd = "alacritty -e systemctl list-units --failed"
+//! ```
+//!
+//! # Icons Used
+//! - `warning`
+
+use super::prelude::*;
+use zbus::zvariant::OwnedObjectPath;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    pub user: bool,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $count ")?;
+
+    let dbus_conn = if config.user {
+        new_dbus_connection().await?
+    } else {
+        new_system_dbus_connection().await?
+    };
+    let manager = ManagerProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create ManagerProxy")?;
+    // Without a subscription systemd does not emit any signals
+    manager
+        .subscribe()
+        .await
+        .error("Failed to subscribe to systemd")?;
+    let mut changes = manager.receive_n_failed_units_changed().await;
+
+    loop {
+        let units = manager
+            .list_units_filtered(&["failed"])
+            .await
+            .error("Failed to list failed units")?;
+
+        if units.is_empty() {
+            api.hide()?;
+        } else {
+            let mut names: Vec<&str> = units.iter().map(|unit| unit.0.as_str()).collect();
+            names.sort_unstable();
+
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = State::Critical;
+            widget.set_values(map! {
+                "icon" => Value::icon("warning"),
+                "count" => Value::number(units.len()),
+                "names" => Value::text(names.join(", ")),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            _ = changes.next() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+type UnitInfo = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+#[zbus::proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait Manager {
+    fn subscribe(&self) -> zbus::Result<()>;
+    fn list_units_filtered(&self, states: &[&str]) -> zbus::Result<Vec<UnitInfo>>;
+
+    #[zbus(property, name = "NFailedUnits")]
+    fn n_failed_units(&self) -> zbus::Result<u32>;
+}
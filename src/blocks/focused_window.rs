@@ -9,6 +9,7 @@
 //! Key | Values | Default
 //! ----|--------|--------
 //! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $title.str(max_w:21) \|\"</code>
+//! `app_icons` | A map from `app_id`s to the text to show as `app_icon`, e.g. `{ firefox = "\uf269" }` | `{}`
 //! `driver` | Which driver to use. Available values: `sway_ipc` - for `i3` and `sway`, `wlr_toplevel_management` - for Wayland compositors that implement [wlr-foreign-toplevel-management-unstable-v1](https://gitlab.freedesktop.org/wlroots/wlr-protocols/-/blob/master/unstable/wlr-foreign-toplevel-management-unstable-v1.xml), `auto` - try to automatically guess which driver to use. | `"auto"`
 //!
 //! Placeholder     | Value                                                                 | Type | Unit
@@ -16,6 +17,8 @@
 //! `title`         | Window's title (may be absent)                                        | Text | -
 //! `marks`         | Window's marks (present only with sway/i3)                            | Text | -
 //! `visible_marks` | Window's marks that do not start with `_` (present only with sway/i3) | Text | -
+//! `app_id`        | Window's app_id, or its class for X11 windows (may be absent)         | Text | -
+//! `app_icon`      | The entry of `app_icons` for `app_id` (may be absent)                 | Text | -
 //!
 //! # Example
 //!
@@ -27,6 +30,17 @@
 //! short = " $title.str(max_w:10) |"
 //! ```
 //!
+//! Show an icon for some applications, and scroll long titles:
+//!
+//! ```toml
+//! [[block]]
+//! block = "focused_window"
+//! format = " {$app_icon |}$title.str(max_w:30,rot_interval:0.3) |"
+//! [block.app_icons]
+//! firefox = "\uf269"
+//! Alacritty = "\uf120"
+//! ```
+//!
 //! This example instead of hiding block when the window's title is empty displays "Missing"
 //!
 //! ```toml
@@ -48,6 +62,7 @@ use super::prelude::*;
 pub struct Config {
     pub format: FormatConfig,
     pub driver: Driver,
+    pub app_icons: HashMap<String, String>,
 }
 
 #[derive(Deserialize, Debug, SmartDefault)]
@@ -72,7 +87,11 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     };
 
     loop {
-        let Info { title, marks } = backend.get_info().await?;
+        let Info {
+            title,
+            app_id,
+            marks,
+        } = backend.get_info().await?;
 
         let mut widget = Widget::new().with_format(format.clone());
 
@@ -88,10 +107,14 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
                 .filter(|m| !m.starts_with('_'))
                 .fold(String::new(), join_marks);
 
+            let app_icon = app_id.as_ref().and_then(|a| config.app_icons.get(a));
+
             widget.set_values(map! {
                 "title" => Value::text(title),
                 "marks" => Value::text(marks_str),
                 "visible_marks" => Value::text(visible_marks_str),
+                [if let Some(a) = app_icon] "app_icon" => Value::text(a.clone()),
+                [if let Some(a) = app_id] "app_id" => Value::text(a),
             });
         }
 
@@ -107,5 +130,6 @@ trait Backend {
 #[derive(Clone, Default)]
 struct Info {
     title: String,
+    app_id: Option<String>,
     marks: Vec<String>,
 }
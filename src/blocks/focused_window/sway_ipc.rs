@@ -37,11 +37,16 @@ impl Backend for SwayIpc {
                         self.info.marks = e.container.marks;
                     }
                     WindowChange::Focus => {
+                        let container = e.container;
                         self.info.title.clear();
-                        if let Some(new_title) = &e.container.name {
+                        if let Some(new_title) = &container.name {
                             self.info.title.push_str(new_title);
                         }
-                        self.info.marks = e.container.marks;
+                        // Native Wayland windows have an app_id, X11 ones a class
+                        self.info.app_id = container
+                            .app_id
+                            .or_else(|| container.window_properties.and_then(|p| p.class));
+                        self.info.marks = container.marks;
                     }
                     WindowChange::Title => {
                         if e.container.focused {
@@ -55,12 +60,14 @@ impl Backend for SwayIpc {
                     }
                     WindowChange::Close => {
                         self.info.title.clear();
+                        self.info.app_id = None;
                         self.info.marks.clear();
                     }
                     _ => continue,
                 },
                 Event::Workspace(e) if e.change == WorkspaceChange::Init => {
                     self.info.title.clear();
+                    self.info.app_id = None;
                     self.info.marks.clear();
                 }
                 _ => continue,
@@ -14,7 +14,7 @@ pub(super) struct WlrToplevelManagement {
 #[derive(Default)]
 struct State {
     error: Option<Error>,
-    new_title: Option<String>,
+    new_info: Option<Info>,
     toplevels: HashMap<ZwlrForeignToplevelHandleV1, Toplevel>,
     active_toplevel: Option<ZwlrForeignToplevelHandleV1>,
 }
@@ -22,6 +22,7 @@ struct State {
 #[derive(Default)]
 struct Toplevel {
     title: Option<String>,
+    app_id: Option<String>,
     is_active: bool,
 }
 
@@ -54,11 +55,8 @@ impl Backend for WlrToplevelManagement {
                 return Err(err);
             }
 
-            if let Some(title) = self.state.new_title.take() {
-                return Ok(Info {
-                    title,
-                    marks: default(),
-                });
+            if let Some(info) = self.state.new_info.take() {
+                return Ok(info);
             }
         }
     }
@@ -90,6 +88,9 @@ fn toplevel_cb(ctx: EventCtx<State, ZwlrForeignToplevelHandleV1>) {
         Event::Title(title) => {
             toplevel.title = Some(String::from_utf8_lossy(title.as_bytes()).into());
         }
+        Event::AppId(app_id) => {
+            toplevel.app_id = Some(String::from_utf8_lossy(app_id.as_bytes()).into());
+        }
         Event::State(state) => {
             toplevel.is_active = state
                 .chunks_exact(4)
@@ -99,7 +100,7 @@ fn toplevel_cb(ctx: EventCtx<State, ZwlrForeignToplevelHandleV1>) {
         Event::Closed => {
             if ctx.state.active_toplevel == Some(ctx.proxy) {
                 ctx.state.active_toplevel = None;
-                ctx.state.new_title = Some(default());
+                ctx.state.new_info = Some(default());
             }
 
             ctx.proxy.destroy(ctx.conn);
@@ -108,10 +109,14 @@ fn toplevel_cb(ctx: EventCtx<State, ZwlrForeignToplevelHandleV1>) {
         Event::Done => {
             if toplevel.is_active {
                 ctx.state.active_toplevel = Some(ctx.proxy);
-                ctx.state.new_title = Some(toplevel.title.clone().unwrap_or_default());
+                ctx.state.new_info = Some(Info {
+                    title: toplevel.title.clone().unwrap_or_default(),
+                    app_id: toplevel.app_id.clone(),
+                    marks: default(),
+                });
             } else if ctx.state.active_toplevel == Some(ctx.proxy) {
                 ctx.state.active_toplevel = None;
-                ctx.state.new_title = Some(default());
+                ctx.state.new_info = Some(default());
             }
         }
         _ => (),
@@ -0,0 +1,109 @@
+//! GameMode status
+//!
+//! This block shows whether [GameMode](https://github.com/FeralInteractive/gamemode) is active,
+//! i.e. whether `gamemoded` applies its optimisations because at least one game requested it.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon{ $count\|} "`
+//!
+//! Placeholder | Value                                                         | Type   | Unit
+//! ------------|---------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                 | Icon   | -
+//! `count`     | The number of games which registered (absent if none)         | Number | -
+//! `games`     | The process names of these games, separated by commas         | Text   | -
+//! `active`    | Present while GameMode is active                              | Flag   | -
+//!
+//! The block is set to the good state while GameMode is active.
+//!
+//! # Example
+//!
+//! Only show the block while GameMode is active
+//!
+//! ```toml
+//! [[block]]
+//! block = "gamemode"
+//! format = "{ $icon $games.str(max_w:20) |}"
+//! ```
+//!
+//! # Icons Used
+//! - `joystick`
+
+use zbus::zvariant::OwnedObjectPath;
+
+use super::prelude::*;
+use crate::util::read_file;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon{ $count|} ")?;
+
+    let dbus_conn = new_dbus_connection().await?;
+    let gamemode = GameModeProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create GameModeProxy")?;
+    let mut registered = gamemode
+        .receive_game_registered()
+        .await
+        .error("Failed to monitor GameMode")?;
+    let mut unregistered = gamemode
+        .receive_game_unregistered()
+        .await
+        .error("Failed to monitor GameMode")?;
+
+    loop {
+        let games = gamemode
+            .list_games()
+            .await
+            .error("Failed to list the games")?;
+        let mut names = Vec::new();
+        for (pid, _) in &games {
+            // The game may have exited in the meantime
+            if let Ok(name) = read_file(format!("/proc/{pid}/comm")).await {
+                names.push(name);
+            }
+        }
+        let names = names.join(", ");
+        let active = !games.is_empty();
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if active {
+            widget.state = State::Good;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon("joystick"),
+            [if active] "count" => Value::number(games.len()),
+            [if !names.is_empty()] "games" => Value::text(names),
+            [if active] "active" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = registered.next() => (),
+            _ = unregistered.next() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+#[zbus::proxy(
+    interface = "com.feralinteractive.GameMode",
+    default_service = "com.feralinteractive.GameMode",
+    default_path = "/com/feralinteractive/GameMode"
+)]
+trait GameMode {
+    /// Returns the pid and the object path of every registered game
+    fn list_games(&self) -> zbus::Result<Vec<(i32, OwnedObjectPath)>>;
+
+    #[zbus(signal)]
+    fn game_registered(&self, pid: i32, path: OwnedObjectPath) -> zbus::Result<()>;
+    #[zbus(signal)]
+    fn game_unregistered(&self, pid: i32, path: OwnedObjectPath) -> zbus::Result<()>;
+}
@@ -1,6 +1,6 @@
 //! The number of GitHub notifications
 //!
-//! This block shows the unread notification count for a GitHub account. A GitHub [personal access token](https://github.com/settings/tokens/new) with the "notifications" scope is required, and must be passed using the `I3RS_GITHUB_TOKEN` environment variable or `token` configuration option. Optionally the colour of the block is determined by the highest notification in the following lists from highest to lowest: `critical`,`warning`,`info`,`good`
+//! This block shows the unread notification count for a GitHub account. A GitHub [personal access token](https://github.com/settings/tokens/new) with the "notifications" scope is required, and must be passed using the `I3RS_GITHUB_TOKEN` environment variable, the `token` configuration option or a file given by `token_file`. Optionally the colour of the block is determined by the highest notification in the following lists from highest to lowest: `critical`,`warning`,`info`,`good`
 //!
 //! # Configuration
 //!
@@ -9,6 +9,9 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $total.eng(w:1) "`
 //! `interval` | Update interval in seconds | `30`
 //! `token` | A GitHub personal access token with the "notifications" scope | `None`
+//! `token_file` | Path to a file containing the token. Supports path expansions e.g. `~`. | `None`
+//! `repos` | Only count notifications of these repositories, e.g. `["rust-lang/rust"]` | `None`
+//! `reasons` | Only count notifications with these reasons, e.g. `["mention", "review_requested"]`. See the placeholders below for the possible values. | `None`
 //! `hide_if_total_is_zero` | Hide this block if the total count of notifications is zero | `false`
 //! `critical` | List of notification types that change the block to the critical colour | `None`
 //! `warning` | List of notification types that change the block to the warning colour | `None`
@@ -55,10 +58,26 @@
 //! hide_if_total_is_zero = true
 //! ```
 //!
+//! Only count mentions and review requests of a few repositories, and open the notifications page
+//! on click:
+//!
+//! ```toml
+//! [[block]]
+//! block = "github"
+//! token_file = "~/.config/github_token"
+//! repos = ["greshake/i3status-rust", "rust-lang/rust"]
+//! reasons = ["mention", "team_mention", "review_requested"]
+//! hide_if_total_is_zero = true
+//! [[block.click]]
+//! button = "left"
+//! cmd = "xdg-open https://github.com/notifications"
+//! ```
+//!
 //! # Icons Used
 //! - `github`
 
 use super::prelude::*;
+use crate::util::get_token;
 
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
@@ -67,6 +86,9 @@ pub struct Config {
     pub interval: Seconds,
     pub format: FormatConfig,
     pub token: Option<String>,
+    pub token_file: Option<ShellString>,
+    pub repos: Option<Vec<String>>,
+    pub reasons: Option<Vec<String>>,
     pub hide_if_total_is_zero: bool,
     pub good: Option<Vec<String>>,
     pub info: Option<Vec<String>>,
@@ -78,14 +100,15 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let format = config.format.with_default(" $icon $total.eng(w:1) ")?;
 
     let mut interval = config.interval.timer();
-    let token = config
-        .token
-        .clone()
-        .or_else(|| std::env::var("I3RS_GITHUB_TOKEN").ok())
-        .error("Github token not found")?;
+    let token = get_token(
+        config.token.as_deref(),
+        config.token_file.as_ref(),
+        "I3RS_GITHUB_TOKEN",
+    )
+    .await?;
 
     loop {
-        let stats = get_stats(&token).await?;
+        let stats = get_stats(config, &token).await?;
 
         if stats.get("total").is_some_and(|x| *x > 0) || !config.hide_if_total_is_zero {
             let mut widget = Widget::new().with_format(format.clone());
@@ -128,9 +151,15 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
 #[derive(Deserialize, Debug)]
 struct Notification {
     reason: String,
+    repository: Repository,
 }
 
-async fn get_stats(token: &str) -> Result<HashMap<String, usize>> {
+#[derive(Deserialize, Debug)]
+struct Repository {
+    full_name: String,
+}
+
+async fn get_stats(config: &Config, token: &str) -> Result<HashMap<String, usize>> {
     let mut stats = HashMap::new();
     let mut total = 0;
     for page in 1..100 {
@@ -139,8 +168,19 @@ async fn get_stats(token: &str) -> Result<HashMap<String, usize>> {
         if on_page.is_empty() {
             break;
         }
-        total += on_page.len();
         for n in on_page {
+            if config
+                .repos
+                .as_ref()
+                .is_some_and(|repos| !repos.contains(&n.repository.full_name))
+                || config
+                    .reasons
+                    .as_ref()
+                    .is_some_and(|reasons| !reasons.contains(&n.reason))
+            {
+                continue;
+            }
+            total += 1;
             stats.entry(n.reason).and_modify(|x| *x += 1).or_insert(1);
         }
     }
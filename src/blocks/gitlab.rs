@@ -0,0 +1,168 @@
+//! GitLab todos and pipeline status
+//!
+//! This block shows the number of pending GitLab todos and, if `project` is set, the status of the
+//! latest pipeline of a project. A [personal access token](https://docs.gitlab.com/ee/user/profile/personal_access_tokens.html)
+//! with the `read_api` scope is required, and must be passed using the `I3RS_GITLAB_TOKEN`
+//! environment variable, the `token` configuration option or a file given by `token_file`.
+//!
+//! The block is set to the critical state if the latest pipeline failed and to the info state while
+//! it is running.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The base URL of the GitLab instance | `"https://gitlab.com"`
+//! `token` | A personal access token with the `read_api` scope | `None`
+//! `token_file` | Path to a file containing the token. Supports path expansions e.g. `~`. | `None`
+//! `project` | ID or path (e.g. `"gitlab-org/gitlab"`) of the project whose pipelines are shown | `None`
+//! `branch` | Only show pipelines for this branch | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $todos.eng(w:1){ $pipeline_status\|} "`
+//! `interval` | Update interval in seconds | `60`
+//!
+//! Placeholder       | Value                                                                | Type   | Unit
+//! ------------------|----------------------------------------------------------------------|--------|-----
+//! `icon`            | A static icon                                                        | Icon   | -
+//! `todos`           | The number of pending todos                                          | Number | -
+//! `pipeline_status` | The status of the latest pipeline, e.g. `success` or `failed`        | Text   | -
+//! `pipeline_ref`    | The branch or tag of the latest pipeline                             | Text   | -
+//! `pipeline_id`     | The ID of the latest pipeline                                        | Number | -
+//!
+//! The `pipeline_*` placeholders are absent if `project` is not set or the project has no
+//! pipelines.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "gitlab"
+//! token_file = "~/.config/gitlab_token"
+//! project = "my-group/my-project"
+//! branch = "main"
+//! format = " $icon $todos.eng(w:1) {$pipeline_status|} "
+//! [[block.click]]
+//! button = "left"
+//! cmd = "xdg-open https://gitlab.com/dashboard/todos"
+//! ```
+//!
+//! # Icons Used
+//! - `gitlab`
+
+use super::prelude::*;
+use crate::util::get_token;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("https://gitlab.com".into())]
+    pub url: String,
+    pub token: Option<String>,
+    pub token_file: Option<ShellString>,
+    pub project: Option<String>,
+    pub branch: Option<String>,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug)]
+struct Pipeline {
+    id: u64,
+    status: String,
+    #[serde(rename = "ref")]
+    git_ref: String,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $todos.eng(w:1){ $pipeline_status|} ")?;
+    let mut timer = config.interval.timer();
+
+    let url = config.url.trim_end_matches('/');
+    let token = get_token(
+        config.token.as_deref(),
+        config.token_file.as_ref(),
+        "I3RS_GITLAB_TOKEN",
+    )
+    .await?;
+    // Project paths have to be URL-encoded
+    let project = config.project.as_ref().map(|p| p.replace('/', "%2F"));
+
+    loop {
+        let fetch = || get_todos(url, &token);
+        let todos = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let pipeline = match &project {
+            Some(project) => {
+                let fetch = || get_latest_pipeline(url, &token, project, config.branch.as_deref());
+                fetch.retry(&ExponentialBuilder::default()).await?
+            }
+            None => None,
+        };
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match pipeline.as_ref().map(|p| p.status.as_str()) {
+            Some("failed") => State::Critical,
+            Some("running" | "pending" | "preparing") => State::Info,
+            _ => State::Idle,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("gitlab"),
+            "todos" => Value::number(todos),
+            [if let Some(p) = &pipeline] "pipeline_status" => Value::text(p.status.clone()),
+            [if let Some(p) = &pipeline] "pipeline_ref" => Value::text(p.git_ref.clone()),
+            [if let Some(p) = &pipeline] "pipeline_id" => Value::number(p.id),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn get_todos(url: &str, token: &str) -> Result<usize> {
+    // https://docs.gitlab.com/ee/api/todos.html
+    let response = REQWEST_CLIENT
+        .get(format!("{url}/api/v4/todos?state=pending&per_page=1"))
+        .header("PRIVATE-TOKEN", token)
+        .send()
+        .await
+        .error("Failed to send request")?
+        .error_for_status()
+        .error("Failed to get todos")?;
+    response
+        .headers()
+        .get("x-total")
+        .and_then(|total| total.to_str().ok())
+        .and_then(|total| total.parse().ok())
+        .error("Failed to get the number of todos")
+}
+
+async fn get_latest_pipeline(
+    url: &str,
+    token: &str,
+    project: &str,
+    branch: Option<&str>,
+) -> Result<Option<Pipeline>> {
+    // https://docs.gitlab.com/ee/api/pipelines.html
+    let mut request = REQWEST_CLIENT
+        .get(format!("{url}/api/v4/projects/{project}/pipelines"))
+        .header("PRIVATE-TOKEN", token)
+        .query(&[("per_page", "1")]);
+    if let Some(branch) = branch {
+        request = request.query(&[("ref", branch)]);
+    }
+    let pipelines: Vec<Pipeline> = request
+        .send()
+        .await
+        .error("Failed to send request")?
+        .error_for_status()
+        .error("Failed to get pipelines")?
+        .json()
+        .await
+        .error("Failed to parse JSON")?;
+    Ok(pipelines.into_iter().next())
+}
@@ -0,0 +1,160 @@
+//! The state of a Home Assistant entity
+//!
+//! This block displays the state of an entity using the Home Assistant REST API. A
+//! [long-lived access token](https://developers.home-assistant.io/docs/auth_api/#long-lived-access-token)
+//! is required, and must be passed using the `I3RS_HOME_ASSISTANT_TOKEN` environment variable or
+//! `token` configuration option.
+//!
+//! Entities that can be switched (lights, switches, fans, etc.) can be toggled using the `toggle`
+//! action, which is not bound to any button by default.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The base URL of your Home Assistant instance | **Required**
+//! `token` | A long-lived access token | `None`
+//! `entity_id` | The entity to display, e.g. `"sensor.living_room_temperature"` | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $state{ $unit\|} "`
+//! `interval` | Update interval in seconds | `30`
+//!
+//! Placeholder | Value                                            | Type           | Unit
+//! ------------|--------------------------------------------------|----------------|-----
+//! `icon`      | A static icon                                    | Icon           | -
+//! `state`     | The state of the entity                          | Number or Text | -
+//! `unit`      | The unit of measurement (absent if not provided) | Text           | -
+//! `name`      | The friendly name of the entity                  | Text           | -
+//! `on`        | Present if the state is `on`                     | Flag           | -
+//!
+//! The block is set to the good state if the entity is `on` and to the warning state if it is
+//! `unavailable`.
+//!
+//! Action   | Description                        | Default button
+//! ---------|------------------------------------|---------------
+//! `toggle` | Toggle the entity                  | -
+//!
+//! # Examples
+//!
+//! ```toml
+//! [[block]]
+//! block = "home_assistant"
+//! url = "http://homeassistant.local:8123"
+//! entity_id = "sensor.living_room_temperature"
+//! format = " $icon $state.eng(w:2)$unit "
+//! ```
+//!
+//! ```toml
+//! [[block]]
+//! block = "home_assistant"
+//! url = "http://homeassistant.local:8123"
+//! entity_id = "light.desk"
+//! format = " $icon $name "
+//! [[block.click]]
+//! button = "left"
+//! action = "toggle"
+//! ```
+//!
+//! # Icons Used
+//! - `home`
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub url: String,
+    pub token: Option<String>,
+    pub entity_id: String,
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default = "default_interval")]
+    pub interval: Seconds,
+}
+
+fn default_interval() -> Seconds {
+    Seconds::new(30)
+}
+
+#[derive(Deserialize, Debug)]
+struct EntityState {
+    state: String,
+    #[serde(default)]
+    attributes: Attributes,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct Attributes {
+    friendly_name: Option<String>,
+    unit_of_measurement: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+
+    let format = config.format.with_default(" $icon $state{ $unit|} ")?;
+    let mut timer = config.interval.timer();
+
+    let url = config.url.trim_end_matches('/');
+    let token = config
+        .token
+        .clone()
+        .or_else(|| std::env::var("I3RS_HOME_ASSISTANT_TOKEN").ok())
+        .error("Home Assistant token not found")?;
+
+    loop {
+        let fetch = || async {
+            REQWEST_CLIENT
+                .get(format!("{url}/api/states/{}", config.entity_id))
+                .bearer_auth(&token)
+                .send()
+                .await
+                .error("Failed to send request")?
+                .error_for_status()
+                .error("Failed to get entity state")?
+                .json::<EntityState>()
+                .await
+                .error("Failed to parse JSON")
+        };
+        let entity = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match entity.state.as_str() {
+            "on" => State::Good,
+            "unavailable" => State::Warning,
+            _ => State::Idle,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("home"),
+            [if entity.state == "on"] "on" => Value::flag(),
+            "name" => Value::text(entity.attributes.friendly_name.unwrap_or_else(|| config.entity_id.clone())),
+            [if let Some(unit) = entity.attributes.unit_of_measurement] "unit" => Value::text(unit),
+            "state" => match entity.state.parse::<f64>() {
+                Ok(number) => Value::number(number),
+                Err(_) => Value::text(entity.state),
+            },
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "toggle" => {
+                        REQWEST_CLIENT
+                            .post(format!("{url}/api/services/homeassistant/toggle"))
+                            .bearer_auth(&token)
+                            .json(&serde_json::json!({ "entity_id": config.entity_id }))
+                            .send()
+                            .await
+                            .error("Failed to send request")?
+                            .error_for_status()
+                            .error("Failed to toggle entity")?;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! A value fetched from an HTTP endpoint
+//!
+//! This block periodically sends a GET request to `url` and displays a value extracted from the
+//! response. Use `json_path` to select a field of a JSON response or `regex` to pick a part of a
+//! plain text response. If neither is set, the whole (trimmed) response body is displayed.
+//!
+//! `json_path` supports a simple subset of JSONPath: object keys separated by dots and array
+//! indices in square brackets, e.g. `$.data.items[0].value`. The leading `$` is optional.
+//!
+//! If `regex` contains a capture group, the first group is used, otherwise the whole match.
+//!
+//! If the extracted value is a number, the block's state is determined by the thresholds.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The URL to request | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $value "`
+//! `interval` | Update interval in seconds | `60`
+//! `headers` | A table of additional request headers | `{}`
+//! `username` | Username for HTTP basic authentication | `None`
+//! `password` | Password for HTTP basic authentication | `None`
+//! `bearer_token` | Token for bearer authentication. May be set using the `I3RS_HTTP_TOKEN` environment variable instead. | `None`
+//! `json_path` | Path of the value in a JSON response | `None`
+//! `regex` | A regex used to extract the value from the response | `None`
+//! `info` | Minimum value, where state is set to info | `None`
+//! `good` | Minimum value, where state is set to good | `None`
+//! `warning` | Minimum value, where state is set to warning | `None`
+//! `critical` | Minimum value, where state is set to critical | `None`
+//!
+//! Placeholder | Value                                  | Type           | Unit
+//! ------------|----------------------------------------|----------------|-----
+//! `value`     | The extracted value                    | Number or Text | -
+//! `status`    | The HTTP status code of the response   | Number         | -
+//!
+//! # Example
+//!
+//! Show the number of stars of a GitHub repository:
+//!
+//! ```toml
+//! [[block]]
+//! block = "http"
+//! url = "https://api.github.com/repos/greshake/i3status-rust"
+//! json_path = "$.stargazers_count"
+//! format = " Stars: $value.eng(w:1) "
+//! interval = 3600
+//! [block.headers]
+//! Accept = "application/vnd.github+json"
+//! ```
+//!
+//! Warn when the queue length reported by an internal service gets long:
+//!
+//! ```toml
+//! [[block]]
+//! block = "http"
+//! url = "http://localhost:8080/metrics"
+//! regex = 'queue_length (\d+)'
+//! warning = 100
+//! critical = 1000
+//! ```
+
+use super::prelude::*;
+use crate::util::state_from_thresholds;
+use crate::wrappers::SerdeRegex;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub url: String,
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default = "default_interval")]
+    pub interval: Seconds,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub bearer_token: Option<String>,
+    pub json_path: Option<String>,
+    pub regex: Option<SerdeRegex>,
+    pub info: Option<f64>,
+    pub good: Option<f64>,
+    pub warning: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+fn default_interval() -> Seconds {
+    Seconds::new(60)
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $value ")?;
+    let mut timer = config.interval.timer();
+
+    let json_path = config
+        .json_path
+        .as_deref()
+        .map(parse_json_path)
+        .transpose()?;
+    let bearer_token = config
+        .bearer_token
+        .clone()
+        .or_else(|| std::env::var("I3RS_HTTP_TOKEN").ok());
+
+    loop {
+        let fetch = || async {
+            let mut request = REQWEST_CLIENT.get(&config.url);
+            for (name, value) in &config.headers {
+                request = request.header(name, value);
+            }
+            if let Some(username) = &config.username {
+                request = request.basic_auth(username, config.password.as_ref());
+            }
+            if let Some(token) = &bearer_token {
+                request = request.bearer_auth(token);
+            }
+            let response = request
+                .send()
+                .await
+                .error("Failed to send request")?
+                .error_for_status()
+                .error("Request failed")?;
+            let status = response.status().as_u16();
+            let body = response
+                .text()
+                .await
+                .error("Failed to read response body")?;
+            Ok::<_, Error>((status, body))
+        };
+        let (status, body) = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let text = match (&json_path, &config.regex) {
+            (Some(path), _) => {
+                let json: serde_json::Value =
+                    serde_json::from_str(&body).error("Failed to parse JSON")?;
+                match select_json(&json, path).error("JSON path not found in response")? {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                }
+            }
+            (None, Some(regex)) => {
+                let captures = regex.0.captures(&body).error("Regex did not match")?;
+                captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .unwrap()
+                    .as_str()
+                    .to_string()
+            }
+            (None, None) => body.trim().to_string(),
+        };
+
+        let mut widget = Widget::new().with_format(format.clone());
+        let value = match text.parse::<f64>() {
+            Ok(number) => {
+                widget.state = state_from_thresholds(
+                    number,
+                    config.info,
+                    config.good,
+                    config.warning,
+                    config.critical,
+                );
+                Value::number(number)
+            }
+            Err(_) => Value::text(text),
+        };
+        widget.set_values(map! {
+            "value" => value,
+            "status" => Value::number(status),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+pub(super) fn parse_json_path(path: &str) -> Result<Vec<PathSegment>> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+    for part in path.split('.').filter(|p| !p.is_empty()) {
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key.to_string()));
+        }
+        while !rest.is_empty() {
+            let (index, tail) = rest
+                .strip_prefix('[')
+                .and_then(|r| r.split_once(']'))
+                .or_error(|| format!("Invalid JSON path segment '{part}'"))?;
+            segments.push(PathSegment::Index(
+                index
+                    .parse()
+                    .or_error(|| format!("Invalid array index '{index}'"))?,
+            ));
+            rest = tail;
+        }
+    }
+    Ok(segments)
+}
+
+pub(super) fn select_json<'a>(
+    mut json: &'a serde_json::Value,
+    path: &[PathSegment],
+) -> Option<&'a serde_json::Value> {
+    for segment in path {
+        json = match segment {
+            PathSegment::Key(key) => json.get(key)?,
+            PathSegment::Index(index) => json.get(index)?,
+        };
+    }
+    Some(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_path() {
+        assert_eq!(
+            parse_json_path("$.data.items[0][1].value").unwrap(),
+            vec![
+                PathSegment::Key("data".into()),
+                PathSegment::Key("items".into()),
+                PathSegment::Index(0),
+                PathSegment::Index(1),
+                PathSegment::Key("value".into()),
+            ]
+        );
+        assert_eq!(
+            parse_json_path("count").unwrap(),
+            vec![PathSegment::Key("count".into())]
+        );
+        assert!(parse_json_path("$.items[x]").is_err());
+        assert!(parse_json_path("$.items[0").is_err());
+    }
+
+    #[test]
+    fn test_select_json() {
+        let json = serde_json::json!({"data": {"items": [{"value": 42}]}});
+        let path = parse_json_path("$.data.items[0].value").unwrap();
+        assert_eq!(select_json(&json, &path), Some(&serde_json::json!(42)));
+        let path = parse_json_path("$.data.items[1]").unwrap();
+        assert_eq!(select_json(&json, &path), None);
+    }
+}
@@ -19,12 +19,12 @@
 //! ----------------------|------------------------------|--------|---------------
 //! `temperature`         | Current temperature          | Number | -
 //!
-//! Action             | Default button
-//! -------------------|---------------
-//! `set_click_temp`   | Left
-//! `reset`            | Right
-//! `temperature_up`   | Wheel Up
-//! `temperature_down` | Wheel Down
+//! Action             | Description                               | Default button
+//! -------------------|-------------------------------------------|---------------
+//! `set_click_temp`   | Set the color temperature to `click_temp` | Left
+//! `reset`            | Reset the color temperature to `6500K`    | Right
+//! `temperature_up`   | Increase the color temperature by `step`  | Wheel Up
+//! `temperature_down` | Decrease the color temperature by `step`  | Wheel Down
 //!
 //! # Available Hue Shifters
 //!
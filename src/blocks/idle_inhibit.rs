@@ -0,0 +1,176 @@
+//! Idle inhibitor
+//!
+//! Clicking this block prevents the machine from idling (blanking or locking the screen) and from
+//! going to sleep, until it is clicked again. Like with `systemd-inhibit`, the inhibitor is held by
+//! the bar itself, so it is released when i3status-rs exits.
+//!
+//! With `driver = "logind"`, an inhibitor lock is taken from `systemd-logind`, the same as with
+//! `systemd-inhibit`. Whether an idle lock is respected depends on the idle daemon in use. With
+//! `driver = "screensaver"`, the `org.freedesktop.ScreenSaver` D-Bus API is used instead, which
+//! most desktop environments listen to.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `driver` | `"logind"` or `"screensaver"` | `"logind"`
+//! `what` | logind only: the colon separated list of what to inhibit, see `man systemd-inhibit` | `"idle:sleep"`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon "`
+//! `inhibited_state` | [`State`] (color) of this block while inhibiting | [info][State::Info]
+//!
+//! Placeholder | Value                                                            | Type | Unit
+//! ------------|------------------------------------------------------------------|------|-----
+//! `icon`      | `idle_inhibit_on` while inhibiting, `idle_inhibit_off` otherwise | Icon | -
+//! `inhibited` | Present while inhibiting                                         | Flag | -
+//!
+//! Action   | Description                   | Default button
+//! ---------|-------------------------------|---------------
+//! `toggle` | Take or release the inhibitor | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "idle_inhibit"
+//! format = " $icon {$inhibited awake|} "
+//! ```
+//!
+//! # Icons Used
+//! - `idle_inhibit_on`
+//! - `idle_inhibit_off`
+
+use zbus::zvariant::OwnedFd;
+
+use super::logind::LoginManagerProxy;
+use super::prelude::*;
+
+const APP_NAME: &str = "i3status-rs";
+const REASON: &str = "Inhibited from the bar";
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub driver: Driver,
+    #[default("idle:sleep".into())]
+    pub what: String,
+    pub format: FormatConfig,
+    #[default(State::Info)]
+    pub inhibited_state: State,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Driver {
+    #[default]
+    Logind,
+    ScreenSaver,
+}
+
+enum Backend {
+    Logind(LoginManagerProxy<'static>),
+    ScreenSaver(ScreenSaverProxy<'static>),
+}
+
+/// A held inhibitor. The logind lock is released when its file descriptor is closed.
+enum Inhibitor {
+    Logind(OwnedFd),
+    ScreenSaver(u32),
+}
+
+impl Backend {
+    async fn inhibit(&self, what: &str) -> Result<Inhibitor> {
+        Ok(match self {
+            Self::Logind(proxy) => Inhibitor::Logind(
+                proxy
+                    .inhibit(what, APP_NAME, REASON, "block")
+                    .await
+                    .error("Failed to take the inhibitor lock")?,
+            ),
+            Self::ScreenSaver(proxy) => Inhibitor::ScreenSaver(
+                proxy
+                    .inhibit(APP_NAME, REASON)
+                    .await
+                    .error("Failed to inhibit the screensaver")?,
+            ),
+        })
+    }
+
+    async fn release(&self, inhibitor: Inhibitor) -> Result<()> {
+        match (self, inhibitor) {
+            (Self::ScreenSaver(proxy), Inhibitor::ScreenSaver(cookie)) => proxy
+                .un_inhibit(cookie)
+                .await
+                .error("Failed to release the inhibitor"),
+            (_, Inhibitor::Logind(fd)) => {
+                drop(fd);
+                Ok(())
+            }
+            (_, Inhibitor::ScreenSaver(_)) => Ok(()),
+        }
+    }
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "toggle")])?;
+
+    let format = config.format.with_default(" $icon ")?;
+
+    let backend = match config.driver {
+        Driver::Logind => Backend::Logind(
+            LoginManagerProxy::new(&new_system_dbus_connection().await?)
+                .await
+                .error("Failed to create LoginManagerProxy")?,
+        ),
+        Driver::ScreenSaver => Backend::ScreenSaver(
+            ScreenSaverProxy::new(&new_dbus_connection().await?)
+                .await
+                .error("Failed to create ScreenSaverProxy")?,
+        ),
+    };
+
+    let mut inhibitor: Option<Inhibitor> = None;
+
+    loop {
+        let inhibited = inhibitor.is_some();
+        let mut widget = Widget::new().with_format(format.clone());
+        if inhibited {
+            widget.state = config.inhibited_state;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon(if inhibited { "idle_inhibit_on" } else { "idle_inhibit_off" }),
+            [if inhibited] "inhibited" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "toggle" => {
+                        inhibitor = match inhibitor.take() {
+                            Some(held) => {
+                                backend.release(held).await?;
+                                None
+                            }
+                            None => Some(backend.inhibit(&config.what).await?),
+                        };
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.ScreenSaver",
+    default_service = "org.freedesktop.ScreenSaver",
+    default_path = "/org/freedesktop/ScreenSaver"
+)]
+trait ScreenSaver {
+    fn inhibit(&self, application_name: &str, reason_for_inhibit: &str) -> zbus::Result<u32>;
+
+    fn un_inhibit(&self, cookie: u32) -> zbus::Result<()>;
+}
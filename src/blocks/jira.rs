@@ -0,0 +1,236 @@
+//! Jira issues matching a JQL query
+//!
+//! This block shows the number of Jira issues matching a JQL query, along with the first issue
+//! returned, which is the one with the highest priority when using the default query.
+//!
+//! For Jira Cloud (`*.atlassian.net`), an [API token](https://id.atlassian.com/manage-profile/security/api-tokens)
+//! is used together with `username`, the email address of the account. For Jira Server and Data
+//! Center, a personal access token is used and `username` must not be set. The token must be passed
+//! using the `I3RS_JIRA_TOKEN` environment variable or `token` configuration option.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The base URL of the Jira instance, e.g. `"https://example.atlassian.net"` | **Required**
+//! `jql` | The JQL query | `"assignee = currentUser() AND statusCategory != Done ORDER BY priority DESC, updated DESC"`
+//! `username` | The email address used for basic authentication | `None`
+//! `token` | An API token or personal access token | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $count.eng(w:1){ $key\|} \"</code>
+//! `interval` | Update interval in seconds | `300`
+//! `hide_if_zero` | Hide the block if no issues match the query | `false`
+//!
+//! Placeholder | Value                                   | Type   | Unit
+//! ------------|-----------------------------------------|--------|-----
+//! `icon`      | A static icon                           | Icon   | -
+//! `count`     | The number of matching issues           | Number | -
+//! `key`       | The key of the first issue, e.g. `AB-1` | Text   | -
+//! `summary`   | The summary of the first issue          | Text   | -
+//! `priority`  | The priority of the first issue         | Text   | -
+//!
+//! `key`, `summary` and `priority` are absent if no issues match the query. On Jira Cloud, `count`
+//! is an approximation for large numbers of issues.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "jira"
+//! url = "https://example.atlassian.net"
+//! username = "me@example.com"
+//! format = " $icon $count.eng(w:1){ $key $summary.str(max_w:20)|} "
+//! [[block.click]]
+//! button = "left"
+//! cmd = "xdg-open https://example.atlassian.net/issues/?filter=-1"
+//! ```
+//!
+//! # Icons Used
+//! - `tasks`
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub url: String,
+    #[serde(default = "default_jql")]
+    pub jql: String,
+    pub username: Option<String>,
+    pub token: Option<String>,
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default = "default_interval")]
+    pub interval: Seconds,
+    #[serde(default)]
+    pub hide_if_zero: bool,
+}
+
+fn default_jql() -> String {
+    "assignee = currentUser() AND statusCategory != Done ORDER BY priority DESC, updated DESC"
+        .into()
+}
+
+fn default_interval() -> Seconds {
+    Seconds::new(300)
+}
+
+#[derive(Deserialize, Debug)]
+struct Issue {
+    key: String,
+    fields: Fields,
+}
+
+#[derive(Deserialize, Debug)]
+struct Fields {
+    summary: String,
+    priority: Option<Priority>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Priority {
+    name: String,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $count.eng(w:1){ $key|} ")?;
+    let mut timer = config.interval.timer();
+
+    let url = config.url.trim_end_matches('/');
+    let token = config
+        .token
+        .clone()
+        .or_else(|| std::env::var("I3RS_JIRA_TOKEN").ok())
+        .error("Jira token not found")?;
+    let client = Client {
+        url,
+        username: config.username.as_deref(),
+        token: &token,
+    };
+    let is_cloud = url.ends_with(".atlassian.net");
+
+    loop {
+        let fetch = || async {
+            if is_cloud {
+                client.search_cloud(&config.jql).await
+            } else {
+                client.search_server(&config.jql).await
+            }
+        };
+        let (count, issue) = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        if count == 0 && config.hide_if_zero {
+            api.hide()?;
+        } else {
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.set_values(map! {
+                "icon" => Value::icon("tasks"),
+                "count" => Value::number(count),
+                [if let Some(issue) = &issue] "key" => Value::text(issue.key.clone()),
+                [if let Some(issue) = &issue] "summary" => Value::text(issue.fields.summary.clone()),
+                [if let Some(p) = issue.as_ref().and_then(|i| i.fields.priority.as_ref())] "priority" => Value::text(p.name.clone()),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+struct Client<'a> {
+    url: &'a str,
+    username: Option<&'a str>,
+    token: &'a str,
+}
+
+impl Client<'_> {
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = REQWEST_CLIENT.request(method, format!("{}{path}", self.url));
+        match self.username {
+            Some(username) => request.basic_auth(username, Some(self.token)),
+            None => request.bearer_auth(self.token),
+        }
+    }
+
+    /// Uses the search API of Jira Server and Data Center, which returns the total number of issues
+    async fn search_server(&self, jql: &str) -> Result<(u64, Option<Issue>)> {
+        #[derive(Deserialize)]
+        struct Response {
+            total: u64,
+            issues: Vec<Issue>,
+        }
+
+        let response: Response = self
+            .request(reqwest::Method::GET, "/rest/api/2/search")
+            .query(&[
+                ("jql", jql),
+                ("maxResults", "1"),
+                ("fields", "summary,priority"),
+            ])
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to search issues")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+        Ok((response.total, response.issues.into_iter().next()))
+    }
+
+    /// Jira Cloud's search API doesn't return the total number of issues, so it has to be
+    /// requested separately
+    async fn search_cloud(&self, jql: &str) -> Result<(u64, Option<Issue>)> {
+        #[derive(Deserialize)]
+        struct SearchResponse {
+            issues: Vec<Issue>,
+        }
+
+        #[derive(Deserialize)]
+        struct CountResponse {
+            count: u64,
+        }
+
+        let search: SearchResponse = self
+            .request(reqwest::Method::GET, "/rest/api/3/search/jql")
+            .query(&[
+                ("jql", jql),
+                ("maxResults", "1"),
+                ("fields", "summary,priority"),
+            ])
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to search issues")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+
+        // The approximate count doesn't accept ORDER BY clauses
+        let count_jql = match jql.to_ascii_lowercase().rfind("order by") {
+            Some(pos) => &jql[..pos],
+            None => jql,
+        };
+        let count: CountResponse = self
+            .request(
+                reqwest::Method::POST,
+                "/rest/api/3/search/approximate-count",
+            )
+            .json(&serde_json::json!({ "jql": count_jql.trim() }))
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to count issues")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+
+        Ok((count.count, search.issues.into_iter().next()))
+    }
+}
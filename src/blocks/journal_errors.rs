@@ -0,0 +1,170 @@
+//! The number of error messages in the systemd journal
+//!
+//! This block follows the journal using `journalctl` and counts the messages with priority
+//! `err` or higher (configurable) logged since boot, or within the last `window` seconds if it is
+//! set. The counter can be reset using the `reset` action, after which only new messages are
+//! counted.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count "`
+//! `priority` | The lowest priority to count, as understood by `journalctl --priority` | `"err"`
+//! `units` | Only count messages from these units | `[]`
+//! `user` | Read the user journal instead of the system one | `false`
+//! `window` | Only count messages logged in the last `window` seconds | `None`
+//! `hide_if_zero` | Hide the block if the counter is zero | `false`
+//!
+//! Placeholder | Value                               | Type   | Unit
+//! ------------|-------------------------------------|--------|-----
+//! `icon`      | A static icon                       | Icon   | -
+//! `count`     | The number of counted messages      | Number | -
+//!
+//! The block is set to the critical state if the counter is not zero.
+//!
+//! Action  | Description                   | Default button
+//! --------|-------------------------------|---------------
+//! `reset` | Reset the counter             | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "journal_errors"
+//! units = ["nginx.service", "postgresql.service"]
+//! window = 3600
+//! hide_if_zero = true
+//! ```
+//!
+//! # Icons Used
+//! - `warning`
+
+use super::prelude::*;
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::BufReader;
+use tokio::process::Command;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default("err".into())]
+    pub priority: String,
+    pub units: Vec<String>,
+    pub user: bool,
+    pub window: Option<Seconds<false>>,
+    pub hide_if_zero: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct Entry {
+    #[serde(rename = "__CURSOR")]
+    cursor: String,
+    #[serde(rename = "__REALTIME_TIMESTAMP")]
+    timestamp: String,
+}
+
+impl Entry {
+    fn timestamp_us(&self) -> u64 {
+        self.timestamp.parse().unwrap_or_default()
+    }
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "reset")])?;
+
+    let format = config.format.with_default(" $icon $count ")?;
+
+    let mut args = vec![
+        "--boot".to_string(),
+        format!("--priority={}", config.priority),
+        "--output=json".into(),
+        "--no-pager".into(),
+    ];
+    if config.user {
+        args.push("--user".into());
+    }
+    for unit in &config.units {
+        args.push(format!("--unit={unit}"));
+    }
+
+    // Count the messages logged so far, then follow the journal from the last one
+    let output = Command::new("journalctl")
+        .args(&args)
+        .output()
+        .await
+        .error("Failed to run journalctl")?;
+    let mut timestamps = VecDeque::new();
+    let mut cursor = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let entry: Entry = serde_json::from_str(line).error("Failed to parse journal entry")?;
+        timestamps.push_back(entry.timestamp_us());
+        cursor = Some(entry.cursor);
+    }
+
+    args.push("--follow".into());
+    match cursor {
+        Some(cursor) => {
+            args.push(format!("--after-cursor={cursor}"));
+            args.push("--lines=all".into());
+        }
+        None => args.push("--lines=0".into()),
+    }
+    let mut child = Command::new("journalctl")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .error("Failed to run journalctl")?;
+    let mut reader = BufReader::new(child.stdout.take().unwrap()).lines();
+
+    let mut timer = tokio::time::interval(Duration::from_secs(10));
+
+    loop {
+        if let Some(window) = config.window {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_micros() as u64;
+            let oldest = now.saturating_sub(window.0.as_micros() as u64);
+            while timestamps.front().is_some_and(|&t| t < oldest) {
+                timestamps.pop_front();
+            }
+        }
+
+        if timestamps.is_empty() && config.hide_if_zero {
+            api.hide()?;
+        } else {
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = if timestamps.is_empty() {
+                State::Idle
+            } else {
+                State::Critical
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("warning"),
+                "count" => Value::number(timestamps.len()),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            line = reader.next_line() => {
+                let line = line
+                    .error("Failed to read journalctl output")?
+                    .error("journalctl exited unexpectedly")?;
+                let entry: Entry = serde_json::from_str(&line).error("Failed to parse journal entry")?;
+                timestamps.push_back(entry.timestamp_us());
+            }
+            _ = timer.tick(), if config.window.is_some() => (),
+            Some(action) = actions.recv() => match action.as_ref() {
+                "reset" => timestamps.clear(),
+                _ => (),
+            }
+        }
+    }
+}
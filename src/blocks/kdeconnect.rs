@@ -30,6 +30,10 @@
 //! `notif_count`      | Number of notifications on your phone (only when connected and non-zero) | Number | -
 //! `name`             | Name of your device as reported by KDEConnect (if available)             | Text   | -
 //!
+//! Action | Description                                              | Default button
+//! -------|----------------------------------------------------------|---------------
+//! `ring` | Make the device ring (requires the Find My Phone plugin) | Left
+//!
 //! # Example
 //!
 //! Do not show the name, do not set the "good" state.
@@ -89,6 +93,9 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         config.bat_critical,
     ) != (0, 0, 0, 0);
 
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "ring")])?;
+
     let mut monitor = DeviceMonitor::new(config.device_id.clone()).await?;
 
     loop {
@@ -177,7 +184,18 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             }
         }
 
-        monitor.wait_for_change().await?;
+        let action = select! {
+            res = monitor.wait_for_change() => {
+                res?;
+                None
+            }
+            Some(action) = actions.recv() => Some(action),
+        };
+        if action.as_deref() == Some("ring") {
+            if let Some(device) = &monitor.device {
+                device.ring().await?;
+            }
+        }
     }
 }
 
@@ -429,6 +447,21 @@ impl Device {
             .unwrap_or(0)
     }
 
+    async fn ring(&self) -> Result<()> {
+        FindMyPhoneDbusProxy::builder(self.device_proxy.inner().connection())
+            .path(format!(
+                "/modules/kdeconnect/devices/{}/findmyphone",
+                self.id
+            ))
+            .error("Failed to set findmyphone path")?
+            .build()
+            .await
+            .error("Failed to create FindMyPhoneDbusProxy")?
+            .ring()
+            .await
+            .error("Failed to ring the device")
+    }
+
     async fn network(&self) -> (Option<String>, i32) {
         let (ty, strength) = tokio::join!(
             self.connectivity_proxy.cellular_network_type(),
@@ -489,3 +522,12 @@ trait NotificationsDbus {
     #[zbus(signal, name = "notificationRemoved")]
     fn notification_removed(&self, id: &str) -> zbus::Result<()>;
 }
+
+#[zbus::proxy(
+    interface = "org.kde.kdeconnect.device.findmyphone",
+    default_service = "org.kde.kdeconnect"
+)]
+trait FindMyPhoneDbus {
+    #[zbus(name = "ring")]
+    fn ring(&self) -> zbus::Result<()>;
+}
@@ -0,0 +1,106 @@
+//! Running kernel version and pending reboot detection
+//!
+//! This block shows the version of the running kernel and detects whether a reboot is required
+//! to finish an upgrade. A reboot is considered required if any of the following is true:
+//!
+//! - The modules directory of the running kernel (`<version>` in `/usr/lib/modules` or
+//!   `/lib/modules`) no longer exists, which happens when the running kernel has been upgraded on
+//!   e.g. Arch Linux. This check is skipped on systems which keep their modules elsewhere.
+//! - `/run/reboot-required` exists, which is created by Debian and Ubuntu when the kernel or core
+//!   libraries are upgraded.
+//! - `needs_restarting = true` and `needs-restarting -r` reports that a reboot is required (Fedora,
+//!   RHEL and derivatives).
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $kernel{ $icon\|} "`
+//! `interval` | Update interval in seconds | `600`
+//! `needs_restarting` | Also check `needs-restarting -r` | `false`
+//!
+//! Placeholder       | Value                                           | Type   | Unit
+//! ------------------|-------------------------------------------------|--------|-----
+//! `icon`            | A static icon (absent if no reboot is required) | Icon   | -
+//! `kernel`          | The version of the running kernel               | Text   | -
+//! `reboot_required` | Present if a reboot is required                 | Flag   | -
+//!
+//! The block is set to the warning state if a reboot is required.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "kernel"
+//! format = " $kernel{ $reboot_required reboot!|} "
+//! ```
+//!
+//! # Icons Used
+//! - `update`
+
+use super::prelude::*;
+use crate::util::read_file;
+use std::path::Path;
+use tokio::process::Command;
+
+const MODULES_DIRS: [&str; 2] = ["/usr/lib/modules", "/lib/modules"];
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(600.into())]
+    pub interval: Seconds,
+    pub needs_restarting: bool,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $kernel{ $icon|} ")?;
+    let mut timer = config.interval.timer();
+
+    let kernel = read_file("/proc/sys/kernel/osrelease")
+        .await
+        .error("Failed to read kernel version")?;
+
+    loop {
+        let mut reboot_required =
+            modules_removed(&kernel) || Path::new("/run/reboot-required").exists();
+        if config.needs_restarting && !reboot_required {
+            // `needs-restarting -r` exits with 1 if a reboot is required
+            reboot_required = Command::new("needs-restarting")
+                .arg("-r")
+                .output()
+                .await
+                .error("Failed to run needs-restarting")?
+                .status
+                .code()
+                == Some(1);
+        }
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if reboot_required {
+            State::Warning
+        } else {
+            State::Idle
+        };
+        widget.set_values(map! {
+            "kernel" => Value::text(kernel.clone()),
+            [if reboot_required] "icon" => Value::icon("update"),
+            [if reboot_required] "reboot_required" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Whether there is a modules directory, but not for the running kernel
+fn modules_removed(kernel: &str) -> bool {
+    MODULES_DIRS.iter().any(|dir| Path::new(dir).is_dir())
+        && !MODULES_DIRS
+            .iter()
+            .any(|dir| Path::new(dir).join(kernel).exists())
+}
@@ -0,0 +1,164 @@
+//! Current Kubernetes context and pod health
+//!
+//! This block shows the current `kubectl` context and namespace, and the number of pods in that
+//! namespace which are not ready. Pods which have completed successfully are not counted. The
+//! `kubectl` binary must be available in `PATH`. The `KUBECONFIG` environment variable is
+//! respected.
+//!
+//! The block is set to the critical state if any pod is not ready, or if the current context is
+//! listed in `critical_contexts`, so you always know when you are pointed at production.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $context{ ($namespace)\|}{ $not_ready\|} "`
+//! `interval` | Update interval in seconds | `30`
+//! `namespace` | The namespace to check pods in. Defaults to the namespace of the current context. | `None`
+//! `critical_contexts` | A list of contexts for which the block is always critical | `[]`
+//!
+//! Placeholder | Value                                                        | Type   | Unit
+//! ------------|--------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                | Icon   | -
+//! `context`   | The current context                                          | Text   | -
+//! `namespace` | The checked namespace                                        | Text   | -
+//! `pods`      | The number of pods in the namespace                          | Number | -
+//! `not_ready` | The number of pods which are not ready (absent if all are)   | Number | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "kubernetes"
+//! format = " $icon $context/$namespace $pods{ ($not_ready not ready)|} "
+//! critical_contexts = ["prod"]
+//! ```
+//!
+//! # Icons Used
+//! - `kubernetes`
+
+use super::prelude::*;
+use tokio::process::Command;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(30.into())]
+    pub interval: Seconds,
+    pub namespace: Option<String>,
+    pub critical_contexts: Vec<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $context{ ($namespace)|}{ $not_ready|} ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let kubeconfig: KubeConfig = kubectl(&["config", "view", "--minify", "-o", "json"]).await?;
+        let context = kubeconfig
+            .contexts
+            .into_iter()
+            .next()
+            .error("No current context")?;
+        let namespace = config
+            .namespace
+            .clone()
+            .or(context.context.namespace)
+            .unwrap_or_else(|| "default".into());
+
+        let pods: PodList = kubectl(&["get", "pods", "-n", &namespace, "-o", "json"]).await?;
+        let not_ready = pods.items.iter().filter(|pod| !pod.is_ready()).count();
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if not_ready > 0 || config.critical_contexts.contains(&context.name) {
+            State::Critical
+        } else {
+            State::Idle
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("kubernetes"),
+            "context" => Value::text(context.name),
+            "namespace" => Value::text(namespace),
+            "pods" => Value::number(pods.items.len()),
+            [if not_ready > 0] "not_ready" => Value::number(not_ready),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn kubectl<T: serde::de::DeserializeOwned>(args: &[&str]) -> Result<T> {
+    let output = Command::new("kubectl")
+        .args(args)
+        .args(["--request-timeout", "10s"])
+        .output()
+        .await
+        .error("Failed to run kubectl")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "kubectl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    serde_json::from_slice(&output.stdout).error("Failed to parse kubectl output")
+}
+
+#[derive(Deserialize, Debug)]
+struct KubeConfig {
+    #[serde(default)]
+    contexts: Vec<NamedContext>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NamedContext {
+    name: String,
+    context: Context,
+}
+
+#[derive(Deserialize, Debug)]
+struct Context {
+    namespace: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PodList {
+    items: Vec<Pod>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Pod {
+    status: PodStatus,
+}
+
+#[derive(Deserialize, Debug)]
+struct PodStatus {
+    phase: Option<String>,
+    #[serde(default)]
+    conditions: Vec<PodCondition>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PodCondition {
+    #[serde(rename = "type")]
+    kind: String,
+    status: String,
+}
+
+impl Pod {
+    fn is_ready(&self) -> bool {
+        self.status.phase.as_deref() == Some("Succeeded")
+            || self
+                .status
+                .conditions
+                .iter()
+                .any(|c| c.kind == "Ready" && c.status == "True")
+    }
+}
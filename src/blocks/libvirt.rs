@@ -0,0 +1,116 @@
+//! Running libvirt virtual machines
+//!
+//! This block shows the number of running libvirt domains using the `virsh` command line tool.
+//! If `domain` is set, that domain can be started and shut down using the `start`, `shutdown`
+//! and `toggle` actions.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $running "`
+//! `interval` | Update interval in seconds | `10`
+//! `uri` | The libvirt connection URI | `"qemu:///system"`
+//! `domain` | The domain to control using the actions | `None`
+//!
+//! Placeholder | Value                                                  | Type   | Unit
+//! ------------|--------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                          | Icon   | -
+//! `running`   | The number of running domains                          | Number | -
+//! `names`     | A comma separated list of running domains              | Text   | -
+//! `active`    | Present if `domain` is running                         | Flag   | -
+//!
+//! Action     | Description                        | Default button
+//! -----------|------------------------------------|---------------
+//! `start`    | Start `domain`                     | -
+//! `shutdown` | Gracefully shut down `domain`      | -
+//! `toggle`   | Start or shut down `domain`        | Left (if `domain` is set)
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "libvirt"
+//! domain = "win10"
+//! format = " $icon $running{ ($names)|} "
+//! ```
+//!
+//! # Icons Used
+//! - `vm`
+
+use super::prelude::*;
+use tokio::process::Command;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+    #[default("qemu:///system".into())]
+    pub uri: String,
+    pub domain: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    if config.domain.is_some() {
+        api.set_default_actions(&[(MouseButton::Left, None, "toggle")])?;
+    }
+
+    let format = config.format.with_default(" $icon $running ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let running = virsh(&config.uri, &["list", "--name"]).await?;
+        let running: Vec<&str> = running.lines().filter(|l| !l.is_empty()).collect();
+        let active = config
+            .domain
+            .as_deref()
+            .is_some_and(|d| running.contains(&d));
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if active { State::Info } else { State::Idle };
+        widget.set_values(map! {
+            "icon" => Value::icon("vm"),
+            "running" => Value::number(running.len()),
+            "names" => Value::text(running.join(", ")),
+            [if active] "active" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => {
+                    let Some(domain) = &config.domain else { continue };
+                    let command = match action.as_ref() {
+                        "start" => "start",
+                        "shutdown" => "shutdown",
+                        "toggle" => if active { "shutdown" } else { "start" },
+                        _ => continue,
+                    };
+                    virsh(&config.uri, &[command, domain]).await?;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn virsh(uri: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("virsh")
+        .args(["--connect", uri])
+        .args(args)
+        .output()
+        .await
+        .error("Failed to run virsh")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "virsh failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).error("virsh produced non-UTF8 output")
+}
@@ -0,0 +1,78 @@
+//! D-Bus proxies of logind, shared by the `idle_inhibit`, `ssh_sessions` and `users`
+//! blocks
+
+use zbus::zvariant::{OwnedFd, OwnedObjectPath};
+
+use super::prelude::*;
+
+/// A logind session
+pub(super) struct SessionInfo {
+    pub user: String,
+    pub remote: bool,
+    pub remote_host: String,
+    pub service: String,
+    pub class: String,
+}
+
+pub(super) async fn list_sessions(manager: &LoginManagerProxy<'_>) -> Result<Vec<SessionInfo>> {
+    let mut sessions = Vec::new();
+    for (_id, _uid, user, _seat, path) in manager
+        .list_sessions()
+        .await
+        .error("Failed to list sessions")?
+    {
+        let session = SessionProxy::builder(manager.inner().connection())
+            .path(path)
+            .error("Could not set path")?
+            .build()
+            .await
+            .error("Failed to create SessionProxy")?;
+        // The session may be closed in the meantime
+        let Ok(remote) = session.remote().await else {
+            continue;
+        };
+        sessions.push(SessionInfo {
+            user,
+            remote,
+            remote_host: session.remote_host().await.unwrap_or_default(),
+            service: session.service().await.unwrap_or_default(),
+            class: session.class().await.unwrap_or_default(),
+        });
+    }
+    Ok(sessions)
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+pub(super) trait LoginManager {
+    fn list_sessions(&self) -> zbus::Result<Vec<(String, u32, String, String, OwnedObjectPath)>>;
+
+    fn inhibit(&self, what: &str, who: &str, why: &str, mode: &str) -> zbus::Result<OwnedFd>;
+
+    #[zbus(signal)]
+    fn session_new(&self, id: String, path: OwnedObjectPath) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn session_removed(&self, id: String, path: OwnedObjectPath) -> zbus::Result<()>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+trait Session {
+    #[zbus(property)]
+    fn remote(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn remote_host(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn service(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn class(&self) -> zbus::Result<String>;
+}
@@ -1,5 +1,9 @@
 //! Unread mail. Only supports maildir format.
 //!
+//! The inboxes are watched using inotify, so the block is updated as soon as mail is delivered,
+//! e.g. by `mbsync` or `offlineimap`, or read. No network requests are made. Inboxes matching a
+//! glob which are created after the block has started are not picked up until it is restarted.
+//!
 //! Note that you need to enable `maildir` feature to use this block:
 //! ```sh
 //! cargo build --release --features maildir
@@ -13,7 +17,7 @@
 //! `inboxes` | List of maildir inboxes to look for mails in. Supports path/glob expansions (e.g. `~` and `*`). | **Required**
 //! `threshold_warning` | Number of unread mails where state is set to warning. | `1`
 //! `threshold_critical` | Number of unread mails where state is set to critical. | `10`
-//! `interval` | Update interval, in seconds. | `5`
+//! `interval` | Update interval, in seconds. Updates are also triggered by changes in the inboxes. | `60`
 //! `display_type` | Which part of the maildir to count: `"new"`, `"cur"`, or `"all"`. | `"new"`
 //!
 //! Placeholder  | Value                  | Type   | Unit
@@ -37,6 +41,8 @@
 //! - `mail`
 
 use super::prelude::*;
+use debounced::debounced;
+use inotify::{Inotify, WatchMask};
 use maildir::Maildir;
 use std::path::PathBuf;
 
@@ -44,7 +50,7 @@ use std::path::PathBuf;
 #[serde(deny_unknown_fields, default)]
 pub struct Config {
     pub format: FormatConfig,
-    #[default(5.into())]
+    #[default(60.into())]
     pub interval: Seconds,
     pub inboxes: Vec<String>,
     #[default(1)]
@@ -64,11 +70,36 @@ fn expand_inbox(inbox: &str) -> Result<impl Iterator<Item = PathBuf>> {
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let format = config.format.with_default(" $icon $status ")?;
 
-    let mut inboxes = Vec::with_capacity(config.inboxes.len());
+    let mut paths = Vec::with_capacity(config.inboxes.len());
     for inbox in &config.inboxes {
-        inboxes.extend(expand_inbox(inbox)?.map(Maildir::from));
+        paths.extend(expand_inbox(inbox)?);
     }
 
+    let notify = Inotify::init().error("Failed to start inotify")?;
+    for path in &paths {
+        for subdir in ["new", "cur"] {
+            notify
+                .watches()
+                .add(
+                    path.join(subdir),
+                    WatchMask::CREATE
+                        | WatchMask::DELETE
+                        | WatchMask::MOVED_TO
+                        | WatchMask::MOVED_FROM,
+                )
+                .or_error(|| format!("Failed to watch {}", path.display()))?;
+        }
+    }
+    // Syncing a mailbox may cause lots of events, so don't recount after every single one
+    let mut updates = debounced(
+        notify
+            .into_event_stream([0; 1024])
+            .error("Failed to create event stream")?,
+        Duration::from_millis(200),
+    );
+
+    let inboxes: Vec<Maildir> = paths.into_iter().map(Maildir::from).collect();
+
     loop {
         let mut newmails = 0;
         for inbox in &inboxes {
@@ -96,6 +127,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
 
         select! {
             _ = sleep(config.interval.0) => (),
+            _ = updates.next() => (),
             _ = api.wait_for_update_request() => (),
         }
     }
@@ -0,0 +1,184 @@
+//! Linux software RAID status
+//!
+//! This block reads `/proc/mdstat` and shows the state of an md array, including the progress of
+//! a running resync, recovery, check or reshape. The block is set to the critical state if the
+//! array is degraded or inactive and to the info state while it is being synced.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `device` | The name of the array | `"md0"`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $device $active_devices/$total_devices{ $action $progress\|} "`
+//! `interval` | Update interval in seconds | `10`
+//!
+//! Placeholder      | Value                                                               | Type   | Unit
+//! -----------------|---------------------------------------------------------------------|--------|-----
+//! `icon`           | A static icon                                                       | Icon   | -
+//! `device`         | The name of the array                                               | Text   | -
+//! `level`          | The RAID level, e.g. `raid1`                                        | Text   | -
+//! `state`          | The state of the array, e.g. `active`                               | Text   | -
+//! `total_devices`  | The number of devices in the array                                  | Number | -
+//! `active_devices` | The number of working devices in the array                          | Number | -
+//! `degraded`       | Present if the array is degraded                                    | Flag   | -
+//! `action`         | The running operation, e.g. `resync` or `recovery` (absent if none) | Text   | -
+//! `progress`       | The progress of the running operation (absent if none)              | Number | %
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "mdstat"
+//! device = "md127"
+//! format = " $icon $level{ $degraded DEGRADED|}{ $action $progress|} "
+//! ```
+//!
+//! # Icons Used
+//! - `disk_drive`
+
+use super::prelude::*;
+use crate::util::read_file;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("md0".into())]
+    pub device: String,
+    pub format: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $device $active_devices/$total_devices{ $action $progress|} ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let mdstat = read_file("/proc/mdstat")
+            .await
+            .error("Failed to read /proc/mdstat")?;
+        let array = parse_mdstat(&mdstat, &config.device)
+            .or_error(|| format!("Array {} not found", config.device))?;
+        let degraded = array.active_devices < array.total_devices;
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if degraded || array.state != "active" {
+            State::Critical
+        } else if array.action.is_some() {
+            State::Info
+        } else {
+            State::Idle
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("disk_drive"),
+            "device" => Value::text(config.device.clone()),
+            "level" => Value::text(array.level),
+            "state" => Value::text(array.state),
+            "total_devices" => Value::number(array.total_devices),
+            "active_devices" => Value::number(array.active_devices),
+            [if degraded] "degraded" => Value::flag(),
+            [if let Some((action, _)) = &array.action] "action" => Value::text(action.clone()),
+            [if let Some((_, progress)) = array.action] "progress" => Value::percents(progress),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct MdArray {
+    state: String,
+    level: String,
+    total_devices: u32,
+    active_devices: u32,
+    action: Option<(String, f64)>,
+}
+
+fn parse_mdstat(mdstat: &str, device: &str) -> Option<MdArray> {
+    let mut lines = mdstat
+        .lines()
+        .skip_while(|l| l.split(" : ").next() != Some(device));
+
+    // e.g. "md0 : active raid1 sdb1[1] sda1[0]"
+    let mut header = lines.next()?.split(" : ").nth(1)?.split_whitespace();
+    let state = header.next()?.to_string();
+    let level = header
+        .find(|w| w.starts_with("raid") || *w == "linear")
+        .unwrap_or_default()
+        .to_string();
+
+    let mut array = MdArray {
+        state,
+        level,
+        total_devices: 0,
+        active_devices: 0,
+        action: None,
+    };
+
+    // The details of an array are indented and end with an empty line
+    for line in lines.take_while(|l| l.starts_with(char::is_whitespace)) {
+        // e.g. "      1953381376 blocks super 1.2 [2/1] [U_]"
+        if let Some((total, active)) = line
+            .split_whitespace()
+            .find_map(|w| w.strip_prefix('[')?.strip_suffix(']')?.split_once('/'))
+        {
+            array.total_devices = total.parse().ok()?;
+            array.active_devices = active.parse().ok()?;
+        }
+        // e.g. "      [=>....]  recovery =  8.5% (166208000/1953381376) finish=150.5min"
+        if let Some((before, after)) = line.split_once(" = ") {
+            let action = before.split_whitespace().last()?.to_string();
+            let progress = after.split_whitespace().next()?.strip_suffix('%')?;
+            array.action = Some((action, progress.parse().ok()?));
+        }
+    }
+
+    Some(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MDSTAT: &str = "Personalities : [raid1] [raid6]
+md1 : active raid6 sde1[3] sdd1[2] sdc1[1] sdb1[0]
+      3906764800 blocks super 1.2 level 6, 512k chunk, algorithm 2 [4/4] [UUUU]
+      bitmap: 0/15 pages [0KB], 65536KB chunk
+
+md0 : active raid1 sdb1[2] sda1[0]
+      1953381376 blocks super 1.2 [2/1] [U_]
+      [=>...................]  recovery =  8.5% (166208000/1953381376) finish=150.5min speed=197876K/sec
+
+unused devices: <none>";
+
+    #[test]
+    fn test_parse_mdstat() {
+        assert_eq!(
+            parse_mdstat(MDSTAT, "md1"),
+            Some(MdArray {
+                state: "active".into(),
+                level: "raid6".into(),
+                total_devices: 4,
+                active_devices: 4,
+                action: None,
+            })
+        );
+        assert_eq!(
+            parse_mdstat(MDSTAT, "md0"),
+            Some(MdArray {
+                state: "active".into(),
+                level: "raid1".into(),
+                total_devices: 2,
+                active_devices: 1,
+                action: Some(("recovery".into(), 8.5)),
+            })
+        );
+        assert_eq!(parse_mdstat(MDSTAT, "md2"), None);
+    }
+}
@@ -0,0 +1,369 @@
+//! The current song of MPD
+//!
+//! This block talks to the [Music Player Daemon](https://www.musicpd.org/) using its own
+//! [protocol](https://mpd.readthedocs.io/en/latest/protocol.html), so neither `mpc` nor an MPRIS
+//! bridge is needed. The block is updated as soon as MPD reports a change, using the `idle`
+//! command.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `address` | The `host:port` of the server, or the path to its socket | `"localhost:6600"`
+//! `password` | The password of the server | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$combo.str(max_w:25,rot_interval:0.5) \|}\"</code>
+//! `separator` | String to insert between artist and title | `" - "`
+//! `volume_step` | The percentage by which the volume is changed when scrolling | `5`
+//!
+//! Placeholder | Value                                                                   | Type   | Unit
+//! ------------|-------------------------------------------------------------------------|--------|--------
+//! `icon`      | A static icon                                                           | Icon   | -
+//! `state`     | `play`, `pause` or `stop`                                               | Text   | -
+//! `title`     | The title of the current song                                           | Text   | -
+//! `artist`    | The artist of the current song                                          | Text   | -
+//! `album`     | The album of the current song                                           | Text   | -
+//! `combo`     | `$artist[separator]$title`, the title, the stream name or the file name | Text   | -
+//! `elapsed`   | The elapsed time of the current song                                    | Number | Seconds
+//! `duration`  | The duration of the current song                                        | Number | Seconds
+//! `volume`    | The volume of MPD (absent if it has no mixer)                           | Number | %
+//!
+//! All placeholders except `icon`, `state` and `volume` are absent while MPD is stopped or when
+//! not known, e.g. streams usually have no artist or duration.
+//!
+//! Action        | Description            | Default button
+//! --------------|------------------------|---------------
+//! `play_pause`  | Toggle playback        | Left
+//! `next`        | Play the next song     | Right
+//! `prev`        | Play the previous song | -
+//! `volume_up`   | Increase the volume    | Wheel Up
+//! `volume_down` | Decrease the volume    | Wheel Down
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "mpd"
+//! address = "~/.local/run/mpd/socket"
+//! format = " $icon {$title.str(max_w:20) ($elapsed.eng(w:3)/$duration.eng(w:3))|} "
+//! [[block.click]]
+//! button = "middle"
+//! action = "prev"
+//! ```
+//!
+//! # Icons Used
+//! - `music`
+
+use std::time::Instant;
+
+use tokio::io::{
+    AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines, ReadHalf, WriteHalf,
+};
+use tokio::net::{TcpStream, UnixStream};
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("localhost:6600".into())]
+    pub address: ShellString,
+    pub password: Option<String>,
+    pub format: FormatConfig,
+    #[default(" - ".into())]
+    pub separator: String,
+    #[default(5)]
+    pub volume_step: u8,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "play_pause"),
+        (MouseButton::Right, None, "next"),
+        (MouseButton::WheelUp, None, "volume_up"),
+        (MouseButton::WheelDown, None, "volume_down"),
+    ])?;
+
+    let format = config
+        .format
+        .with_default(" $icon {$combo.str(max_w:25,rot_interval:0.5) |}")?;
+    let address = config.address.expand()?;
+    let password = config.password.as_deref();
+
+    // A separate connection waits for changes, since no other commands may be sent while idle
+    let mut idle = Connection::connect(&address, password).await?;
+    idle.send(IDLE).await?;
+
+    loop {
+        let mut conn = Connection::connect(&address, password).await?;
+        let status = Status::from_pairs(&conn.command("status").await?);
+        let song = Song::from_pairs(&conn.command("currentsong").await?);
+        drop(conn);
+        let fetched_at = Instant::now();
+
+        loop {
+            let playing = status.state == "play";
+            let stopped = status.state == "stop";
+            // MPD only reports the elapsed time when asked, so it is counted here while playing
+            let elapsed = status.elapsed.map(|e| {
+                if playing {
+                    e + fetched_at.elapsed().as_secs_f64()
+                } else {
+                    e
+                }
+            });
+
+            let mut widget = Widget::new().with_format(format.clone());
+            if playing {
+                widget.state = State::Info;
+            }
+            let mut values = map! {
+                "icon" => Value::icon("music"),
+                "state" => Value::text(status.state.clone()),
+                [if let Some(v) = status.volume] "volume" => Value::percents(v),
+            };
+            if !stopped {
+                values.extend(map! {
+                    [if let Some(t) = &song.title] "title" => Value::text(t.clone()),
+                    [if let Some(a) = &song.artist] "artist" => Value::text(a.clone()),
+                    [if let Some(a) = &song.album] "album" => Value::text(a.clone()),
+                    [if let Some(c) = song.combo(&config.separator)] "combo" => Value::text(c),
+                    [if let Some(e) = elapsed] "elapsed" => Value::seconds(e.floor()),
+                    [if let Some(d) = status.duration] "duration" => Value::seconds(d.floor()),
+                });
+            }
+            widget.set_values(values);
+            api.set_widget(widget)?;
+
+            select! {
+                line = idle.reader.next_line() => {
+                    let line = line
+                        .error("Failed to read from MPD")?
+                        .error("MPD closed the connection")?;
+                    // The response is a list of "changed: <subsystem>" lines, ending with OK
+                    if line == "OK" {
+                        idle.send(IDLE).await?;
+                        break;
+                    }
+                    if line.starts_with("ACK ") {
+                        return Err(Error::new(format!("MPD error: {line}")));
+                    }
+                }
+                _ = sleep(Duration::from_secs(1)), if playing => (),
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => {
+                    let command = match action.as_ref() {
+                        "play_pause" if playing => "pause 1".into(),
+                        "play_pause" if stopped => "play".into(),
+                        "play_pause" => "pause 0".into(),
+                        "next" => "next".into(),
+                        "prev" => "previous".into(),
+                        "volume_up" | "volume_down" => {
+                            let Some(volume) = status.volume else {
+                                continue;
+                            };
+                            let step = config.volume_step as f64;
+                            let volume = if action.as_ref() == "volume_up" {
+                                volume + step
+                            } else {
+                                volume - step
+                            };
+                            format!("setvol {}", volume.clamp(0.0, 100.0))
+                        }
+                        _ => continue,
+                    };
+                    // The idle connection reports the change
+                    Connection::connect(&address, password)
+                        .await?
+                        .command(&command)
+                        .await?;
+                }
+            }
+        }
+    }
+}
+
+const IDLE: &str = "idle player mixer options";
+
+trait Stream: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Stream for T {}
+
+struct Connection {
+    reader: Lines<BufReader<ReadHalf<Box<dyn Stream>>>>,
+    writer: WriteHalf<Box<dyn Stream>>,
+}
+
+impl Connection {
+    async fn connect(address: &str, password: Option<&str>) -> Result<Self> {
+        let stream: Box<dyn Stream> = if address.starts_with('/') {
+            Box::new(
+                UnixStream::connect(address)
+                    .await
+                    .error("Failed to connect to MPD")?,
+            )
+        } else {
+            Box::new(
+                TcpStream::connect(address)
+                    .await
+                    .error("Failed to connect to MPD")?,
+            )
+        };
+        let (reader, writer) = tokio::io::split(stream);
+        let mut conn = Self {
+            reader: BufReader::new(reader).lines(),
+            writer,
+        };
+
+        let greeting = conn
+            .reader
+            .next_line()
+            .await
+            .error("Failed to read from MPD")?
+            .error("MPD closed the connection")?;
+        if !greeting.starts_with("OK MPD ") {
+            return Err(Error::new(format!("Unexpected greeting: {greeting}")));
+        }
+        if let Some(password) = password {
+            conn.command(&format!("password {}", quote(password)))
+                .await?;
+        }
+        Ok(conn)
+    }
+
+    async fn send(&mut self, command: &str) -> Result<()> {
+        self.writer
+            .write_all(format!("{command}\n").as_bytes())
+            .await
+            .error("Failed to write to MPD")
+    }
+
+    /// Sends a command and returns the key-value pairs of the response
+    async fn command(&mut self, command: &str) -> Result<Vec<(String, String)>> {
+        self.send(command).await?;
+        let mut pairs = Vec::new();
+        loop {
+            let line = self
+                .reader
+                .next_line()
+                .await
+                .error("Failed to read from MPD")?
+                .error("MPD closed the connection")?;
+            if line == "OK" {
+                return Ok(pairs);
+            }
+            if line.starts_with("ACK ") {
+                return Err(Error::new(format!("MPD error: {line}")));
+            }
+            if let Some((key, value)) = line.split_once(": ") {
+                pairs.push((key.into(), value.into()));
+            }
+        }
+    }
+}
+
+/// Quotes an argument of a command
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn get<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+#[derive(Debug, PartialEq)]
+struct Status {
+    state: String,
+    /// Absent if MPD has no mixer
+    volume: Option<f64>,
+    elapsed: Option<f64>,
+    duration: Option<f64>,
+}
+
+impl Status {
+    fn from_pairs(pairs: &[(String, String)]) -> Self {
+        Self {
+            state: get(pairs, "state").unwrap_or("stop").into(),
+            // -1 means that there is no mixer
+            volume: get(pairs, "volume")
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v >= 0.0),
+            elapsed: get(pairs, "elapsed").and_then(|e| e.parse().ok()),
+            duration: get(pairs, "duration").and_then(|d| d.parse().ok()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Song {
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    /// The name of the stream
+    name: Option<String>,
+    file: Option<String>,
+}
+
+impl Song {
+    fn from_pairs(pairs: &[(String, String)]) -> Self {
+        let get = |key| get(pairs, key).map(String::from);
+        Self {
+            title: get("Title"),
+            artist: get("Artist"),
+            album: get("Album"),
+            name: get("Name"),
+            file: get("file"),
+        }
+    }
+
+    fn combo(&self, separator: &str) -> Option<String> {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => Some(format!("{artist}{separator}{title}")),
+            (None, Some(title)) => Some(title.clone()),
+            _ => self.name.clone().or_else(|| {
+                let file = self.file.as_deref()?;
+                Some(file.rsplit('/').next().unwrap_or(file).into())
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(s: &str) -> Vec<(String, String)> {
+        s.lines()
+            .filter_map(|l| l.split_once(": "))
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect()
+    }
+
+    #[test]
+    fn test_status() {
+        let status = Status::from_pairs(&pairs(
+            "volume: -1\nrepeat: 0\nstate: play\nsong: 3\nelapsed: 12.345\nduration: 241.5",
+        ));
+        assert_eq!(
+            status,
+            Status {
+                state: "play".into(),
+                volume: None,
+                elapsed: Some(12.345),
+                duration: Some(241.5),
+            }
+        );
+    }
+
+    #[test]
+    fn test_song_combo() {
+        let song = Song::from_pairs(&pairs("file: music/a/b.flac\nArtist: A\nTitle: B"));
+        assert_eq!(song.combo(" - ").as_deref(), Some("A - B"));
+        let song = Song::from_pairs(&pairs("file: music/a/b.flac"));
+        assert_eq!(song.combo(" - ").as_deref(), Some("b.flac"));
+        let song = Song::from_pairs(&pairs("file: http://radio/x\nName: Radio X"));
+        assert_eq!(song.combo(" - ").as_deref(), Some("Radio X"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Availability of network mounts
+//!
+//! This block checks whether the configured mount points (e.g. NFS, CIFS or sshfs mounts) are
+//! mounted and responsive. A mount point is considered responsive if its metadata can be read
+//! within `timeout` seconds. A hung mount never blocks the block itself: while a check is still
+//! pending, the mount point is reported as unresponsive and no new check is started for it.
+//!
+//! The block is set to the critical state if any mount point is unavailable.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `mounts` | A list of mount points to check. Supports path expansions e.g. `~`. | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $available/$total "`
+//! `interval` | Update interval in seconds | `30`
+//! `timeout` | Time in seconds after which a mount point is considered unresponsive | `5`
+//!
+//! Placeholder   | Value                                                        | Type   | Unit
+//! --------------|--------------------------------------------------------------|--------|-----
+//! `icon`        | A static icon                                                | Icon   | -
+//! `total`       | The number of configured mount points                        | Number | -
+//! `available`   | The number of mounted and responsive mount points            | Number | -
+//! `unavailable` | A comma separated list of unavailable mount points           | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "network_mounts"
+//! mounts = ["/mnt/nas", "~/remote"]
+//! format = " $icon $available/$total{ $unavailable|} "
+//! ```
+//!
+//! # Icons Used
+//! - `disk_drive`
+
+use super::prelude::*;
+use crate::util::read_file;
+use tokio::task::JoinHandle;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub mounts: Vec<ShellString>,
+    pub format: FormatConfig,
+    #[default(30.into())]
+    pub interval: Seconds,
+    #[default(5.into())]
+    pub timeout: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $available/$total ")?;
+    let mut timer = config.interval.timer();
+
+    let mount_points = config
+        .mounts
+        .iter()
+        .map(|m| {
+            let path = m.expand()?;
+            // `/proc/self/mounts` has no trailing slashes, except for the root itself
+            let trimmed = path.trim_end_matches('/');
+            Ok(if trimmed.is_empty() { "/" } else { trimmed }.to_string())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut pending: Vec<Option<JoinHandle<bool>>> = mount_points.iter().map(|_| None).collect();
+
+    loop {
+        let mounts = read_file("/proc/self/mounts")
+            .await
+            .error("Failed to read /proc/self/mounts")?;
+        let mounted: Vec<String> = mounts
+            .lines()
+            .filter_map(|line| line.split(' ').nth(1))
+            .map(unescape_mount_point)
+            .collect();
+
+        let mut unavailable = Vec::new();
+        for (mount_point, pending) in mount_points.iter().zip(&mut pending) {
+            let available = mounted.contains(mount_point)
+                && is_responsive(mount_point, pending, config.timeout.0).await;
+            if !available {
+                unavailable.push(mount_point.as_str());
+            }
+        }
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if unavailable.is_empty() {
+            State::Idle
+        } else {
+            State::Critical
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("disk_drive"),
+            "total" => Value::number(mount_points.len()),
+            "available" => Value::number(mount_points.len() - unavailable.len()),
+            "unavailable" => Value::text(unavailable.join(", ")),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Checks whether the metadata of `mount_point` can be read within `timeout`.
+///
+/// The check runs on a blocking thread, which can't be cancelled. If it times out, its handle is
+/// kept in `pending` and polled again on the next call instead of starting a new check.
+async fn is_responsive(
+    mount_point: &str,
+    pending: &mut Option<JoinHandle<bool>>,
+    timeout: Duration,
+) -> bool {
+    let handle = pending.get_or_insert_with(|| {
+        let path = mount_point.to_string();
+        tokio::task::spawn_blocking(move || std::fs::metadata(path).is_ok())
+    });
+    match tokio::time::timeout(timeout, handle).await {
+        Ok(result) => {
+            *pending = None;
+            result.unwrap_or(false)
+        }
+        Err(_) => false,
+    }
+}
+
+/// Mount points in `/proc/self/mounts` have spaces, tabs, newlines and backslashes escaped as
+/// octal sequences
+fn unescape_mount_point(s: &str) -> String {
+    s.replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
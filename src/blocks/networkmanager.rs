@@ -0,0 +1,293 @@
+//! The primary NetworkManager connection
+//!
+//! This block shows the type, name and IP address of the primary connection of NetworkManager. It
+//! listens for NetworkManager's D-Bus signals, so it is updated as soon as a connection goes up or
+//! down.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $name{ $ip\|} \"</code>
+//! `format_disconnected` | Same as `format` but for when there is no primary connection | `" $icon "`
+//! `editor_command` | The shell command run by the `edit` action | `"nm-connection-editor"`
+//!
+//! Placeholder | Value                                                                               | Type | Unit
+//! ------------|-------------------------------------------------------------------------------------|------|-----
+//! `icon`      | Icon based on the type of the connection                                            | Icon | -
+//! `name`      | The name of the connection                                                          | Text | -
+//! `type`      | `wifi`, `ethernet`, `vpn`, `wireguard`, `mobile` or the type used by NetworkManager | Text | -
+//! `ip`        | The first IPv4 address of the connection (absent if none)                           | Text | -
+//! `ipv6`      | The first IPv6 address of the connection (absent if none)                           | Text | -
+//!
+//! Action | Description          | Default button
+//! -------|----------------------|---------------
+//! `edit` | Run `editor_command` | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "networkmanager"
+//! format = " $icon $name.str(max_w:15) "
+//! editor_command = "alacritty -e nmtui"
+//! ```
+//!
+//! # Icons Used
+//! - `net_wireless`
+//! - `net_wired`
+//! - `net_vpn`
+//! - `net_modem`
+//! - `net_up`
+//! - `net_down`
+
+use futures::FutureExt;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+use zbus::{Connection, MatchRule, MessageStream};
+
+use super::prelude::*;
+use crate::subprocess::spawn_shell;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    pub format_disconnected: FormatConfig,
+    #[default("nm-connection-editor".into())]
+    pub editor_command: String,
+}
+
+#[derive(Debug)]
+struct ConnectionInfo {
+    name: String,
+    conn_type: String,
+    ipv4: Option<String>,
+    ipv6: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "edit")])?;
+
+    let format = config.format.with_default(" $icon $name{ $ip|} ")?;
+    let format_disconnected = config.format_disconnected.with_default(" $icon ")?;
+
+    let dbus_conn = new_system_dbus_connection().await?;
+    let nm_proxy = NetworkManagerProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create NetworkManagerProxy")?;
+
+    // Only listen to NetworkManager's signals, so that the replies to our own property calls
+    // don't trigger another update
+    let properties_changed = MessageStream::for_match_rule(
+        MatchRule::builder()
+            .msg_type(zbus::MessageType::Signal)
+            .sender("org.freedesktop.NetworkManager")
+            .and_then(|x| x.path_namespace("/org/freedesktop/NetworkManager"))
+            .and_then(|x| x.interface("org.freedesktop.DBus.Properties"))
+            .and_then(|x| x.member("PropertiesChanged"))
+            .unwrap()
+            .build(),
+        &dbus_conn,
+        None,
+    )
+    .await
+    .error("Failed to add match rule")?;
+    let state_changed = MessageStream::for_match_rule(
+        MatchRule::builder()
+            .msg_type(zbus::MessageType::Signal)
+            .sender("org.freedesktop.NetworkManager")
+            .and_then(|x| x.path("/org/freedesktop/NetworkManager"))
+            .and_then(|x| x.interface("org.freedesktop.NetworkManager"))
+            .and_then(|x| x.member("StateChanged"))
+            .unwrap()
+            .build(),
+        &dbus_conn,
+        None,
+    )
+    .await
+    .error("Failed to add match rule")?;
+    let mut updates = futures::stream::select(properties_changed, state_changed);
+
+    loop {
+        match get_primary_connection(&dbus_conn, &nm_proxy).await? {
+            Some(info) => {
+                let mut widget = Widget::new().with_format(format.clone());
+                widget.set_values(map! {
+                    "icon" => Value::icon(match info.conn_type.as_str() {
+                        "wifi" => "net_wireless",
+                        "ethernet" => "net_wired",
+                        "vpn" | "wireguard" => "net_vpn",
+                        "mobile" => "net_modem",
+                        _ => "net_up",
+                    }),
+                    "name" => Value::text(info.name),
+                    "type" => Value::text(info.conn_type),
+                    [if let Some(ip) = info.ipv4] "ip" => Value::text(ip),
+                    [if let Some(ip) = info.ipv6] "ipv6" => Value::text(ip),
+                });
+                api.set_widget(widget)?;
+            }
+            None => {
+                let mut widget = Widget::new().with_format(format_disconnected.clone());
+                widget.state = State::Warning;
+                widget.set_values(map! {
+                    "icon" => Value::icon("net_down"),
+                });
+                api.set_widget(widget)?;
+            }
+        }
+
+        loop {
+            select! {
+                _ = updates.next() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "edit" => {
+                        spawn_shell(&config.editor_command)
+                            .or_error(|| format!("Failed to run '{}'", config.editor_command))?;
+                    }
+                    _ => (),
+                }
+            }
+        }
+        // NetworkManager sends several signals in a row when a connection changes
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        while updates.next().now_or_never().is_some() {}
+    }
+}
+
+async fn get_primary_connection(
+    dbus_conn: &Connection,
+    nm_proxy: &NetworkManagerProxy<'_>,
+) -> Result<Option<ConnectionInfo>> {
+    let path = nm_proxy
+        .primary_connection()
+        .await
+        .error("Failed to get the primary connection")?;
+    if path.as_str() == "/" {
+        return Ok(None);
+    }
+
+    let active = ActiveConnectionProxy::builder(dbus_conn)
+        .path(path)
+        .unwrap()
+        .build()
+        .await
+        .error("Failed to create ActiveConnectionProxy")?;
+    // The connection may disappear while being queried
+    let Ok(name) = active.id().await else {
+        return Ok(None);
+    };
+    let raw_type = active.type_().await.unwrap_or_default();
+
+    Ok(Some(ConnectionInfo {
+        name,
+        conn_type: connection_type(&raw_type).map_or(raw_type, String::from),
+        ipv4: ipv4_address(dbus_conn, &active).await,
+        ipv6: ipv6_address(dbus_conn, &active).await,
+    }))
+}
+
+/// Maps NetworkManager's connection types to shorter names
+fn connection_type(raw_type: &str) -> Option<&'static str> {
+    Some(match raw_type {
+        "802-11-wireless" => "wifi",
+        "802-3-ethernet" => "ethernet",
+        "vpn" => "vpn",
+        "wireguard" => "wireguard",
+        "gsm" | "cdma" => "mobile",
+        _ => return None,
+    })
+}
+
+fn first_address(data: &[HashMap<String, OwnedValue>]) -> Option<String> {
+    let address: &str = data.first()?.get("address")?.downcast_ref().ok()?;
+    Some(address.to_owned())
+}
+
+async fn ipv4_address(
+    dbus_conn: &Connection,
+    active: &ActiveConnectionProxy<'_>,
+) -> Option<String> {
+    let path = active.ip4_config().await.ok()?;
+    if path.as_str() == "/" {
+        return None;
+    }
+    let data = IP4ConfigProxy::builder(dbus_conn)
+        .path(path)
+        .ok()?
+        .build()
+        .await
+        .ok()?
+        .address_data()
+        .await
+        .ok()?;
+    first_address(&data)
+}
+
+async fn ipv6_address(
+    dbus_conn: &Connection,
+    active: &ActiveConnectionProxy<'_>,
+) -> Option<String> {
+    let path = active.ip6_config().await.ok()?;
+    if path.as_str() == "/" {
+        return None;
+    }
+    let data = IP6ConfigProxy::builder(dbus_conn)
+        .path(path)
+        .ok()?
+        .build()
+        .await
+        .ok()?
+        .address_data()
+        .await
+        .ok()?;
+    first_address(&data)
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager"
+)]
+trait NetworkManager {
+    #[zbus(property)]
+    fn primary_connection(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.Connection.Active",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait ActiveConnection {
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "Type")]
+    fn type_(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn ip4_config(&self) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(property)]
+    fn ip6_config(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.IP4Config",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait IP4Config {
+    #[zbus(property)]
+    fn address_data(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.IP6Config",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait IP6Config {
+    #[zbus(property)]
+    fn address_data(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+}
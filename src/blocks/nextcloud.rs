@@ -0,0 +1,249 @@
+//! Nextcloud sync status and notifications
+//!
+//! This block shows whether the [Nextcloud desktop client](https://nextcloud.com/install/#install-clients)
+//! is syncing, using the socket API which the client provides for file manager integrations. The
+//! block is updated as soon as the status of a sync folder changes.
+//!
+//! If `url` is set, the number of unread notifications on the server is shown as well. This
+//! requires `user` and an [app password](https://docs.nextcloud.com/server/latest/user_manual/en/session_management.html#managing-devices),
+//! which must be passed using the `I3RS_NEXTCLOUD_PASSWORD` environment variable or the `password`
+//! configuration option.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `socket_path` | The path to the socket of the desktop client. Supports path expansions e.g. `~` and environment variables. | `"$XDG_RUNTIME_DIR/Nextcloud/socket"`
+//! `url` | The URL of the server, e.g. `"https://cloud.example.com"` | `None`
+//! `user` | The user to fetch the notifications of | `None`
+//! `password` | An app password of `user` | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $status{ $notifications\|} \"</code>
+//! `interval` | Update interval of the notifications in seconds | `60`
+//!
+//! Placeholder     | Value                                                                 | Type   | Unit
+//! ----------------|-----------------------------------------------------------------------|--------|-----
+//! `icon`          | `sync` while syncing, `cloud` otherwise                               | Icon   | -
+//! `status`        | `ok`, `syncing`, `error` or `offline` if the client isn't running     | Text   | -
+//! `folders`       | The number of sync folders                                            | Number | -
+//! `notifications` | The number of notifications on the server (absent if `url` isn't set) | Number | -
+//! `subject`       | The subject of the latest notification (absent if there is none)      | Text   | -
+//!
+//! The block is set to the critical state if a folder failed to sync, and to the info state while
+//! syncing or if there are notifications.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "nextcloud"
+//! url = "https://cloud.example.com"
+//! user = "alice"
+//! format = " $icon $status{ $notifications $subject.str(max_w:20)|} "
+//! ```
+//!
+//! # Icons Used
+//! - `cloud`
+//! - `sync`
+
+use std::path::Path;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("$XDG_RUNTIME_DIR/Nextcloud/socket".into())]
+    pub socket_path: ShellString,
+    pub url: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $status{ $notifications|} ")?;
+    let mut timer = config.interval.timer();
+
+    let socket_path = config.socket_path.expand()?;
+    let server = match &config.url {
+        Some(url) => Some(Server {
+            url: url.trim_end_matches('/'),
+            user: config
+                .user
+                .as_deref()
+                .error("user must be set together with url")?,
+            password: config
+                .password
+                .clone()
+                .or_else(|| std::env::var("I3RS_NEXTCLOUD_PASSWORD").ok())
+                .error("Nextcloud password not found")?,
+        }),
+        None => None,
+    };
+
+    let mut client = None;
+    let mut notifications = None;
+    let mut refresh = true;
+
+    loop {
+        // The client may be started after the bar, so try to connect on every update
+        if client.is_none() {
+            client = SyncClient::connect(&*socket_path).await;
+        }
+        if refresh {
+            if let Some(server) = &server {
+                notifications = Some(server.notifications().await?);
+            }
+            refresh = false;
+        }
+
+        let status = client.as_ref().map_or("offline", SyncClient::status);
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match status {
+            "error" => State::Critical,
+            "syncing" => State::Info,
+            _ if notifications.as_ref().is_some_and(|n| !n.is_empty()) => State::Info,
+            _ => State::Idle,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon(if status == "syncing" { "sync" } else { "cloud" }),
+            "status" => Value::text(status.into()),
+            "folders" => Value::number(client.as_ref().map_or(0, |c| c.folders.len())),
+            [if let Some(n) = &notifications] "notifications" => Value::number(n.len()),
+            [if let Some(n) = notifications.as_ref().and_then(|n| n.first())] "subject" => Value::text(n.subject.clone()),
+        });
+        api.set_widget(widget)?;
+
+        let connected = client.is_some();
+        select! {
+            line = async { client.as_mut().unwrap().reader.next_line().await }, if connected => {
+                match line {
+                    Ok(Some(line)) => {
+                        if client.as_mut().unwrap().handle(&line).await.is_err() {
+                            client = None;
+                        }
+                    }
+                    // The desktop client was closed
+                    _ => client = None,
+                }
+            }
+            _ = timer.tick() => refresh = true,
+            _ = api.wait_for_update_request() => refresh = true,
+        }
+    }
+}
+
+/// A connection to the socket API of the desktop client
+struct SyncClient {
+    reader: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+    /// The status of each sync folder, e.g. `OK` or `SYNC`
+    folders: HashMap<String, String>,
+}
+
+impl SyncClient {
+    async fn connect(path: impl AsRef<Path>) -> Option<Self> {
+        // Once connected, the client sends a REGISTER_PATH message for each sync folder
+        let (reader, writer) = UnixStream::connect(path).await.ok()?.into_split();
+        Some(Self {
+            reader: BufReader::new(reader).lines(),
+            writer,
+            folders: HashMap::new(),
+        })
+    }
+
+    async fn handle(&mut self, line: &str) -> std::io::Result<()> {
+        let Some((command, args)) = line.split_once(':') else {
+            return Ok(());
+        };
+        match command {
+            "REGISTER_PATH" | "UPDATE_VIEW" => {
+                if command == "REGISTER_PATH" {
+                    self.folders.entry(args.into()).or_default();
+                } else if !self.folders.contains_key(args) {
+                    return Ok(());
+                }
+                self.writer
+                    .write_all(format!("RETRIEVE_FOLDER_STATUS:{args}\n").as_bytes())
+                    .await?;
+            }
+            "UNREGISTER_PATH" => {
+                self.folders.remove(args);
+            }
+            "STATUS" => {
+                // The status of files is sent as well, which is of no interest here
+                if let Some((status, path)) = args.split_once(':') {
+                    if let Some(folder) = self.folders.get_mut(path) {
+                        // Shared items have a suffix, e.g. OK+SWM
+                        status.split('+').next().unwrap().clone_into(folder);
+                    }
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> &'static str {
+        let statuses = || self.folders.values().map(String::as_str);
+        if statuses().any(|s| s == "ERROR") {
+            "error"
+        } else if statuses().any(|s| matches!(s, "SYNC" | "NEW")) {
+            "syncing"
+        } else {
+            "ok"
+        }
+    }
+}
+
+struct Server<'a> {
+    url: &'a str,
+    user: &'a str,
+    password: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Notification {
+    subject: String,
+}
+
+impl Server<'_> {
+    /// Returns the notifications of the user, the latest first
+    async fn notifications(&self) -> Result<Vec<Notification>> {
+        #[derive(Deserialize)]
+        struct Response {
+            ocs: Ocs,
+        }
+
+        #[derive(Deserialize)]
+        struct Ocs {
+            data: Vec<Notification>,
+        }
+
+        let response: Response = REQWEST_CLIENT
+            .get(format!(
+                "{}/ocs/v2.php/apps/notifications/api/v2/notifications",
+                self.url
+            ))
+            .basic_auth(self.user, Some(&self.password))
+            .header("OCS-APIRequest", "true")
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to fetch notifications")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+        Ok(response.ocs.data)
+    }
+}
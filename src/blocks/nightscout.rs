@@ -0,0 +1,227 @@
+//! Blood glucose from Nightscout
+//!
+//! This block polls a [Nightscout](https://nightscout.github.io/) instance and shows the latest
+//! sensor glucose value, its trend and the age of the reading.
+//!
+//! The block is set to the critical state if the glucose is at or beyond `urgent_low` or
+//! `urgent_high`, to the warning state if it is at or beyond `low` or `high`, or if the latest
+//! reading is older than `stale_after`, and to the good state otherwise. Thresholds are given in the
+//! configured `units`.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The URL of the Nightscout instance, e.g. `"https://my-cgm.example.com"` | **Required**
+//! `token` | An access token with the `readable` role. Can also be provided using the `NIGHTSCOUT_TOKEN` environment variable. Not needed if the instance is publicly readable. | `None`
+//! `units` | `"mgdl"` or `"mmol"` | `"mgdl"`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $glucose $trend "`
+//! `interval` | Update interval in seconds | `60`
+//! `urgent_low` | Glucose at or below which the state is set to critical | `55` (mg/dL) or `3.0` (mmol/L)
+//! `low` | Glucose at or below which the state is set to warning | `70` (mg/dL) or `3.9` (mmol/L)
+//! `high` | Glucose at or above which the state is set to warning | `180` (mg/dL) or `10.0` (mmol/L)
+//! `urgent_high` | Glucose at or above which the state is set to critical | `250` (mg/dL) or `13.9` (mmol/L)
+//! `stale_after` | Age of the latest reading, in seconds, after which it is considered stale | `900`
+//!
+//! Placeholder | Value                                                          | Type   | Unit
+//! ------------|----------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                  | Icon   | -
+//! `glucose`   | The latest glucose value in the configured units               | Number | -
+//! `trend`     | An arrow showing the trend, e.g. `↗`                           | Text   | -
+//! `delta`     | The change since the previous reading (absent if unknown)      | Number | -
+//! `age`       | The age of the latest reading in minutes                       | Number | -
+//! `stale`     | Present if the latest reading is older than `stale_after`      | Flag   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "nightscout"
+//! url = "https://my-cgm.example.com"
+//! units = "mmol"
+//! format = " $icon $glucose.eng(w:3) $trend{ ($age min ago)|} "
+//! ```
+//!
+//! # Icons Used
+//! - `glucose`
+
+use chrono::{DateTime, Utc};
+
+use super::prelude::*;
+
+/// The factor between mg/dL and mmol/L used by Nightscout
+const MGDL_PER_MMOL: f64 = 18.0;
+
+/// The previous reading is only used for the delta if it is at most this old
+const MAX_DELTA_GAP_MINUTES: i64 = 15;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub url: String,
+    pub token: Option<String>,
+    pub units: GlucoseUnits,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+    pub urgent_low: Option<f64>,
+    pub low: Option<f64>,
+    pub high: Option<f64>,
+    pub urgent_high: Option<f64>,
+    #[default(900.into())]
+    pub stale_after: Seconds,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GlucoseUnits {
+    #[default]
+    Mgdl,
+    Mmol,
+}
+
+impl GlucoseUnits {
+    fn convert_mgdl(self, value: f64) -> f64 {
+        match self {
+            Self::Mgdl => value,
+            Self::Mmol => value / MGDL_PER_MMOL,
+        }
+    }
+
+    /// Returns the default `urgent_low`, `low`, `high` and `urgent_high` thresholds
+    fn default_thresholds(self) -> [f64; 4] {
+        match self {
+            Self::Mgdl => [55.0, 70.0, 180.0, 250.0],
+            Self::Mmol => [3.0, 3.9, 10.0, 13.9],
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Entry {
+    /// Sensor glucose in mg/dL
+    sgv: f64,
+    /// Milliseconds since the epoch
+    date: i64,
+    direction: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $glucose $trend ")?;
+
+    if config.url.is_empty() {
+        return Err(Error::new("`url` is required"));
+    }
+    let url = format!(
+        "{}/api/v1/entries/sgv.json",
+        config.url.trim_end_matches('/')
+    );
+    let token = config
+        .token
+        .clone()
+        .or_else(|| std::env::var("NIGHTSCOUT_TOKEN").ok());
+
+    let units = config.units;
+    let [default_urgent_low, default_low, default_high, default_urgent_high] =
+        units.default_thresholds();
+    let urgent_low = config.urgent_low.unwrap_or(default_urgent_low);
+    let low = config.low.unwrap_or(default_low);
+    let high = config.high.unwrap_or(default_high);
+    let urgent_high = config.urgent_high.unwrap_or(default_urgent_high);
+
+    let mut timer = config.interval.timer();
+
+    loop {
+        let fetch = || get_entries(&url, token.as_deref());
+        let entries = fetch.retry(&ExponentialBuilder::default()).await?;
+        let latest = entries.first().error("Nightscout returned no readings")?;
+
+        let taken_at = DateTime::from_timestamp_millis(latest.date).error("Invalid timestamp")?;
+        let age = (Utc::now() - taken_at).num_minutes().max(0);
+        let stale = age as u64 * 60 >= config.stale_after.seconds();
+        let glucose = units.convert_mgdl(latest.sgv);
+        let delta = entries.get(1).and_then(|previous| {
+            let gap = latest.date - previous.date;
+            (gap > 0 && gap <= MAX_DELTA_GAP_MINUTES * 60_000)
+                .then(|| units.convert_mgdl(latest.sgv - previous.sgv))
+        });
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match glucose {
+            x if x <= urgent_low || x >= urgent_high => State::Critical,
+            x if x <= low || x >= high => State::Warning,
+            _ if stale => State::Warning,
+            _ => State::Good,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("glucose"),
+            "glucose" => Value::number(glucose),
+            "trend" => Value::text(trend_arrow(latest.direction.as_deref()).into()),
+            [if let Some(delta) = delta] "delta" => Value::number(delta),
+            "age" => Value::number(age),
+            [if stale] "stale" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Returns the two latest readings, newest first
+async fn get_entries(url: &str, token: Option<&str>) -> Result<Vec<Entry>> {
+    let mut request = REQWEST_CLIENT.get(url).query(&[("count", "2")]);
+    if let Some(token) = token {
+        request = request.query(&[("token", token)]);
+    }
+    request
+        .send()
+        .await
+        .error("Failed to send request")?
+        .error_for_status()
+        .error("Failed to get readings")?
+        .json()
+        .await
+        .error("Failed to parse JSON")
+}
+
+fn trend_arrow(direction: Option<&str>) -> &'static str {
+    match direction {
+        Some("DoubleUp") => "⇈",
+        Some("SingleUp") => "↑",
+        Some("FortyFiveUp") => "↗",
+        Some("Flat") => "→",
+        Some("FortyFiveDown") => "↘",
+        Some("SingleDown") => "↓",
+        Some("DoubleDown") => "⇊",
+        Some("RATE OUT OF RANGE") => "⇕",
+        _ => "-",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entries() {
+        let json = r#"[
+            {"_id": "a", "sgv": 142, "date": 1749549900000, "dateString": "2025-06-10T10:05:00.000Z", "direction": "FortyFiveUp", "type": "sgv"},
+            {"_id": "b", "sgv": 131, "date": 1749549600000, "dateString": "2025-06-10T10:00:00.000Z", "direction": "Flat", "type": "sgv"}
+        ]"#;
+        let entries: Vec<Entry> = serde_json::from_str(json).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].sgv, 142.0);
+        assert_eq!(entries[0].date, 1749549900000);
+        assert_eq!(trend_arrow(entries[0].direction.as_deref()), "↗");
+        assert_eq!(trend_arrow(None), "-");
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(GlucoseUnits::Mgdl.convert_mgdl(90.0), 90.0);
+        assert_eq!(GlucoseUnits::Mmol.convert_mgdl(90.0), 5.0);
+    }
+}
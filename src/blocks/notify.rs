@@ -2,23 +2,33 @@
 //!
 //! Left-clicking on this block will enable/disable notifications.
 //!
+//! With mako, notifications are disabled by enabling the `do-not-disturb` mode, which should be
+//! defined in mako's configuration, e.g.:
+//!
+//! ```text
+//! [mode=do-not-disturb]
+//! invisible=1
+//! ```
+//!
 //! # Configuration
 //!
 //! Key | Values | Default
 //! ----|--------|--------
-//! `driver` | Which notifications daemon is running. Available drivers are: `"dunst"` and `"swaync"` | `"dunst"`
+//! `driver` | Which notifications daemon is running. Available drivers are: `"dunst"`, `"swaync"` and `"mako"` | `"dunst"`
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon "`
 //!
-//! Placeholder                               | Value                                      | Type   | Unit
-//! ------------------------------------------|--------------------------------------------|--------|-----
-//! `icon`                                    | Icon based on notification's state         | Icon   | -
-//! `notification_count`[^dunst_version_note] | The number of notification (omitted if 0)  | Number | -
-//! `paused`                                  | Present only if notifications are disabled | Flag   | -
+//! Placeholder                               | Value                                                                 | Type   | Unit
+//! ------------------------------------------|-----------------------------------------------------------------------|--------|-----
+//! `icon`                                    | Icon based on notification's state                                    | Icon   | -
+//! `notification_count`[^dunst_version_note] | The number of notification (omitted if 0)                             | Number | -
+//! `suppressed_count`                        | The number of notifications not shown while paused (omitted if 0)     | Number | -
+//! `history_count`                           | dunst only: notifications in the history (omitted if 0 or unknown)    | Number | -
+//! `paused`                                  | Present only if notifications are disabled                            | Flag   | -
 //!
-//! Action          | Default button
-//! ----------------|---------------
-//! `toggle_paused` | Left
-//! `show`          | -
+//! Action          | Description                                                      | Default button
+//! ----------------|------------------------------------------------------------------|---------------
+//! `toggle_paused` | Enable or disable notifications                                  | Left
+//! `show`          | Open the notification center, or the history with dunst and mako | -
 //!
 //! # Examples
 //!
@@ -54,6 +64,12 @@
 //! - `bell`
 //! - `bell-slash`
 //!
+//! With `dunst`, `suppressed_count` is the number of notifications waiting to be shown once
+//! notifications are enabled again. With the other drivers, it is the number of notifications
+//! received while paused.
+//!
+//! The `mako` driver doesn't get notified of changes, so it checks for them every second.
+//!
 //! [^dunst_version_note]: when using `notification_count` with the `dunst` driver use dunst > 1.9.0
 
 use super::prelude::*;
@@ -76,6 +92,7 @@ pub enum DriverType {
     #[default]
     Dunst,
     SwayNC,
+    Mako,
 }
 
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
@@ -87,16 +104,35 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let mut driver: Box<dyn Driver> = match config.driver {
         DriverType::Dunst => Box::new(DunstDriver::new().await?),
         DriverType::SwayNC => Box::new(SwayNCDriver::new().await?),
+        DriverType::Mako => Box::new(MakoDriver::new().await?),
     };
 
+    // The number of notifications when notifications were paused
+    let mut paused_at = None;
+
     loop {
         let (is_paused, notification_count) =
             try_join!(driver.is_paused(), driver.notification_count())?;
+        // Older daemons don't keep a history, which shouldn't break the whole block
+        let history_count = driver.history_count().await.ok().flatten();
+        let suppressed_count = if is_paused {
+            match driver.waiting_count().await? {
+                Some(waiting_count) => waiting_count,
+                None => {
+                    notification_count.saturating_sub(*paused_at.get_or_insert(notification_count))
+                }
+            }
+        } else {
+            paused_at = None;
+            0
+        };
 
         let mut widget = Widget::new().with_format(format.clone());
         widget.set_values(map!(
             "icon" => Value::icon(if is_paused { ICON_OFF } else { ICON_ON }),
             [if notification_count != 0] "notification_count" => Value::number(notification_count),
+            [if suppressed_count != 0] "suppressed_count" => Value::number(suppressed_count),
+            [if let Some(c) = history_count.filter(|&c| c != 0)] "history_count" => Value::number(c),
             [if is_paused] "paused" => Value::flag(),
         ));
         widget.state = if notification_count == 0 {
@@ -127,6 +163,10 @@ trait Driver {
     async fn set_paused(&self, paused: bool) -> Result<()>;
     async fn notification_show(&self) -> Result<()>;
     async fn notification_count(&self) -> Result<u32>;
+    /// The number of notifications waiting to be shown, if the daemon keeps track of it
+    async fn waiting_count(&self) -> Result<Option<u32>>;
+    /// The number of notifications in the history, if the daemon keeps one
+    async fn history_count(&self) -> Result<Option<u32>>;
     async fn wait_for_change(&mut self) -> Result<()>;
 }
 
@@ -135,6 +175,7 @@ struct DunstDriver {
     paused_changes: PropertyStream<'static, bool>,
     displayed_length_changes: PropertyStream<'static, u32>,
     waiting_length_changes: PropertyStream<'static, u32>,
+    history_length_changes: PropertyStream<'static, u32>,
 }
 
 impl DunstDriver {
@@ -147,6 +188,7 @@ impl DunstDriver {
             paused_changes: proxy.receive_paused_changed().await,
             displayed_length_changes: proxy.receive_displayed_length_changed().await,
             waiting_length_changes: proxy.receive_waiting_length_changed().await,
+            history_length_changes: proxy.receive_history_length_changed().await,
             proxy,
         })
     }
@@ -180,11 +222,28 @@ impl Driver for DunstDriver {
         Ok(displayed_length + waiting_length)
     }
 
+    async fn waiting_count(&self) -> Result<Option<u32>> {
+        self.proxy
+            .waiting_length()
+            .await
+            .map(Some)
+            .error("Failed to get 'waitingLength'")
+    }
+
+    async fn history_count(&self) -> Result<Option<u32>> {
+        self.proxy
+            .history_length()
+            .await
+            .map(Some)
+            .error("Failed to get 'historyLength'")
+    }
+
     async fn wait_for_change(&mut self) -> Result<()> {
         select! {
             _ = self.paused_changes.next() => {}
             _ = self.displayed_length_changes.next() => {}
             _ = self.waiting_length_changes.next() => {}
+            _ = self.history_length_changes.next() => {}
         }
         Ok(())
     }
@@ -205,6 +264,8 @@ trait DunstDbus {
     fn displayed_length(&self) -> zbus::Result<u32>;
     #[zbus(property, name = "waitingLength")]
     fn waiting_length(&self) -> zbus::Result<u32>;
+    #[zbus(property, name = "historyLength")]
+    fn history_length(&self) -> zbus::Result<u32>;
 }
 struct SwayNCDriver {
     proxy: SwayNCDbusProxy<'static>,
@@ -259,6 +320,14 @@ impl Driver for SwayNCDriver {
             .error("Failed to call 'NotificationCount'")
     }
 
+    async fn waiting_count(&self) -> Result<Option<u32>> {
+        Ok(None)
+    }
+
+    async fn history_count(&self) -> Result<Option<u32>> {
+        Ok(None)
+    }
+
     async fn wait_for_change(&mut self) -> Result<()> {
         select! {
             _ = self.changes.next() => (),
@@ -289,3 +358,88 @@ trait SwayNCDbus {
         inhibited: bool,
     ) -> zbus::Result<()>;
 }
+
+struct MakoDriver {
+    proxy: MakoDbusProxy<'static>,
+}
+
+impl MakoDriver {
+    const DND_MODE: &'static str = "do-not-disturb";
+
+    async fn new() -> Result<Self> {
+        let dbus_conn = new_dbus_connection().await?;
+        let proxy = MakoDbusProxy::new(&dbus_conn)
+            .await
+            .error("Failed to create MakoDbusProxy")?;
+        Ok(Self { proxy })
+    }
+}
+
+#[async_trait]
+impl Driver for MakoDriver {
+    async fn is_paused(&self) -> Result<bool> {
+        let modes = self
+            .proxy
+            .list_modes()
+            .await
+            .error("Failed to call 'ListModes'")?;
+        Ok(modes.iter().any(|m| m == Self::DND_MODE))
+    }
+
+    async fn set_paused(&self, paused: bool) -> Result<()> {
+        let mut modes = self
+            .proxy
+            .list_modes()
+            .await
+            .error("Failed to call 'ListModes'")?;
+        modes.retain(|m| m != Self::DND_MODE);
+        if paused {
+            modes.push(Self::DND_MODE.into());
+        }
+        self.proxy
+            .set_modes(&modes)
+            .await
+            .error("Failed to call 'SetModes'")
+    }
+
+    async fn notification_show(&self) -> Result<()> {
+        self.proxy
+            .restore_notification()
+            .await
+            .error("Failed to call 'RestoreNotification'")
+    }
+
+    async fn notification_count(&self) -> Result<u32> {
+        let notifications = self
+            .proxy
+            .list_notifications()
+            .await
+            .error("Failed to call 'ListNotifications'")?;
+        Ok(notifications.len() as u32)
+    }
+
+    async fn waiting_count(&self) -> Result<Option<u32>> {
+        Ok(None)
+    }
+
+    async fn history_count(&self) -> Result<Option<u32>> {
+        Ok(None)
+    }
+
+    async fn wait_for_change(&mut self) -> Result<()> {
+        sleep(Duration::from_secs(1)).await;
+        Ok(())
+    }
+}
+
+#[zbus::proxy(
+    interface = "fr.emersion.Mako",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/fr/emersion/Mako"
+)]
+trait MakoDbus {
+    fn list_modes(&self) -> zbus::Result<Vec<String>>;
+    fn set_modes(&self, modes: &[String]) -> zbus::Result<()>;
+    fn restore_notification(&self) -> zbus::Result<()>;
+    fn list_notifications(&self) -> zbus::Result<Vec<HashMap<String, zbus::zvariant::OwnedValue>>>;
+}
@@ -4,6 +4,12 @@
 //!
 //! The simplest configuration will return the total count of messages in the notmuch database stored at $HOME/.mail
 //!
+//! Additional named queries can be configured using `queries`; the count of each one is available
+//! as a placeholder with the query's name.
+//!
+//! If the Xapian database is located in `.notmuch/xapian` inside `maildir`, the block is updated as
+//! soon as the database changes, e.g. after `notmuch new` or tagging messages.
+//!
 //! Note that you need to enable `notmuch` feature to use this block:
 //! ```sh
 //! cargo build --release --features notmuch
@@ -16,6 +22,7 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count "`
 //! `maildir` | Path to the directory containing the notmuch database. Supports path expansions e.g. `~`. | `~/.mail`
 //! `query` | Query to run on the database. | `""`
+//! `queries` | Map of names to additional queries, e.g. `{ unread = "tag:unread" }` | `{}`
 //! `threshold_critical` | Mail count that triggers `critical` state. | `99999`
 //! `threshold_warning` | Mail count that triggers `warning` state. | `99999`
 //! `threshold_good` | Mail count that triggers `good` state. | `99999`
@@ -26,6 +33,7 @@
 //! ------------|--------------------------------------------|--------|-----
 //! `icon`      | A static icon                              | Icon   | -
 //! `count`     | Number of messages for the query           | Number | -
+//! `<name>`    | Number of messages for the query `<name>`  | Number | -
 //!
 //! # Example
 //!
@@ -40,10 +48,24 @@
 //! update = true
 //! ```
 //!
+//! ```toml
+//! [[block]]
+//! block = "notmuch"
+//! query = "tag:unread and tag:inbox"
+//! format = " $icon $count.eng(w:1) ($flagged.eng(w:1) flagged, $lists.eng(w:1) lists) "
+//! threshold_info = 1
+//! [block.queries]
+//! flagged = "tag:flagged and tag:unread"
+//! lists = "tag:lists and tag:unread"
+//! ```
+//!
 //! # Icons Used
 //! - `mail`
 
 use super::prelude::*;
+use debounced::debounced;
+use inotify::{Inotify, WatchMask};
+use std::path::Path;
 
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
@@ -54,6 +76,7 @@ pub struct Config {
     #[default("~/.mail".into())]
     pub maildir: ShellString,
     pub query: String,
+    pub queries: HashMap<String, String>,
     #[default(u32::MAX)]
     pub threshold_warning: u32,
     #[default(u32::MAX)]
@@ -70,16 +93,42 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let db = config.maildir.expand()?;
     let mut timer = config.interval.timer();
 
+    let xapian_dir = Path::new(&*db).join(".notmuch/xapian");
+    let notify = Inotify::init().error("Failed to start inotify")?;
+    if xapian_dir.is_dir() {
+        notify
+            .watches()
+            .add(
+                &xapian_dir,
+                WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE,
+            )
+            .error("Failed to watch notmuch database")?;
+    }
+    // Writing to the database changes several files
+    let mut updates = debounced(
+        notify
+            .into_event_stream([0; 1024])
+            .error("Failed to create event stream")?,
+        Duration::from_millis(500),
+    );
+
     loop {
         // TODO: spawn_blocking?
         let count = run_query(&db, &config.query).error("Failed to get count")?;
 
-        let mut widget = Widget::new().with_format(format.clone());
-
-        widget.set_values(map! {
+        let mut values = map! {
             "icon" => Value::icon("mail"),
             "count" => Value::number(count)
-        });
+        };
+        for (name, query) in &config.queries {
+            let count = run_query(&db, query)
+                .or_error(|| format!("Failed to get count of query '{name}'"))?;
+            values.insert(name.clone().into(), Value::number(count));
+        }
+
+        let mut widget = Widget::new().with_format(format.clone());
+
+        widget.set_values(values);
 
         widget.state = if count >= config.threshold_critical {
             State::Critical
@@ -97,6 +146,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
 
         tokio::select! {
             _ = timer.tick() => (),
+            _ = updates.next() => (),
             _ = api.wait_for_update_request() => (),
         }
     }
@@ -0,0 +1,195 @@
+//! Clock synchronization status
+//!
+//! This block shows whether the system clock is synchronized, as reported by `systemd-timedated`
+//! (the same as `timedatectl`) or by `chronyc`. With chrony, the current offset of the clock and the
+//! time server in use are shown as well. The block is set to the warning state if the clock is not
+//! synchronized or if the offset exceeds `offset_warning`.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `driver` | `"timedated"` or `"chrony"` | `"timedated"`
+//! `offset_warning` | Offset, in milliseconds, above which the state is set to warning | `100`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon{ $offset\|} \"</code>
+//! `interval` | Update interval in seconds | `60`
+//!
+//! Placeholder | Value                                                       | Type   | Unit
+//! ------------|-------------------------------------------------------------|--------|--------
+//! `icon`      | A static icon                                               | Icon   | -
+//! `synced`    | Present if the clock is synchronized                        | Flag   | -
+//! `offset`    | chrony only: the offset of the system clock from NTP time   | Number | Seconds
+//! `server`    | chrony only: the time server in use                         | Text   | -
+//! `stratum`   | chrony only: the stratum of the system clock                | Number | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "ntp"
+//! driver = "chrony"
+//! format = " $icon {$synced ok|unsynced} $offset.eng(w:3,u:s,p:m) "
+//! offset_warning = 50
+//! ```
+//!
+//! # Icons Used
+//! - `time`
+
+use tokio::process::Command;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub driver: Driver,
+    #[default(100.0)]
+    pub offset_warning: f64,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Driver {
+    #[default]
+    Timedated,
+    Chrony,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SyncStatus {
+    synced: bool,
+    /// Offset in seconds
+    offset: Option<f64>,
+    server: Option<String>,
+    stratum: Option<u32>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon{ $offset|} ")?;
+    let mut timer = config.interval.timer();
+
+    let timedate_proxy = match config.driver {
+        Driver::Timedated => {
+            let dbus_conn = new_system_dbus_connection().await?;
+            Some(
+                TimedateProxy::builder(&dbus_conn)
+                    .cache_properties(zbus::CacheProperties::No)
+                    .build()
+                    .await
+                    .error("Failed to create TimedateProxy")?,
+            )
+        }
+        Driver::Chrony => None,
+    };
+
+    loop {
+        let status = match &timedate_proxy {
+            Some(proxy) => SyncStatus {
+                synced: proxy
+                    .ntp_synchronized()
+                    .await
+                    .error("Failed to get NTPSynchronized")?,
+                ..Default::default()
+            },
+            None => chrony_tracking().await?,
+        };
+
+        let offset_exceeded = status
+            .offset
+            .is_some_and(|o| o.abs() * 1e3 > config.offset_warning);
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if !status.synced || offset_exceeded {
+            widget.state = State::Warning;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon("time"),
+            [if status.synced] "synced" => Value::flag(),
+            [if let Some(o) = status.offset] "offset" => Value::seconds(o),
+            [if let Some(s) = status.server] "server" => Value::text(s),
+            [if let Some(s) = status.stratum] "stratum" => Value::number(s),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn chrony_tracking() -> Result<SyncStatus> {
+    let output = Command::new("chronyc")
+        .args(["-c", "tracking"])
+        .output()
+        .await
+        .error("Failed to run chronyc")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "chronyc tracking failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    parse_tracking(&String::from_utf8_lossy(&output.stdout))
+        .error("Failed to parse the output of chronyc")
+}
+
+/// Parses the output of `chronyc -c tracking`. The fields are described in the documentation of
+/// the `tracking` command; the fifth one is the offset of the system clock and the last one the
+/// leap status.
+fn parse_tracking(output: &str) -> Option<SyncStatus> {
+    let fields: Vec<&str> = output.trim().split(',').collect();
+    let leap_status = *fields.get(13)?;
+    let stratum: u32 = fields.get(2)?.parse().ok()?;
+    Some(SyncStatus {
+        synced: leap_status != "Not synchronised" && stratum != 0,
+        offset: fields.get(4)?.parse().ok(),
+        server: fields
+            .get(1)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string()),
+        stratum: Some(stratum),
+    })
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.timedate1",
+    default_service = "org.freedesktop.timedate1",
+    default_path = "/org/freedesktop/timedate1"
+)]
+trait Timedate {
+    #[zbus(property, name = "NTPSynchronized")]
+    fn ntp_synchronized(&self) -> zbus::Result<bool>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tracking() {
+        assert_eq!(
+            parse_tracking(
+                "A29FC87B,time.cloudflare.com,4,1718000000.123456,-0.000012345,-0.000001234,\
+                 0.000023456,-3.210,-0.001,0.012,0.012345678,0.000987654,1031.7,Normal\n"
+            ),
+            Some(SyncStatus {
+                synced: true,
+                offset: Some(-0.000012345),
+                server: Some("time.cloudflare.com".into()),
+                stratum: Some(4),
+            })
+        );
+        assert_eq!(
+            parse_tracking(
+                "00000000,,0,0.000000000,0.000000000,0.000000000,0.000000000,0.000,0.000,\
+                 0.000,1.000000000,1.000000000,0.0,Not synchronised\n"
+            )
+            .map(|s| s.synced),
+            Some(false)
+        );
+    }
+}
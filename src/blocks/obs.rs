@@ -0,0 +1,356 @@
+//! OBS Studio status
+//!
+//! This block shows whether [OBS Studio](https://obsproject.com/) is streaming or recording, the
+//! current scene and the percentage of frames dropped while streaming, using
+//! [obs-websocket](https://github.com/obsproject/obs-websocket) 5, which is included in OBS 28 and
+//! newer. The WebSocket server must be enabled in "Tools > WebSocket Server Settings". If
+//! authentication is enabled, the password must be passed using the `I3RS_OBS_PASSWORD`
+//! environment variable or the `password` configuration option.
+//!
+//! The block is hidden while OBS isn't running.
+//!
+//! This block requires the `websocket` feature to be enabled at compile time.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The URL of the WebSocket server | `"ws://localhost:4455"`
+//! `password` | The password of the WebSocket server | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $scene{ $dropped\|} \"</code>
+//! `interval` | Update interval in seconds, also used to check whether OBS was started | `5`
+//! `warning` | The percentage of dropped frames above which the block is set to the warning state | `1.0`
+//! `critical` | The percentage of dropped frames above which the block is set to the critical state | `5.0`
+//!
+//! Placeholder   | Value                                            | Type   | Unit
+//! --------------|--------------------------------------------------|--------|--------
+//! `icon`        | A static icon                                    | Icon   | -
+//! `scene`       | The name of the current scene                    | Text   | -
+//! `streaming`   | Present while streaming                          | Flag   | -
+//! `recording`   | Present while recording, even if paused          | Flag   | -
+//! `paused`      | Present while the recording is paused            | Flag   | -
+//! `stream_time` | How long the stream has been running             | Number | Seconds
+//! `record_time` | How long the recording has been running          | Number | Seconds
+//! `dropped`     | The percentage of frames dropped while streaming | Number | %
+//!
+//! `stream_time` and `dropped` are only present while streaming, and `record_time` while
+//! recording. The block is set to the info state while streaming or recording.
+//!
+//! Action          | Description                    | Default button
+//! ----------------|--------------------------------|---------------
+//! `toggle_record` | Start or stop recording        | Left
+//! `toggle_stream` | Start or stop streaming        | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "obs"
+//! format = " $icon $scene{ $recording REC|}{ $streaming LIVE $dropped.eng(w:1)|} "
+//! [[block.click]]
+//! button = "right"
+//! action = "toggle_stream"
+//! ```
+//!
+//! # Icons Used
+//! - `webcam`
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use futures::SinkExt as _;
+use serde::de::DeserializeOwned;
+use sha2::{Digest as _, Sha256};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use super::prelude::*;
+
+/// The events of the "Scenes" and "Outputs" categories
+const EVENT_SUBSCRIPTIONS: u32 = (1 << 2) | (1 << 6);
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("ws://localhost:4455".into())]
+    pub url: String,
+    pub password: Option<String>,
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+    #[default(1.0)]
+    pub warning: f64,
+    #[default(5.0)]
+    pub critical: f64,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "toggle_record")])?;
+
+    let format = config.format.with_default(" $icon $scene{ $dropped|} ")?;
+    let mut timer = config.interval.timer();
+
+    let password = config
+        .password
+        .clone()
+        .or_else(|| std::env::var("I3RS_OBS_PASSWORD").ok());
+
+    loop {
+        let Some(mut obs) = Obs::connect(&config.url, password.as_deref()).await? else {
+            api.hide()?;
+            timer.tick().await;
+            continue;
+        };
+
+        loop {
+            let Ok(status) = obs.status().await else {
+                break;
+            };
+
+            let dropped = status
+                .stream
+                .filter(|s| s.output_total_frames > 0)
+                .map(|s| s.output_skipped_frames as f64 / s.output_total_frames as f64 * 100.0);
+
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = match dropped {
+                Some(d) if d > config.critical => State::Critical,
+                Some(d) if d > config.warning => State::Warning,
+                _ if status.stream.is_some() || status.record.is_some() => State::Info,
+                _ => State::Idle,
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("webcam"),
+                "scene" => Value::text(status.scene),
+                [if status.stream.is_some()] "streaming" => Value::flag(),
+                [if let Some(s) = status.stream] "stream_time" => Value::seconds((s.output_duration / 1000.0).floor()),
+                [if let Some(d) = dropped] "dropped" => Value::percents(d),
+                [if status.record.is_some()] "recording" => Value::flag(),
+                [if let Some(r) = status.record] "record_time" => Value::seconds((r.output_duration / 1000.0).floor()),
+                [if status.record.is_some_and(|r| r.output_paused)] "paused" => Value::flag(),
+            });
+            api.set_widget(widget)?;
+
+            loop {
+                select! {
+                    _ = timer.tick() => break,
+                    _ = api.wait_for_update_request() => break,
+                    // Any event changes the status, and an error means that OBS was closed
+                    _ = obs.event() => break,
+                    Some(action) = actions.recv() => match action.as_ref() {
+                        "toggle_record" => {
+                            let _: serde_json::Value = obs.request("ToggleRecord").await?;
+                        }
+                        "toggle_stream" => {
+                            let _: serde_json::Value = obs.request("ToggleStream").await?;
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Status {
+    scene: String,
+    /// Absent while not streaming
+    stream: Option<OutputStatus>,
+    /// Absent while not recording
+    record: Option<OutputStatus>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+struct OutputStatus {
+    output_active: bool,
+    /// Only reported for recordings
+    #[serde(default)]
+    output_paused: bool,
+    /// In milliseconds
+    output_duration: f64,
+    /// Only reported for streams
+    #[serde(default)]
+    output_skipped_frames: u64,
+    #[serde(default)]
+    output_total_frames: u64,
+}
+
+/// A message of the obs-websocket protocol
+#[derive(Deserialize)]
+struct ObsMessage {
+    op: u8,
+    d: serde_json::Value,
+}
+
+struct Obs {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_request_id: u64,
+}
+
+impl Obs {
+    /// Returns `None` if OBS isn't running
+    async fn connect(url: &str, password: Option<&str>) -> Result<Option<Self>> {
+        #[derive(Deserialize)]
+        struct Hello {
+            authentication: Option<Challenge>,
+        }
+
+        #[derive(Deserialize)]
+        struct Challenge {
+            challenge: String,
+            salt: String,
+        }
+
+        let Ok((stream, _)) = tokio_tungstenite::connect_async(url).await else {
+            return Ok(None);
+        };
+        let mut obs = Self {
+            stream,
+            next_request_id: 0,
+        };
+
+        let hello: Hello = obs.recv_op(0).await?;
+        let mut identify = serde_json::json!({
+            "rpcVersion": 1,
+            "eventSubscriptions": EVENT_SUBSCRIPTIONS,
+        });
+        if let Some(challenge) = hello.authentication {
+            let password = password.error("OBS requires a password")?;
+            identify["authentication"] =
+                authentication(password, &challenge.salt, &challenge.challenge).into();
+        }
+        obs.send(1, identify).await?;
+        // OBS closes the connection if the password is wrong
+        let _: serde_json::Value = obs
+            .recv_op(2)
+            .await
+            .or_error(|| "Failed to identify, the password may be wrong")?;
+        Ok(Some(obs))
+    }
+
+    async fn send(&mut self, op: u8, d: serde_json::Value) -> Result<()> {
+        self.stream
+            .send(Message::Text(
+                serde_json::json!({ "op": op, "d": d }).to_string(),
+            ))
+            .await
+            .error("Failed to send message")
+    }
+
+    async fn recv(&mut self) -> Result<ObsMessage> {
+        loop {
+            match self
+                .stream
+                .next()
+                .await
+                .error("OBS closed the connection")?
+                .error("Failed to receive message")?
+            {
+                Message::Text(text) => {
+                    return serde_json::from_str(&text).error("Failed to parse message");
+                }
+                Message::Close(_) => return Err(Error::new("OBS closed the connection")),
+                _ => (),
+            }
+        }
+    }
+
+    /// Receives messages until one with the given opcode, ignoring events
+    async fn recv_op<T: DeserializeOwned>(&mut self, op: u8) -> Result<T> {
+        loop {
+            let message = self.recv().await?;
+            if message.op == op {
+                return serde_json::from_value(message.d).error("Failed to parse message");
+            }
+        }
+    }
+
+    /// Waits for an event
+    async fn event(&mut self) -> Result<()> {
+        while self.recv().await?.op != 5 {}
+        Ok(())
+    }
+
+    async fn request<T: DeserializeOwned>(&mut self, request_type: &str) -> Result<T> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            request_id: String,
+            request_status: RequestStatus,
+            #[serde(default)]
+            response_data: serde_json::Value,
+        }
+
+        #[derive(Deserialize)]
+        struct RequestStatus {
+            result: bool,
+            comment: Option<String>,
+        }
+
+        self.next_request_id += 1;
+        let request_id = self.next_request_id.to_string();
+        self.send(
+            6,
+            serde_json::json!({ "requestType": request_type, "requestId": request_id }),
+        )
+        .await?;
+        loop {
+            let response: Response = self.recv_op(7).await?;
+            if response.request_id != request_id {
+                continue;
+            }
+            if !response.request_status.result {
+                return Err(Error::new(format!(
+                    "{request_type} failed: {}",
+                    response.request_status.comment.unwrap_or_default()
+                )));
+            }
+            return serde_json::from_value(response.response_data)
+                .error("Failed to parse response");
+        }
+    }
+
+    async fn status(&mut self) -> Result<Status> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Scene {
+            current_program_scene_name: String,
+        }
+
+        let scene: Scene = self.request("GetCurrentProgramScene").await?;
+        let stream: OutputStatus = self.request("GetStreamStatus").await?;
+        let record: OutputStatus = self.request("GetRecordStatus").await?;
+        Ok(Status {
+            scene: scene.current_program_scene_name,
+            stream: stream.output_active.then_some(stream),
+            record: record.output_active.then_some(record),
+        })
+    }
+}
+
+/// Computes the authentication string from the password and the challenge sent by OBS
+fn authentication(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{password}{salt}")));
+    BASE64.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authentication() {
+        // The example of the protocol documentation
+        assert_eq!(
+            authentication(
+                "supersecretpassword",
+                "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+                "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY="
+            ),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+    }
+}
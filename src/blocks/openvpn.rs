@@ -0,0 +1,275 @@
+//! OpenVPN session
+//!
+//! This block reads the state of an OpenVPN session from its
+//! [management interface](https://openvpn.net/community-resources/management-interface/) and shows
+//! for how long it has been connected and the IP address assigned to it. The management interface
+//! must be enabled in the OpenVPN configuration, e.g. with `management localhost 7505` or
+//! `management /run/openvpn/work.sock unix`.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `management` | The `host:port` or the path of the unix socket of the management interface | `"localhost:7505"`
+//! `password` | The password of the management interface, if any | `None`
+//! `unit` | The systemd unit of the session, e.g. `"openvpn-client@work"`. If set, `restart` restarts this unit instead of sending `SIGUSR1` through the management interface. | `None`
+//! `format` | A string to customise the output of this block while connected. See below for available placeholders. | <code>\" $icon{ $ip\|} \"</code>
+//! `format_disconnected` | Same as `format` but for when the session is not connected | <code>\" $icon $state \"</code>
+//! `interval` | Update interval in seconds | `10`
+//!
+//! Placeholder | Value                                                                      | Type   | Unit
+//! ------------|----------------------------------------------------------------------------|--------|--------
+//! `icon`      | `net_vpn` while connected, `net_down` otherwise                            | Icon   | -
+//! `state`     | The state of the session in lower case, e.g. `connected`, `wait` or `down` | Text   | -
+//! `ip`        | The IP address assigned to the tunnel (absent if none)                     | Text   | -
+//! `remote`    | The address of the server (absent if unknown)                              | Text   | -
+//! `time`      | For how long the session has been in its current state                     | Number | Seconds
+//!
+//! `state` is `down` if the management interface can't be reached. The block is set to the good
+//! state while connected and to the critical state otherwise.
+//!
+//! Action    | Description         | Default button
+//! ----------|---------------------|---------------
+//! `restart` | Restart the session | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "openvpn"
+//! management = "/run/openvpn/work.sock"
+//! unit = "openvpn-client@work"
+//! format = " $icon work $time "
+//! ```
+//!
+//! # Icons Used
+//! - `net_vpn`
+//! - `net_down`
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, BufReader};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::process::Command;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("localhost:7505".into())]
+    pub management: String,
+    pub password: Option<String>,
+    pub unit: Option<String>,
+    pub format: FormatConfig,
+    pub format_disconnected: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Debug, PartialEq)]
+struct SessionState {
+    /// Unix timestamp of the last state change
+    since: u64,
+    state: String,
+    local_ip: Option<String>,
+    remote_ip: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "restart")])?;
+
+    let format = config.format.with_default(" $icon{ $ip|} ")?;
+    let format_disconnected = config.format_disconnected.with_default(" $icon $state ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let session = query(config, "state").await.ok().and_then(|lines| {
+            lines
+                .iter()
+                .filter_map(|line| parse_state(line))
+                .next_back()
+        });
+
+        let mut widget = Widget::new();
+        match &session {
+            Some(session) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                let connected = session.state == "CONNECTED";
+                widget.set_format(if connected {
+                    format.clone()
+                } else {
+                    format_disconnected.clone()
+                });
+                widget.state = if connected {
+                    State::Good
+                } else {
+                    State::Critical
+                };
+                widget.set_values(map! {
+                    "icon" => Value::icon(if connected { "net_vpn" } else { "net_down" }),
+                    "state" => Value::text(session.state.to_lowercase()),
+                    "time" => Value::seconds(now.saturating_sub(session.since) as f64),
+                    [if let Some(ip) = &session.local_ip] "ip" => Value::text(ip.clone()),
+                    [if let Some(ip) = &session.remote_ip] "remote" => Value::text(ip.clone()),
+                });
+            }
+            None => {
+                widget.set_format(format_disconnected.clone());
+                widget.state = State::Critical;
+                widget.set_values(map! {
+                    "icon" => Value::icon("net_down"),
+                    "state" => Value::text("down".into()),
+                });
+            }
+        }
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "restart" => {
+                        restart(config).await?;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+async fn restart(config: &Config) -> Result<()> {
+    match &config.unit {
+        Some(unit) => {
+            let status = Command::new("systemctl")
+                .args(["restart", unit])
+                .status()
+                .await
+                .error("Failed to run systemctl")?;
+            if !status.success() {
+                return Err(Error::new(format!("Failed to restart {unit}")));
+            }
+        }
+        None => {
+            query(config, "signal SIGUSR1").await?;
+        }
+    }
+    Ok(())
+}
+
+/// Sends a command to the management interface and returns the lines of the response
+async fn query(config: &Config, command: &str) -> Result<Vec<String>> {
+    if config.management.starts_with('/') {
+        let stream = UnixStream::connect(&config.management)
+            .await
+            .error("Failed to connect to the management interface")?;
+        query_stream(stream, config.password.as_deref(), command).await
+    } else {
+        let stream = TcpStream::connect(&config.management)
+            .await
+            .error("Failed to connect to the management interface")?;
+        query_stream(stream, config.password.as_deref(), command).await
+    }
+}
+
+async fn query_stream<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    password: Option<&str>,
+    command: &str,
+) -> Result<Vec<String>> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+
+    if let Some(password) = password {
+        // The interface asks for the password before anything else
+        stream
+            .get_mut()
+            .write_all(format!("{password}\n").as_bytes())
+            .await
+            .error("Failed to write to the management interface")?;
+    }
+    stream
+        .get_mut()
+        .write_all(format!("{command}\n").as_bytes())
+        .await
+        .error("Failed to write to the management interface")?;
+
+    let mut lines = Vec::new();
+    loop {
+        line.clear();
+        if stream
+            .read_line(&mut line)
+            .await
+            .error("Failed to read from the management interface")?
+            == 0
+        {
+            return Err(Error::new("The management interface closed the connection"));
+        }
+        let line = line.trim_end();
+        // Skip real-time notifications. The password prompt has no line break, so it ends up in
+        // front of the confirmation of the password.
+        if line.starts_with('>') || line.starts_with("ENTER PASSWORD") {
+            continue;
+        }
+        if let Some(err) = line.strip_prefix("ERROR: ") {
+            return Err(Error::new(format!(
+                "The management interface returned an error: {err}"
+            )));
+        }
+        if line == "END" || line.starts_with("SUCCESS:") {
+            return Ok(lines);
+        }
+        lines.push(line.to_owned());
+    }
+}
+
+/// Parses a line of the response to `state`, e.g.
+/// `1718000000,CONNECTED,SUCCESS,10.8.0.6,203.0.113.1,1194,,`
+fn parse_state(line: &str) -> Option<SessionState> {
+    let mut fields = line.split(',');
+    let since = fields.next()?.parse().ok()?;
+    let state = fields.next()?.to_owned();
+    let _description = fields.next()?;
+    let non_empty = |s: Option<&str>| s.filter(|s| !s.is_empty()).map(String::from);
+    Some(SessionState {
+        since,
+        state,
+        local_ip: non_empty(fields.next()),
+        remote_ip: non_empty(fields.next()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(
+            parse_state("1718000000,CONNECTED,SUCCESS,10.8.0.6,203.0.113.1,1194,,"),
+            Some(SessionState {
+                since: 1718000000,
+                state: "CONNECTED".into(),
+                local_ip: Some("10.8.0.6".into()),
+                remote_ip: Some("203.0.113.1".into()),
+            })
+        );
+        assert_eq!(
+            parse_state("1718000000,WAIT,,,,,,"),
+            Some(SessionState {
+                since: 1718000000,
+                state: "WAIT".into(),
+                local_ip: None,
+                remote_ip: None,
+            })
+        );
+        assert_eq!(parse_state("END"), None);
+    }
+}
@@ -5,6 +5,17 @@
 //! - `pacman` for Arch based system
 //! - `aur` for Arch based system
 //! - `dnf` for Fedora based system
+//! - `zypper` for openSUSE based system
+//! - `xbps` for Void Linux
+//! - `apk` for Alpine Linux
+//! - `flatpak` for Flatpak applications and runtimes
+//! - `nix` for inputs of a Nix flake (e.g. a NixOS or home-manager configuration)
+//! - `snap` for Snap packages
+//! - `fwupd` for device firmware
+//!
+//! Any number of package managers can be combined in one block, with `$total` being the sum of
+//! their updates. The block is in the idle state if `$total` is zero, unless `$zypper_patches` is
+//! used and patches are pending.
 //!
 //! # Configuration
 //!
@@ -19,7 +30,9 @@
 //! `critical_updates_regex` | Display block as critical if updates matching regex are available. | `None`
 //! `ignore_updates_regex` | Doesn't include updates matching regex in the count. | `None`
 //! `ignore_phased_updates` | Doesn't include potentially held back phased updates in the count. (For Debian/Ubuntu based system) | `false`
-//! `aur_command` | AUR command to check available updates, which outputs in the same format as pacman. e.g. `yay -Qua` (For Arch based system) | Required if `$aur` are used
+//! `aur_command` | AUR command to check available updates, which outputs in the same format as pacman. e.g. `yay -Qua` (For Arch based system). If not set, the AUR RPC is queried directly. | `None`
+//! `aur_ignore` | Names of foreign packages which are not checked against the AUR RPC | `[]`
+//! `nix_flake` | Path to the directory of the flake whose inputs are checked. Supports path expansions e.g. `~`. | `"/etc/nixos"`
 //!
 //!  Placeholder | Value                                                                            | Type   | Unit
 //! -------------|----------------------------------------------------------------------------------|--------|-----
@@ -28,6 +41,15 @@
 //! `pacman`     | Number of updates available in Arch based system                                 | Number | -
 //! `aur`        | Number of updates available in Arch based system                                 | Number | -
 //! `dnf`        | Number of updates available in Fedora based system                               | Number | -
+//! `dnf_security` | Number of `dnf` updates which fix a security issue                              | Number | -
+//! `zypper`     | Number of updates available in openSUSE based system                             | Number | -
+//! `zypper_patches` | Present if patches are pending in openSUSE based system                      | Flag   | -
+//! `xbps`       | Number of updates available in Void Linux                                        | Number | -
+//! `apk`        | Number of updates available in Alpine Linux                                      | Number | -
+//! `flatpak`    | Number of Flatpak updates available                                              | Number | -
+//! `nix`        | Number of outdated inputs of the Nix flake                                       | Number | -
+//! `snap`       | Number of Snap updates available                                                 | Number | -
+//! `fwupd`      | Number of devices with a firmware update available                               | Number | -
 //! `total`      | Number of updates available in all package manager listed                        | Number | -
 //!
 //! # Apt
@@ -36,6 +58,32 @@
 //!
 //! Tip: You can grab the list of available updates using `APT_CONFIG=/tmp/i3rs-apt/apt.conf apt list --upgradable`
 //!
+//! # XBPS
+//!
+//! Behind the scenes this uses `xbps-install --memory-sync --update --dry-run`, which fetches the
+//! repository index into memory, so it doesn't require root privileges and doesn't touch the
+//! system's package database.
+//!
+//! # Apk
+//!
+//! Behind the scenes this uses `apk version -l '<'`, which compares the installed packages against
+//! the locally cached repository indexes. Refreshing the indexes requires root privileges, so
+//! make sure `apk update` is run periodically, e.g. by a cron job.
+//!
+//! # Nix
+//!
+//! The `nix` package manager checks the direct inputs of `nix_flake` which track a git branch
+//! (`github`, `gitlab` and `git` inputs) by comparing the revision in `flake.lock` with the latest
+//! one reported by `git ls-remote`. Inputs pinned to a revision are ignored. The system is
+//! considered outdated if the lock file is, so make sure the running system is built from it.
+//!
+//! Tip: You can run `nix flake update` in the flake's directory to update all inputs.
+//!
+//! # Fwupd
+//!
+//! Behind the scenes this uses `fwupdmgr get-updates`, which only uses the locally cached
+//! metadata. Make sure it is refreshed periodically, e.g. by enabling `fwupd-refresh.timer`.
+//!
 //! # Pacman
 //!
 //! Requires fakeroot to be installed (only required for pacman).
@@ -45,6 +93,13 @@
 //!
 //! Note: `pikaur` may hang the whole block if there is no internet connectivity [reference](https://github.com/actionless/pikaur/issues/595). In that case, try a different AUR helper.
 //!
+//! ## AUR
+//!
+//! If `aur_command` is not set, the versions of foreign packages (`pacman -Qm`) are compared with
+//! the ones reported by the [AUR RPC](https://wiki.archlinux.org/title/Aurweb_RPC_interface), so no
+//! AUR helper is required. To respect the AUR's rate limits, the RPC is queried at most once every
+//! 30 minutes, regardless of `interval`. Packages which are not in the AUR are skipped.
+//!
 //! ### Pacman hook
 //!
 //! Tip: On Arch Linux you can setup a `pacman` hook to signal i3status-rs to update after packages
@@ -129,6 +184,19 @@
 //! aur_command = "yay -Qua"
 //! ```
 //!
+//! AUR only config, without an AUR helper:
+//!
+//! ```toml
+//! [[block]]
+//! block = "packages"
+//! package_manager = ["aur"]
+//! interval = 3600
+//! format = " $icon $aur AUR updates "
+//! format_singular = " $icon One AUR update "
+//! format_up_to_date = ""
+//! aur_ignore = ["my-local-package"]
+//! ```
+//!
 //!
 //! Dnf only config:
 //!
@@ -146,6 +214,56 @@
 //! cmd = "dnf list -q --upgrades | tail -n +2 | rofi -dmenu"
 //! ```
 //!
+//! Dnf config highlighting security updates:
+//!
+//! ```toml
+//! [[block]]
+//! block = "packages"
+//! interval = 1800
+//! format = " $icon $dnf.eng(w:1) ($dnf_security.eng(w:1) security) "
+//! format_singular = " $icon $dnf.eng(w:1) ($dnf_security.eng(w:1) security) "
+//! format_up_to_date = " $icon system up to date "
+//! ```
+//!
+//! Zypper config, which also tells about pending patches:
+//!
+//! ```toml
+//! [[block]]
+//! block = "packages"
+//! interval = 1800
+//! format = " $icon $zypper.eng(w:1) updates{$zypper_patches (patches)|} "
+//! format_singular = " $icon One update available{$zypper_patches (patches)|} "
+//! format_up_to_date = " $icon system up to date{$zypper_patches (patches)|} "
+//! ```
+//!
+//! Flatpak config, which runs the update on left click:
+//!
+//! ```toml
+//! [[block]]
+//! block = "packages"
+//! package_manager = ["flatpak"]
+//! interval = 3600
+//! format = " $icon $flatpak.eng(w:1) flatpak updates "
+//! format_singular = " $icon One flatpak update "
+//! format_up_to_date = ""
+//! [[block.click]]
+//! button = "left"
+//! cmd = "alacritty -e flatpak update"
+//! sync = true
+//! update = true
+//! ```
+//!
+//! All updates of an Arch based system in a single block, with per source counts:
+//!
+//! ```toml
+//! [[block]]
+//! block = "packages"
+//! interval = 3600
+//! format = " $icon $total.eng(w:1) ($pacman.eng(w:1)/$aur.eng(w:1)/$flatpak.eng(w:1)/$snap.eng(w:1)/$fwupd.eng(w:1)) "
+//! format_singular = " $icon One update available "
+//! format_up_to_date = ""
+//! ```
+//!
 //! Multiple package managers config:
 //!
 //! Update the list of pending updates every thirty minutes (1800 seconds):
@@ -179,6 +297,27 @@ use pacman::{Aur, Pacman};
 pub mod dnf;
 use dnf::Dnf;
 
+pub mod zypper;
+use zypper::Zypper;
+
+pub mod xbps;
+use xbps::Xbps;
+
+pub mod apk;
+use apk::Apk;
+
+pub mod flatpak;
+use flatpak::Flatpak;
+
+pub mod nix;
+use nix::Nix;
+
+pub mod snap;
+use snap::Snap;
+
+pub mod fwupd;
+use fwupd::Fwupd;
+
 use regex::Regex;
 
 use super::prelude::*;
@@ -197,6 +336,9 @@ pub struct Config {
     pub ignore_updates_regex: Option<String>,
     pub ignore_phased_updates: bool,
     pub aur_command: Option<String>,
+    pub aur_ignore: Vec<String>,
+    #[default("/etc/nixos".into())]
+    pub nix_flake: ShellString,
 }
 
 #[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
@@ -206,6 +348,13 @@ pub enum PackageManager {
     Pacman,
     Aur,
     Dnf,
+    Zypper,
+    Xbps,
+    Apk,
+    Flatpak,
+    Nix,
+    Snap,
+    Fwupd,
 }
 
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
@@ -228,22 +377,24 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         };
     }
 
-    let apt = any_format_contains!("apt");
-    let aur = any_format_contains!("aur");
-    let pacman = any_format_contains!("pacman");
-    let dnf = any_format_contains!("dnf");
-
-    if !config.package_manager.contains(&PackageManager::Apt) && apt {
-        config.package_manager.push(PackageManager::Apt);
-    }
-    if !config.package_manager.contains(&PackageManager::Pacman) && pacman {
-        config.package_manager.push(PackageManager::Pacman);
-    }
-    if !config.package_manager.contains(&PackageManager::Aur) && aur {
-        config.package_manager.push(PackageManager::Aur);
-    }
-    if !config.package_manager.contains(&PackageManager::Dnf) && dnf {
-        config.package_manager.push(PackageManager::Dnf);
+    for (name, package_manager) in [
+        ("apt", PackageManager::Apt),
+        ("pacman", PackageManager::Pacman),
+        ("aur", PackageManager::Aur),
+        ("dnf", PackageManager::Dnf),
+        ("dnf_security", PackageManager::Dnf),
+        ("zypper", PackageManager::Zypper),
+        ("zypper_patches", PackageManager::Zypper),
+        ("xbps", PackageManager::Xbps),
+        ("apk", PackageManager::Apk),
+        ("flatpak", PackageManager::Flatpak),
+        ("nix", PackageManager::Nix),
+        ("snap", PackageManager::Snap),
+        ("fwupd", PackageManager::Fwupd),
+    ] {
+        if any_format_contains!(name) && !config.package_manager.contains(&package_manager) {
+            config.package_manager.push(package_manager);
+        }
     }
 
     let warning_updates_regex = config
@@ -272,9 +423,17 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             PackageManager::Apt => Box::new(Apt::new(config.ignore_phased_updates).await?),
             PackageManager::Pacman => Box::new(Pacman::new().await?),
             PackageManager::Aur => Box::new(Aur::new(
-                config.aur_command.clone().error("aur_command is not set")?,
+                config.aur_command.clone(),
+                config.aur_ignore.clone(),
             )),
-            PackageManager::Dnf => Box::new(Dnf::new()),
+            PackageManager::Dnf => Box::new(Dnf::new(any_format_contains!("dnf_security"))),
+            PackageManager::Zypper => Box::new(Zypper::new(any_format_contains!("zypper_patches"))),
+            PackageManager::Xbps => Box::new(Xbps::new()),
+            PackageManager::Apk => Box::new(Apk::new()),
+            PackageManager::Flatpak => Box::new(Flatpak::new()),
+            PackageManager::Nix => Box::new(Nix::new(config.nix_flake.expand()?.into_owned())),
+            PackageManager::Snap => Box::new(Snap::new()),
+            PackageManager::Fwupd => Box::new(Fwupd::new()),
         });
     }
 
@@ -283,6 +442,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
 
         let mut critical = false;
         let mut warning = false;
+        let mut pending = false;
         let mut total_count = 0;
 
         // Iterate over the all package manager listed in Config
@@ -297,6 +457,11 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             package_manager_map.insert(package_manager.name(), Value::number(updates_count));
             total_count += updates_count;
 
+            for extra in package_manager.get_extra_values(&updates).await? {
+                pending |= extra.pending;
+                package_manager_map.insert(extra.name, extra.value);
+            }
+
             warning |= warning_updates_regex
                 .as_ref()
                 .is_some_and(|regex| has_matching_update(&updates, regex));
@@ -317,17 +482,14 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         });
         widget.set_values(package_manager_map);
 
-        widget.state = match total_count {
-            0 => State::Idle,
-            _ => {
-                if critical {
-                    State::Critical
-                } else if warning {
-                    State::Warning
-                } else {
-                    State::Info
-                }
-            }
+        widget.state = if total_count == 0 && !pending {
+            State::Idle
+        } else if critical {
+            State::Critical
+        } else if warning {
+            State::Warning
+        } else {
+            State::Info
         };
         api.set_widget(widget)?;
 
@@ -339,10 +501,23 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
 }
 
 #[async_trait]
-pub trait Backend {
+pub trait Backend: Sync {
     fn name(&self) -> Cow<'static, str>;
 
     async fn get_updates_list(&self) -> Result<Vec<String>>;
+
+    /// Placeholders besides the number of updates, derived from the result of `get_updates_list`
+    /// after `ignore_updates_regex` has been applied.
+    async fn get_extra_values(&self, _updates: &[String]) -> Result<Vec<ExtraValue>> {
+        Ok(Vec::new())
+    }
+}
+
+pub struct ExtraValue {
+    pub name: Cow<'static, str>,
+    pub value: Value,
+    /// Whether the block should leave the idle state because of this value
+    pub pending: bool,
 }
 
 pub fn has_matching_update(updates: &[String], regex: &Regex) -> bool {
@@ -0,0 +1,39 @@
+use tokio::process::Command;
+
+use super::*;
+
+#[derive(Default)]
+pub struct Apk;
+
+impl Apk {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[async_trait]
+impl Backend for Apk {
+    fn name(&self) -> Cow<'static, str> {
+        "apk".into()
+    }
+
+    async fn get_updates_list(&self) -> Result<Vec<String>> {
+        let stdout = Command::new("apk")
+            .env("LC_ALL", "C")
+            .args(["version", "-l", "<"])
+            .output()
+            .await
+            .error("Failed to run apk version")?
+            .stdout;
+        let updates = String::from_utf8(stdout).error("apk produced non-UTF8 output")?;
+
+        // Skip the `Installed: Available:` header
+        let updates = updates
+            .lines()
+            .filter(|line| line.contains(" < "))
+            .map(|line| line.to_string())
+            .collect();
+
+        Ok(updates)
+    }
+}
@@ -1,13 +1,16 @@
+use std::collections::HashSet;
+
 use tokio::process::Command;
 
 use super::super::packages::*;
 
-#[derive(Default)]
-pub struct Dnf;
+pub struct Dnf {
+    security: bool,
+}
 
 impl Dnf {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(security: bool) -> Self {
+        Self { security }
     }
 }
 
@@ -18,15 +21,8 @@ impl Backend for Dnf {
     }
 
     async fn get_updates_list(&self) -> Result<Vec<String>> {
-        let stdout = Command::new("sh")
-            .env("LC_LANG", "C")
-            .args(["-c", "dnf check-update -q --skip-broken"])
-            .output()
-            .await
-            .error("Failed to run dnf check-update")?
-            .stdout;
-        let updates = String::from_utf8(stdout).error("dnf produced non-UTF8 output")?;
-        let updates: Vec<String> = updates
+        let stdout = run_dnf("check-update -q --skip-broken").await?;
+        let updates: Vec<String> = stdout
             .lines()
             .filter(|line| line.len() > 1)
             .map(|lines| lines.to_string())
@@ -34,4 +30,79 @@ impl Backend for Dnf {
 
         Ok(updates)
     }
+
+    async fn get_extra_values(&self, updates: &[String]) -> Result<Vec<ExtraValue>> {
+        if !self.security {
+            return Ok(Vec::new());
+        }
+        // `check-update` has just refreshed the metadata, so this doesn't hit the repositories
+        // again
+        let advisories = run_dnf("updateinfo list --updates --security -q").await?;
+        let count = count_security_updates(updates, &advisories);
+        Ok(vec![ExtraValue {
+            name: "dnf_security".into(),
+            value: Value::number(count),
+            pending: count > 0,
+        }])
+    }
+}
+
+async fn run_dnf(args: &str) -> Result<String> {
+    let stdout = Command::new("sh")
+        .env("LC_LANG", "C")
+        .args(["-c", &format!("dnf {args}")])
+        .output()
+        .await
+        .or_error(|| format!("Failed to run dnf {args}"))?
+        .stdout;
+    String::from_utf8(stdout).error("dnf produced non-UTF8 output")
+}
+
+/// Counts the lines of `dnf check-update` whose package is affected by one of the security
+/// advisories listed by `dnf updateinfo list`
+fn count_security_updates(updates: &[String], advisories: &str) -> usize {
+    // Advisory lines look like `FEDORA-2026-1a2b3c4d5e Important/Sec. curl-8.9.1-2.fc41.x86_64`
+    let affected: HashSet<String> = advisories
+        .lines()
+        .filter_map(|line| {
+            let nevra = line.split_whitespace().nth(2)?;
+            let (nvr, arch) = nevra.rsplit_once('.')?;
+            let (name, _release) = nvr.rsplit_once('-')?;
+            let (name, _version) = name.rsplit_once('-')?;
+            Some(format!("{name}.{arch}"))
+        })
+        .collect();
+    // Update lines look like `curl.x86_64 8.9.1-3.fc41 updates`
+    updates
+        .iter()
+        .filter(|update| {
+            update
+                .split_whitespace()
+                .next()
+                .is_some_and(|package| affected.contains(package))
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_security_updates() {
+        let updates = [
+            "curl.x86_64                 8.9.1-3.fc41         updates",
+            "libcurl.x86_64              8.9.1-3.fc41         updates",
+            "python3-requests.noarch     2.32.3-2.fc41        updates",
+            "kernel-core.x86_64          6.11.4-301.fc41      updates",
+        ]
+        .map(String::from);
+        let advisories = "\
+FEDORA-2026-1a2b3c4d5e Important/Sec.  curl-8.9.1-2.fc41.x86_64
+FEDORA-2026-1a2b3c4d5e Important/Sec.  libcurl-8.9.1-2.fc41.x86_64
+FEDORA-2026-6f7a8b9c0d Moderate/Sec.   kernel-core-6.11.4-301.fc41.x86_64
+";
+        assert_eq!(count_security_updates(&updates, advisories), 3);
+        assert_eq!(count_security_updates(&updates, ""), 0);
+    }
 }
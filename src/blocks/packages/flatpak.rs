@@ -0,0 +1,43 @@
+use tokio::process::Command;
+
+use super::*;
+
+#[derive(Default)]
+pub struct Flatpak;
+
+impl Flatpak {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[async_trait]
+impl Backend for Flatpak {
+    fn name(&self) -> Cow<'static, str> {
+        "flatpak".into()
+    }
+
+    async fn get_updates_list(&self) -> Result<Vec<String>> {
+        let output = Command::new("flatpak")
+            .env("LC_ALL", "C")
+            .args(["remote-ls", "--updates", "--columns=application,version"])
+            .output()
+            .await
+            .error("Failed to run flatpak remote-ls")?;
+        if !output.status.success() {
+            return Err(Error::new(format!(
+                "flatpak remote-ls failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let updates = String::from_utf8(output.stdout).error("flatpak produced non-UTF8 output")?;
+
+        let updates = updates
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect();
+
+        Ok(updates)
+    }
+}
@@ -0,0 +1,121 @@
+use tokio::process::Command;
+
+use super::*;
+
+#[derive(Default)]
+pub struct Fwupd;
+
+impl Fwupd {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[async_trait]
+impl Backend for Fwupd {
+    fn name(&self) -> Cow<'static, str> {
+        "fwupd".into()
+    }
+
+    async fn get_updates_list(&self) -> Result<Vec<String>> {
+        let output = Command::new("fwupdmgr")
+            .env("LC_ALL", "C")
+            .args([
+                "get-updates",
+                "--json",
+                "--no-unreported-check",
+                "--no-metadata-check",
+            ])
+            .output()
+            .await
+            .error("Failed to run fwupdmgr get-updates")?;
+        // Exit code 2 means that there is nothing to do
+        match output.status.code() {
+            Some(0) => (),
+            Some(2) => return Ok(Vec::new()),
+            _ => {
+                return Err(Error::new(format!(
+                    "fwupdmgr get-updates failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )))
+            }
+        }
+        let updates =
+            String::from_utf8(output.stdout).error("fwupdmgr produced non-UTF8 output")?;
+
+        parse_updates(&updates)
+    }
+}
+
+fn parse_updates(json: &str) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Updates {
+        #[serde(default)]
+        devices: Vec<Device>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Device {
+        name: String,
+        version: Option<String>,
+        #[serde(default)]
+        releases: Vec<Release>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Release {
+        version: String,
+    }
+
+    let updates: Updates = serde_json::from_str(json).error("Failed to parse fwupdmgr output")?;
+
+    // Releases are sorted from newest to oldest
+    Ok(updates
+        .devices
+        .into_iter()
+        .filter_map(|device| {
+            let release = device.releases.into_iter().next()?;
+            Some(format!(
+                "{} {} -> {}",
+                device.name,
+                device.version.as_deref().unwrap_or("?"),
+                release.version
+            ))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_updates() {
+        let json = r#"{
+          "Devices" : [
+            {
+              "Name" : "System Firmware",
+              "DeviceId" : "a45df35ac0e948ee180fe216a5f703f32dda163f",
+              "Version" : "0.1.21",
+              "Releases" : [
+                { "Version" : "0.1.23", "Summary" : "Firmware" },
+                { "Version" : "0.1.22", "Summary" : "Firmware" }
+              ]
+            },
+            {
+              "Name" : "UEFI dbx",
+              "Version" : "371",
+              "Releases" : []
+            }
+          ]
+        }"#;
+        assert_eq!(
+            parse_updates(json).unwrap(),
+            ["System Firmware 0.1.21 -> 0.1.23"]
+        );
+        assert!(parse_updates("{}").unwrap().is_empty());
+    }
+}
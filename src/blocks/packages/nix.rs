@@ -0,0 +1,186 @@
+use tokio::process::Command;
+
+use super::*;
+
+pub struct Nix {
+    flake: String,
+}
+
+impl Nix {
+    pub fn new(flake: String) -> Self {
+        Nix { flake }
+    }
+}
+
+#[async_trait]
+impl Backend for Nix {
+    fn name(&self) -> Cow<'static, str> {
+        "nix".into()
+    }
+
+    async fn get_updates_list(&self) -> Result<Vec<String>> {
+        let path = format!("{}/flake.lock", self.flake.trim_end_matches('/'));
+        let lock = tokio::fs::read_to_string(&path)
+            .await
+            .or_error(|| format!("Failed to read {path}"))?;
+        let inputs = parse_flake_lock(&lock)?;
+
+        let mut updates = Vec::new();
+        for input in inputs {
+            let output = Command::new("git")
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .args([
+                    "ls-remote",
+                    &input.url,
+                    input.git_ref.as_deref().unwrap_or("HEAD"),
+                ])
+                .output()
+                .await
+                .error("Failed to run git ls-remote")?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            // Inputs which can't be checked (e.g. private repositories) are not reported
+            let Some(latest) = stdout.split_whitespace().next() else {
+                continue;
+            };
+            if latest != input.rev {
+                updates.push(format!(
+                    "{} {} -> {}",
+                    input.name,
+                    &input.rev[..input.rev.len().min(7)],
+                    &latest[..latest.len().min(7)],
+                ));
+            }
+        }
+
+        Ok(updates)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Input {
+    name: String,
+    url: String,
+    git_ref: Option<String>,
+    rev: String,
+}
+
+#[derive(Deserialize)]
+struct FlakeLock {
+    nodes: HashMap<String, Node>,
+    root: String,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    #[serde(default)]
+    inputs: HashMap<String, serde_json::Value>,
+    locked: Option<Locked>,
+    original: Option<Original>,
+}
+
+#[derive(Deserialize)]
+struct Locked {
+    #[serde(rename = "type")]
+    kind: String,
+    rev: Option<String>,
+    owner: Option<String>,
+    repo: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Original {
+    #[serde(rename = "ref")]
+    git_ref: Option<String>,
+    rev: Option<String>,
+}
+
+/// Returns the direct inputs of the flake which follow a git branch
+fn parse_flake_lock(lock: &str) -> Result<Vec<Input>> {
+    let lock: FlakeLock = serde_json::from_str(lock).error("Failed to parse flake.lock")?;
+    let root = lock.nodes.get(&lock.root).error("Invalid flake.lock")?;
+
+    let mut inputs = Vec::new();
+    for (name, node_name) in &root.inputs {
+        // Inputs which follow other inputs are represented as arrays
+        let Some(node) = node_name.as_str().and_then(|n| lock.nodes.get(n)) else {
+            continue;
+        };
+        let (Some(locked), Some(original)) = (&node.locked, &node.original) else {
+            continue;
+        };
+        // Inputs pinned to a revision can't be outdated
+        if original.rev.is_some() {
+            continue;
+        }
+        let Some(rev) = locked.rev.clone() else {
+            continue;
+        };
+        let url = match (locked.kind.as_str(), &locked.owner, &locked.repo) {
+            ("github", Some(owner), Some(repo)) => format!("https://github.com/{owner}/{repo}"),
+            ("gitlab", Some(owner), Some(repo)) => format!("https://gitlab.com/{owner}/{repo}"),
+            ("git", ..) => match &locked.url {
+                Some(url) => url.clone(),
+                None => continue,
+            },
+            _ => continue,
+        };
+        inputs.push(Input {
+            name: name.clone(),
+            url,
+            git_ref: original.git_ref.clone(),
+            rev,
+        });
+    }
+    inputs.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flake_lock() {
+        let lock = r#"{
+  "nodes": {
+    "home-manager": {
+      "inputs": { "nixpkgs": ["nixpkgs"] },
+      "locked": { "owner": "nix-community", "repo": "home-manager", "rev": "1111111111", "type": "github" },
+      "original": { "owner": "nix-community", "repo": "home-manager", "type": "github" }
+    },
+    "nixpkgs": {
+      "locked": { "owner": "NixOS", "repo": "nixpkgs", "rev": "2222222222", "type": "github" },
+      "original": { "owner": "NixOS", "ref": "nixos-unstable", "repo": "nixpkgs", "type": "github" }
+    },
+    "pinned": {
+      "locked": { "owner": "foo", "repo": "bar", "rev": "3333333333", "type": "github" },
+      "original": { "owner": "foo", "repo": "bar", "rev": "3333333333", "type": "github" }
+    },
+    "root": {
+      "inputs": { "home-manager": "home-manager", "nixpkgs": "nixpkgs", "pinned": "pinned" }
+    }
+  },
+  "root": "root",
+  "version": 7
+}"#;
+        assert_eq!(
+            parse_flake_lock(lock).unwrap(),
+            vec![
+                Input {
+                    name: "home-manager".into(),
+                    url: "https://github.com/nix-community/home-manager".into(),
+                    git_ref: None,
+                    rev: "1111111111".into(),
+                },
+                Input {
+                    name: "nixpkgs".into(),
+                    url: "https://github.com/NixOS/nixpkgs".into(),
+                    git_ref: Some("nixos-unstable".into()),
+                    rev: "2222222222".into(),
+                },
+            ]
+        );
+    }
+}
@@ -1,6 +1,9 @@
+use std::cmp::Ordering;
 use std::env;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Instant;
 
 use tokio::fs::{create_dir_all, symlink};
 use tokio::process::Command;
@@ -38,9 +41,17 @@ pub static PACMAN_DB: Lazy<PathBuf> = Lazy::new(|| {
 pub struct Pacman;
 
 pub struct Aur {
-    aur_command: String,
+    aur_command: Option<String>,
+    ignore: Vec<String>,
+    cache: Mutex<Option<(Instant, Vec<String>)>>,
 }
 
+/// The AUR asks to keep the number of RPC requests low, so results are reused for this long
+const AUR_RPC_MIN_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Maximum number of packages queried in a single RPC request
+const AUR_RPC_CHUNK_SIZE: usize = 100;
+
 impl Pacman {
     pub async fn new() -> Result<Self> {
         check_fakeroot_command_exists().await?;
@@ -50,8 +61,89 @@ impl Pacman {
 }
 
 impl Aur {
-    pub fn new(aur_command: String) -> Self {
-        Aur { aur_command }
+    pub fn new(aur_command: Option<String>, ignore: Vec<String>) -> Self {
+        Aur {
+            aur_command,
+            ignore,
+            cache: Mutex::new(None),
+        }
+    }
+
+    async fn run_aur_command(aur_command: &str) -> Result<Vec<String>> {
+        let stdout = Command::new("sh")
+            .args(["-c", aur_command])
+            .output()
+            .await
+            .or_error(|| format!("aur command: {aur_command} failed"))?
+            .stdout;
+        let updates = String::from_utf8(stdout)
+            .error("There was a problem while converting the aur command output to a string")?;
+
+        let updates = updates
+            .lines()
+            .filter(|line| !line.contains("[ignored]"))
+            .map(|line| line.to_string())
+            .collect();
+
+        Ok(updates)
+    }
+
+    /// Compares the installed foreign packages with the versions reported by the AUR RPC
+    async fn query_rpc(&self) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct RpcResponse {
+            results: Vec<RpcPackage>,
+        }
+
+        #[derive(Deserialize)]
+        struct RpcPackage {
+            #[serde(rename = "Name")]
+            name: String,
+            #[serde(rename = "Version")]
+            version: String,
+        }
+
+        let stdout = Command::new("pacman")
+            .env("LC_ALL", "C")
+            .arg("-Qm")
+            .output()
+            .await
+            .error("Failed to run pacman -Qm")?
+            .stdout;
+        let installed = String::from_utf8(stdout).error("Pacman produced non-UTF8 output")?;
+        let installed: HashMap<&str, &str> = installed
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .filter(|(name, _)| !self.ignore.iter().any(|i| i == name))
+            .collect();
+
+        let mut names: Vec<&str> = installed.keys().copied().collect();
+        names.sort_unstable();
+
+        let mut updates = Vec::new();
+        for chunk in names.chunks(AUR_RPC_CHUNK_SIZE) {
+            let query: Vec<(&str, &str)> = chunk.iter().map(|name| ("arg[]", *name)).collect();
+            let response: RpcResponse = REQWEST_CLIENT
+                .get("https://aur.archlinux.org/rpc/v5/info")
+                .query(&query)
+                .send()
+                .await
+                .error("Failed to query the AUR")?
+                .json()
+                .await
+                .error("Failed to parse AUR response")?;
+            for package in response.results {
+                let Some(&local) = installed.get(package.name.as_str()) else {
+                    continue;
+                };
+                if vercmp(local, &package.version) == Ordering::Less {
+                    updates.push(format!("{} {local} -> {}", package.name, package.version));
+                }
+            }
+        }
+        updates.sort_unstable();
+
+        Ok(updates)
     }
 }
 
@@ -132,25 +224,132 @@ impl Backend for Aur {
     }
 
     async fn get_updates_list(&self) -> Result<Vec<String>> {
-        let stdout = Command::new("sh")
-            .args(["-c", &self.aur_command])
-            .output()
-            .await
-            .or_error(|| format!("aur command: {} failed", self.aur_command))?
-            .stdout;
-        let updates = String::from_utf8(stdout)
-            .error("There was a problem while converting the aur command output to a string")?;
-
-        let updates = updates
-            .lines()
-            .filter(|line| !line.contains("[ignored]"))
-            .map(|line| line.to_string())
-            .collect();
+        if let Some(aur_command) = &self.aur_command {
+            return Self::run_aur_command(aur_command).await;
+        }
 
+        if let Some((time, updates)) = &*self.cache.lock().unwrap() {
+            if time.elapsed() < AUR_RPC_MIN_INTERVAL {
+                return Ok(updates.clone());
+            }
+        }
+        let updates = self.query_rpc().await?;
+        *self.cache.lock().unwrap() = Some((Instant::now(), updates.clone()));
         Ok(updates)
     }
 }
 
+/// Compares two package versions the same way as pacman's `vercmp`
+fn vercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    // Splits `[epoch:]version[-release]`
+    fn parse_evr(evr: &str) -> (&str, &str, Option<&str>) {
+        let (epoch, rest) = match evr.find(|c: char| !c.is_ascii_digit()) {
+            Some(i) if evr[i..].starts_with(':') => (&evr[..i], &evr[i + 1..]),
+            _ => ("0", evr),
+        };
+        let epoch = if epoch.is_empty() { "0" } else { epoch };
+        match rest.rsplit_once('-') {
+            Some((version, release)) => (epoch, version, Some(release)),
+            None => (epoch, rest, None),
+        }
+    }
+
+    let (epoch_a, version_a, release_a) = parse_evr(a);
+    let (epoch_b, version_b, release_b) = parse_evr(b);
+    rpmvercmp(epoch_a, epoch_b)
+        .then_with(|| rpmvercmp(version_a, version_b))
+        .then_with(|| match (release_a, release_b) {
+            (Some(a), Some(b)) => rpmvercmp(a, b),
+            _ => Ordering::Equal,
+        })
+}
+
+/// Port of libalpm's `rpmvercmp`
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut one, mut two) = (0, 0);
+
+    while one < a.len() && two < b.len() {
+        let (start_one, start_two) = (one, two);
+        while one < a.len() && !a[one].is_ascii_alphanumeric() {
+            one += 1;
+        }
+        while two < b.len() && !b[two].is_ascii_alphanumeric() {
+            two += 1;
+        }
+        if one == a.len() || two == b.len() {
+            break;
+        }
+        // Different amounts of separators
+        if one - start_one != two - start_two {
+            return (one - start_one).cmp(&(two - start_two));
+        }
+
+        let is_num = a[one].is_ascii_digit();
+        let (seg_start_one, seg_start_two) = (one, two);
+        if is_num {
+            while one < a.len() && a[one].is_ascii_digit() {
+                one += 1;
+            }
+            while two < b.len() && b[two].is_ascii_digit() {
+                two += 1;
+            }
+        } else {
+            while one < a.len() && a[one].is_ascii_alphabetic() {
+                one += 1;
+            }
+            while two < b.len() && b[two].is_ascii_alphabetic() {
+                two += 1;
+            }
+        }
+        let seg_one = &a[seg_start_one..one];
+        let seg_two = &b[seg_start_two..two];
+
+        // Segments of different types: numeric segments are newer than alphabetic ones
+        if seg_two.is_empty() {
+            return if is_num {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let ordering = if is_num {
+            let seg_one = trim_leading_zeros(seg_one);
+            let seg_two = trim_leading_zeros(seg_two);
+            seg_one
+                .len()
+                .cmp(&seg_two.len())
+                .then_with(|| seg_one.cmp(seg_two))
+        } else {
+            seg_one.cmp(seg_two)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (a.get(one), b.get(two)) {
+        (None, None) => Ordering::Equal,
+        (None, Some(c)) if !c.is_ascii_alphabetic() => Ordering::Less,
+        (Some(c), _) if c.is_ascii_alphabetic() => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+fn trim_leading_zeros(s: &[u8]) -> &[u8] {
+    let zeros = s.iter().take_while(|&&c| c == b'0').count();
+    &s[zeros..]
+}
+
 async fn check_fakeroot_command_exists() -> Result<()> {
     if !has_command("fakeroot").await? {
         Err(Error::new("fakeroot not found"))
@@ -158,3 +357,21 @@ async fn check_fakeroot_command_exists() -> Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vercmp() {
+        assert_eq!(vercmp("1.0.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(vercmp("1.0.0-1", "1.0.0-2"), Ordering::Less);
+        assert_eq!(vercmp("1.0.10", "1.0.9"), Ordering::Greater);
+        assert_eq!(vercmp("1.0a", "1.0"), Ordering::Less);
+        assert_eq!(vercmp("1.0", "1.0.1"), Ordering::Less);
+        assert_eq!(vercmp("1:1.0", "2.0"), Ordering::Greater);
+        assert_eq!(vercmp("1.0alpha", "1.0beta"), Ordering::Less);
+        assert_eq!(vercmp("1.0", "1.0-1"), Ordering::Equal);
+        assert_eq!(vercmp("r1234.abcdef-1", "r1235.abcdef-1"), Ordering::Less);
+    }
+}
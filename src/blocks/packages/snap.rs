@@ -0,0 +1,46 @@
+use tokio::process::Command;
+
+use super::*;
+
+#[derive(Default)]
+pub struct Snap;
+
+impl Snap {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[async_trait]
+impl Backend for Snap {
+    fn name(&self) -> Cow<'static, str> {
+        "snap".into()
+    }
+
+    async fn get_updates_list(&self) -> Result<Vec<String>> {
+        let output = Command::new("snap")
+            .env("LC_ALL", "C")
+            .args(["refresh", "--list"])
+            .output()
+            .await
+            .error("Failed to run snap refresh --list")?;
+        if !output.status.success() {
+            return Err(Error::new(format!(
+                "snap refresh --list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let updates = String::from_utf8(output.stdout).error("snap produced non-UTF8 output")?;
+
+        // If all snaps are up to date, nothing is printed to stdout. Otherwise the first line is
+        // a table header.
+        let updates = updates
+            .lines()
+            .skip(1)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect();
+
+        Ok(updates)
+    }
+}
@@ -0,0 +1,47 @@
+use tokio::process::Command;
+
+use super::*;
+
+#[derive(Default)]
+pub struct Xbps;
+
+impl Xbps {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[async_trait]
+impl Backend for Xbps {
+    fn name(&self) -> Cow<'static, str> {
+        "xbps".into()
+    }
+
+    async fn get_updates_list(&self) -> Result<Vec<String>> {
+        // `--memory-sync` fetches the repository index into memory instead of the system's
+        // database, so no root privileges are required
+        let output = Command::new("xbps-install")
+            .env("LC_ALL", "C")
+            .args(["--memory-sync", "--update", "--dry-run"])
+            .output()
+            .await
+            .error("Failed to run xbps-install")?;
+        // Exit code 6 means that the system is up to date
+        if !output.status.success() && output.status.code() != Some(6) {
+            return Err(Error::new(format!(
+                "xbps-install failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let updates = String::from_utf8(output.stdout).error("xbps produced non-UTF8 output")?;
+
+        // Each line is `<pkgver> <action> <arch> <repository> ...`
+        let updates = updates
+            .lines()
+            .filter(|line| line.split_whitespace().nth(1) == Some("update"))
+            .map(|line| line.to_string())
+            .collect();
+
+        Ok(updates)
+    }
+}
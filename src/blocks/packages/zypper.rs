@@ -0,0 +1,91 @@
+use tokio::process::Command;
+
+use super::*;
+
+pub struct Zypper {
+    patches: bool,
+}
+
+impl Zypper {
+    pub fn new(patches: bool) -> Self {
+        Self { patches }
+    }
+}
+
+#[async_trait]
+impl Backend for Zypper {
+    fn name(&self) -> Cow<'static, str> {
+        "zypper".into()
+    }
+
+    async fn get_updates_list(&self) -> Result<Vec<String>> {
+        list_updates("list-updates").await
+    }
+
+    async fn get_extra_values(&self, _updates: &[String]) -> Result<Vec<ExtraValue>> {
+        if !self.patches || list_updates("list-patches").await?.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![ExtraValue {
+            name: "zypper_patches".into(),
+            value: Value::flag(),
+            pending: true,
+        }])
+    }
+}
+
+async fn list_updates(command: &str) -> Result<Vec<String>> {
+    let stdout = Command::new("zypper")
+        .env("LC_ALL", "C")
+        .args(["--non-interactive", "--xmlout", command])
+        .output()
+        .await
+        .or_error(|| format!("Failed to run zypper {command}"))?
+        .stdout;
+    let output = String::from_utf8(stdout).error("zypper produced non-UTF8 output")?;
+    Ok(parse_updates(&output))
+}
+
+/// Extracts `<name> <edition>` of each `<update>` element of zypper's XML output
+fn parse_updates(xml: &str) -> Vec<String> {
+    let name_regex = regex!(r#"\sname="([^"]*)""#);
+    let edition_regex = regex!(r#"\sedition="([^"]*)""#);
+    xml.split("<update ")
+        .skip(1)
+        .filter_map(|update| {
+            let attrs = update.split('>').next()?;
+            let name = &name_regex.captures(attrs)?[1];
+            Some(match edition_regex.captures(attrs) {
+                Some(edition) => format!("{name} {}", &edition[1]),
+                None => name.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_updates() {
+        let xml = r#"<?xml version='1.0'?>
+<stream>
+<update-status version="0.6">
+<update-list>
+<update kind="package" name="curl" edition="8.9.1-1.1" arch="x86_64" edition-old="8.9.0-1.1">
+<summary>A Tool for Transferring Data from URLs</summary>
+<source url="http://download.opensuse.org/tumbleweed/repo/oss" alias="repo-oss"/>
+</update>
+<update kind="patch" name="openSUSE-2026-1234" edition="1" arch="noarch" status="needed" category="security" severity="important" pkgmanager="false" restart="false" interactive="false">
+</update>
+</update-list>
+</update-status>
+</stream>"#;
+        assert_eq!(
+            parse_updates(xml),
+            vec!["curl 8.9.1-1.1", "openSUSE-2026-1234 1"]
+        );
+        assert!(parse_updates("<stream></stream>").is_empty());
+    }
+}
@@ -188,7 +188,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         .error("invalid critical updates regex")?;
 
     let pacman_backend = Pacman::new().await?;
-    let aur_backend = Aur::new(config.aur_command.clone().unwrap_or_default());
+    let aur_backend = Aur::new(config.aur_command.clone(), Vec::new());
 
     loop {
         let (mut values, warning, critical, total) = match &watched {
@@ -0,0 +1,248 @@
+//! Battery levels of wireless peripherals
+//!
+//! This block shows the battery levels of wireless peripherals such as mice, keyboards and
+//! gamepads, as reported by [UPower](https://upower.freedesktop.org/). Only one device is shown at
+//! a time, by default the one with the lowest battery level; the mouse wheel switches between
+//! them. The state of the block is based on the lowest battery level of all devices, so it warns
+//! even if another device is shown. The block is hidden if no matching device is found.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $percentage "`
+//! `device_types` | The types of devices to show. See below for the available types. | `["mouse", "keyboard", "gaming_input"]`
+//! `warning` | Battery level, in percent, below which the state is set to warning | `20`
+//! `critical` | Battery level, in percent, below which the state is set to critical | `10`
+//! `interval` | Update interval in seconds | `60`
+//!
+//! Available device types: `mouse`, `keyboard`, `gaming_input`, `pen`, `touchpad`, `tablet`,
+//! `phone`, `headset`, `headphones`, `speakers`, `remote_control` and `wearable`.
+//!
+//! Placeholder  | Value                                         | Type   | Unit
+//! -------------|-----------------------------------------------|--------|-----
+//! `icon`       | An icon based on the type of the device       | Icon   | -
+//! `name`       | The model name of the device                  | Text   | -
+//! `percentage` | The battery level of the device               | Number | %
+//! `count`      | The number of devices found                   | Number | -
+//! `low`        | The number of devices below `warning`         | Number | -
+//!
+//! Action | Description              | Default button
+//! -------|--------------------------|---------------
+//! `next` | Show the next device     | Wheel Up
+//! `prev` | Show the previous device | Wheel Down
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "peripherals"
+//! format = " $icon $name.str(max_w:12) $percentage "
+//! device_types = ["mouse", "keyboard", "headset"]
+//! warning = 30
+//! ```
+//!
+//! # Icons Used
+//! - `mouse`
+//! - `keyboard`
+//! - `joystick`
+//! - `headphones`
+//! - `bat`
+
+use tokio::try_join;
+
+use super::prelude::*;
+use super::upower::{DeviceProxy, UPowerProxy};
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(vec![DeviceType::Mouse, DeviceType::Keyboard, DeviceType::GamingInput])]
+    pub device_types: Vec<DeviceType>,
+    #[default(20.0)]
+    pub warning: f64,
+    #[default(10.0)]
+    pub critical: f64,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+/// <https://upower.freedesktop.org/docs/Device.html#Device:Type>
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceType {
+    Phone,
+    Mouse,
+    Keyboard,
+    Tablet,
+    GamingInput,
+    Pen,
+    Touchpad,
+    Headset,
+    Speakers,
+    Headphones,
+    RemoteControl,
+    Wearable,
+}
+
+impl DeviceType {
+    fn from_upower(device_type: u32) -> Option<Self> {
+        Some(match device_type {
+            5 => Self::Mouse,
+            6 => Self::Keyboard,
+            8 => Self::Phone,
+            10 => Self::Tablet,
+            12 => Self::GamingInput,
+            13 => Self::Pen,
+            14 => Self::Touchpad,
+            17 => Self::Headset,
+            18 => Self::Speakers,
+            19 => Self::Headphones,
+            22 => Self::RemoteControl,
+            26 => Self::Wearable,
+            _ => return None,
+        })
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Mouse | Self::Touchpad => "mouse",
+            Self::Keyboard => "keyboard",
+            Self::GamingInput => "joystick",
+            Self::Headset | Self::Headphones => "headphones",
+            _ => "bat",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Peripheral {
+    device_type: DeviceType,
+    name: String,
+    percentage: f64,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::WheelUp, None, "next"),
+        (MouseButton::WheelDown, None, "prev"),
+    ])?;
+
+    let format = config.format.with_default(" $icon $percentage ")?;
+    let mut timer = config.interval.timer();
+
+    let dbus_conn = new_system_dbus_connection().await?;
+    let upower_proxy = UPowerProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create UPowerProxy")?;
+    let (mut device_added, mut device_removed) = try_join! {
+        upower_proxy.receive_device_added(),
+        upower_proxy.receive_device_removed()
+    }
+    .error("Failed to create signal stream")?;
+
+    let mut index = 0;
+
+    loop {
+        let mut peripherals = get_peripherals(&dbus_conn, &upower_proxy, config).await?;
+        peripherals.sort_by(|a, b| a.percentage.total_cmp(&b.percentage));
+
+        loop {
+            let Some(lowest) = peripherals.first() else {
+                api.hide()?;
+                break;
+            };
+            let shown = &peripherals[index % peripherals.len()];
+
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = if lowest.percentage < config.critical {
+                State::Critical
+            } else if lowest.percentage < config.warning {
+                State::Warning
+            } else {
+                State::Idle
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon(shown.device_type.icon()),
+                "name" => Value::text(shown.name.clone()),
+                "percentage" => Value::percents(shown.percentage),
+                "count" => Value::number(peripherals.len()),
+                "low" => Value::number(
+                    peripherals
+                        .iter()
+                        .filter(|p| p.percentage < config.warning)
+                        .count()
+                ),
+            });
+            api.set_widget(widget)?;
+
+            let len = peripherals.len();
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                _ = device_added.next() => break,
+                _ = device_removed.next() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "next" => index = (index + 1) % len,
+                    "prev" => index = (index + len - 1) % len,
+                    _ => (),
+                }
+            }
+        }
+
+        if peripherals.is_empty() {
+            select! {
+                _ = timer.tick() => (),
+                _ = api.wait_for_update_request() => (),
+                _ = device_added.next() => (),
+                _ = device_removed.next() => (),
+            }
+        }
+    }
+}
+
+async fn get_peripherals(
+    dbus_conn: &zbus::Connection,
+    upower_proxy: &UPowerProxy<'_>,
+    config: &Config,
+) -> Result<Vec<Peripheral>> {
+    let mut peripherals = Vec::new();
+    for path in upower_proxy
+        .enumerate_devices()
+        .await
+        .error("Failed to retrieve UPower devices")?
+    {
+        let proxy = DeviceProxy::builder(dbus_conn)
+            .path(path)
+            .unwrap()
+            .build()
+            .await
+            .error("Failed to create DeviceProxy")?;
+
+        let Ok((device_type, is_present, percentage, model)) = try_join!(
+            proxy.type_(),
+            proxy.is_present(),
+            proxy.percentage(),
+            proxy.model(),
+        ) else {
+            // The device may have been removed in the meantime
+            continue;
+        };
+
+        let Some(device_type) = DeviceType::from_upower(device_type) else {
+            continue;
+        };
+        if !is_present || !config.device_types.contains(&device_type) {
+            continue;
+        }
+
+        peripherals.push(Peripheral {
+            device_type,
+            name: model,
+            percentage,
+        });
+    }
+    Ok(peripherals)
+}
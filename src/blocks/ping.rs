@@ -0,0 +1,268 @@
+//! Latency and packet loss
+//!
+//! This block periodically pings one or more hosts and shows the average round trip time and the
+//! packet loss, as a lightweight indicator of whether the internet connection is fine.
+//!
+//! With `method = "icmp"`, the `ping` command is used, since sending ICMP packets requires
+//! privileges. With `method = "tcp"`, the block measures how long it takes to open a TCP
+//! connection to each host instead, which also works on networks that drop ICMP packets.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `hosts` | The hosts to ping. With `method = "tcp"`, a port can be appended, e.g. `"example.com:80"` or `"[2606:4700::1111]:80"`. | `["1.1.1.1"]`
+//! `method` | `"icmp"` or `"tcp"` | `"icmp"`
+//! `port` | The default port for `method = "tcp"` | `443`
+//! `count` | The number of pings sent to each host per update | `3`
+//! `timeout` | How long to wait for a reply, in seconds. Rounded up to whole seconds for `method = "icmp"`. | `2`
+//! `latency_warning` | Average latency, in milliseconds, above which the state is set to warning | `100`
+//! `latency_critical` | Average latency, in milliseconds, above which the state is set to critical | `300`
+//! `loss_warning` | Packet loss, in percent, above which the state is set to warning | `0`
+//! `loss_critical` | Packet loss, in percent, above which the state is set to critical | `50`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" ^icon_ping {$latency\|down}{ $loss\|} \"</code>
+//! `interval` | Update interval in seconds | `30`
+//!
+//! Placeholder | Value                                                        | Type   | Unit
+//! ------------|--------------------------------------------------------------|--------|--------
+//! `latency`   | The average round trip time of all replies (absent if none)  | Number | Seconds
+//! `loss`      | The share of pings without a reply (absent if zero)          | Number | %
+//! `worst`     | The host with the highest packet loss or latency             | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "ping"
+//! hosts = ["1.1.1.1", "9.9.9.9"]
+//! format = " ^icon_ping {$latency.eng(w:3,u:s,p:m)|down}{ $loss|} "
+//! latency_warning = 60
+//! ```
+//!
+//! # Icons Used
+//! - `ping`
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Instant;
+
+use tokio::net::TcpStream;
+use tokio::process::Command;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default(vec!["1.1.1.1".into()])]
+    pub hosts: Vec<String>,
+    pub method: Method,
+    #[default(443)]
+    pub port: u16,
+    #[default(3)]
+    pub count: u32,
+    #[default(2.into())]
+    pub timeout: Seconds<false>,
+    #[default(100.0)]
+    pub latency_warning: f64,
+    #[default(300.0)]
+    pub latency_critical: f64,
+    #[default(0.0)]
+    pub loss_warning: f64,
+    #[default(50.0)]
+    pub loss_critical: f64,
+    pub format: FormatConfig,
+    #[default(30.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    #[default]
+    Icmp,
+    Tcp,
+}
+
+#[derive(Debug, PartialEq)]
+struct PingResult {
+    sent: u32,
+    /// Round trip times of the replies, in milliseconds
+    replies: Vec<f64>,
+}
+
+impl PingResult {
+    fn loss(&self) -> f64 {
+        if self.sent == 0 {
+            return 100.0;
+        }
+        100.0 * (1.0 - self.replies.len() as f64 / self.sent as f64)
+    }
+
+    fn average(&self) -> Option<f64> {
+        (!self.replies.is_empty())
+            .then(|| self.replies.iter().sum::<f64>() / self.replies.len() as f64)
+    }
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" ^icon_ping {$latency|down}{ $loss|} ")?;
+    let mut timer = config.interval.timer();
+
+    if config.hosts.is_empty() {
+        return Err(Error::new("`hosts` is empty"));
+    }
+
+    loop {
+        let results: Vec<PingResult> =
+            futures::future::join_all(config.hosts.iter().map(|host| ping(config, host)))
+                .await
+                .into_iter()
+                .collect::<Result<_>>()?;
+
+        let total = PingResult {
+            sent: results.iter().map(|r| r.sent).sum(),
+            replies: results.iter().flat_map(|r| r.replies.clone()).collect(),
+        };
+        let latency = total.average();
+        let loss = total.loss();
+        let worst = results
+            .iter()
+            .zip(&config.hosts)
+            .max_by(|(a, _), (b, _)| {
+                a.loss().total_cmp(&b.loss()).then(
+                    a.average()
+                        .unwrap_or(0.0)
+                        .total_cmp(&b.average().unwrap_or(0.0)),
+                )
+            })
+            .map(|(_, host)| host.clone());
+
+        let latency_ms = latency.unwrap_or(f64::INFINITY);
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if loss > config.loss_critical || latency_ms > config.latency_critical {
+            State::Critical
+        } else if loss > config.loss_warning || latency_ms > config.latency_warning {
+            State::Warning
+        } else {
+            State::Idle
+        };
+        widget.set_values(map! {
+            [if let Some(l) = latency] "latency" => Value::seconds(l * 1e-3),
+            [if loss > 0.0] "loss" => Value::percents(loss),
+            [if let Some(w) = worst] "worst" => Value::text(w),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn ping(config: &Config, host: &str) -> Result<PingResult> {
+    match config.method {
+        Method::Icmp => {
+            let output = Command::new("ping")
+                .args(["-n", "-c", &config.count.to_string()])
+                // `-W` only takes whole seconds
+                .args(["-W", &config.timeout.0.as_secs_f64().ceil().to_string()])
+                .arg(host)
+                .env("LC_ALL", "C")
+                .output()
+                .await
+                .error("Failed to run ping")?;
+            // ping exits with 1 if there were no replies, which is not an error for this block
+            Ok(
+                parse_ping_output(&String::from_utf8_lossy(&output.stdout)).unwrap_or(PingResult {
+                    sent: config.count,
+                    replies: Vec::new(),
+                }),
+            )
+        }
+        Method::Tcp => {
+            let address = tcp_address(host, config.port);
+            let mut replies = Vec::new();
+            for _ in 0..config.count {
+                let start = Instant::now();
+                if let Ok(Ok(_)) =
+                    tokio::time::timeout(config.timeout.0, TcpStream::connect(&address)).await
+                {
+                    replies.push(start.elapsed().as_secs_f64() * 1e3);
+                }
+            }
+            Ok(PingResult {
+                sent: config.count,
+                replies,
+            })
+        }
+    }
+}
+
+/// Appends `default_port` to `host` unless it already has a port. IPv6 addresses need to be
+/// enclosed in brackets if a port is given.
+fn tcp_address(host: &str, default_port: u16) -> String {
+    if host.parse::<SocketAddr>().is_ok() {
+        return host.to_owned();
+    }
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return SocketAddr::new(ip, default_port).to_string();
+    }
+    match host.rsplit_once(':') {
+        Some((name, port)) if !name.contains(':') && port.parse::<u16>().is_ok() => host.to_owned(),
+        _ => format!("{host}:{default_port}"),
+    }
+}
+
+/// Parses the output of `ping -n`, where each reply is printed as
+/// `64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms`
+fn parse_ping_output(output: &str) -> Option<PingResult> {
+    let sent = regex!(r"(\d+) packets transmitted")
+        .captures(output)?
+        .get(1)?
+        .as_str()
+        .parse()
+        .ok()?;
+    let replies = regex!(r"time=([\d.]+) ms")
+        .captures_iter(output)
+        .filter_map(|c| c[1].parse().ok())
+        .collect();
+    Some(PingResult { sent, replies })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ping_output() {
+        let output = "\
+PING 1.1.1.1 (1.1.1.1) 56(84) bytes of data.
+64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.0 ms
+64 bytes from 1.1.1.1: icmp_seq=3 ttl=57 time=14.0 ms
+
+--- 1.1.1.1 ping statistics ---
+3 packets transmitted, 2 received, 33.3333% packet loss, time 2003ms
+rtt min/avg/max/mdev = 12.000/13.000/14.000/1.000 ms
+";
+        let result = parse_ping_output(output).unwrap();
+        assert_eq!(result.sent, 3);
+        assert_eq!(result.replies, [12.0, 14.0]);
+        assert_eq!(result.average(), Some(13.0));
+    }
+
+    #[test]
+    fn test_tcp_address() {
+        assert_eq!(tcp_address("1.1.1.1", 443), "1.1.1.1:443");
+        assert_eq!(tcp_address("1.1.1.1:80", 443), "1.1.1.1:80");
+        assert_eq!(tcp_address("example.com", 443), "example.com:443");
+        assert_eq!(tcp_address("example.com:80", 443), "example.com:80");
+        assert_eq!(tcp_address("2606:4700::1111", 443), "[2606:4700::1111]:443");
+        assert_eq!(
+            tcp_address("[2606:4700::1111]:80", 443),
+            "[2606:4700::1111]:80"
+        );
+    }
+}
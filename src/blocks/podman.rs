@@ -0,0 +1,63 @@
+//! Local podman containers status
+//!
+//! This block uses the Docker-compatible API of the podman service, so it provides the same
+//! information as the [`docker`](super::docker) block. The API socket must be enabled, e.g. using
+//! `systemctl --user enable --now podman.socket` for rootless containers or
+//! `systemctl enable --now podman.socket` (with `socket_path = "/run/podman/podman.sock"`) for
+//! rootful ones.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `interval` | Update interval, in seconds. | `5`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $running.eng(w:1) "`
+//! `socket_path` | The path to the podman socket. Supports path expansions e.g. `~` and environment variables. | `"$XDG_RUNTIME_DIR/podman/podman.sock"`
+//!
+//! Key       | Value                                              | Type   | Unit
+//! ----------|----------------------------------------------------|--------|-----
+//! `icon`    | A static icon                                      | Icon   | -
+//! `total`   | Total containers on the host                       | Number | -
+//! `running` | Containers running on the host                     | Number | -
+//! `stopped` | Containers stopped on the host                     | Number | -
+//! `paused`  | Containers paused on the host                      | Number | -
+//! `images`  | Total images on the host                           | Number | -
+//! `cpu`     | Total CPU usage of the running containers          | Number | %
+//! `memory`  | Total memory usage of the running containers       | Number | Bytes
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "podman"
+//! format = " $icon $running/$total "
+//! [[block.click]]
+//! button = "left"
+//! cmd = "alacritty -e podman-tui"
+//! ```
+//!
+//! # Icons Used
+//!
+//! - `docker`
+
+use super::docker;
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default(5.into())]
+    pub interval: Seconds,
+    pub format: FormatConfig,
+    #[default("$XDG_RUNTIME_DIR/podman/podman.sock".into())]
+    pub socket_path: ShellString,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let config = docker::Config {
+        interval: config.interval,
+        format: config.format.clone(),
+        socket_path: config.socket_path.clone(),
+    };
+    docker::run(&config, api).await
+}
@@ -10,6 +10,14 @@
 //! 5) If you have fewer than four checkmarks, take a short break (3–5 minutes) and then return to step 2.
 //! 6) After four pomodoros, take a longer break (15–30 minutes), reset your checkmark count to zero, then go to step 1.
 //!
+//! # Usage
+//!
+//! Left click the block to set the lengths of the tasks and breaks and the number of pomodoros,
+//! using the mouse wheel to change the values and left click to confirm each one. During a task or
+//! a break, right click pauses or resumes the timer, and middle click stops the pomodoro.
+//!
+//! The block is in the info state while working, in the good state during breaks and in the
+//! warning state while paused.
 //!
 //! # Configuration
 //!
@@ -19,6 +27,9 @@
 //! `message` | Message when timer expires | `"Pomodoro over! Take a break!"`
 //! `break_message` | Message when break is over | `"Break over! Time to work!"`
 //! `notify_cmd` | A shell command to run as a notifier. `{msg}` will be substituted with either `message` or `break_message`. | `None`
+//! `work_length` | Default length of a task, in minutes | `25`
+//! `break_length` | Default length of a break, in minutes | `5`
+//! `pomodoros` | Default number of pomodoros | `4`
 //! `blocking_cmd` | Is `notify_cmd` blocking? If it is, then pomodoro block will wait until the command finishes before proceeding. Otherwise, you will have to click on the block in order to proceed. | `false`
 //!
 //! Placeholder | Value                               | Type
@@ -73,6 +84,12 @@ pub struct Config {
     pub break_message: String,
     pub notify_cmd: Option<String>,
     pub blocking_cmd: bool,
+    #[default(25)]
+    pub work_length: u64,
+    #[default(5)]
+    pub break_length: u64,
+    #[default(4)]
+    pub pomodoros: u64,
 }
 
 struct Block<'a> {
@@ -100,9 +117,15 @@ impl Block<'_> {
     }
 
     async fn read_params(&mut self) -> Result<(Duration, Duration, u64)> {
-        let task_len = self.read_u64(25, "Task length:").await?;
-        let break_len = self.read_u64(5, "Break length:").await?;
-        let pomodoros = self.read_u64(4, "Pomodoros:").await?;
+        let task_len = self
+            .read_u64(self.block_config.work_length, "Task length:")
+            .await?;
+        let break_len = self
+            .read_u64(self.block_config.break_length, "Break length:")
+            .await?;
+        let pomodoros = self
+            .read_u64(self.block_config.pomodoros, "Pomodoros:")
+            .await?;
         Ok((
             Duration::from_secs(task_len * 60),
             Duration::from_secs(break_len * 60),
@@ -123,6 +146,45 @@ impl Block<'_> {
         Ok(number)
     }
 
+    /// Counts down `len`, returns `false` if the pomodoro was stopped
+    async fn countdown(&mut self, len: Duration, prefix: &str, state: State) -> Result<bool> {
+        let mut left = len;
+        // `None` while paused
+        let mut started = Some(Instant::now());
+        loop {
+            let remaining = match started {
+                Some(started) => left.saturating_sub(started.elapsed()),
+                None => left,
+            };
+            if remaining.is_zero() {
+                return Ok(true);
+            }
+            self.widget.state = if started.is_some() {
+                state
+            } else {
+                State::Warning
+            };
+            let paused = if started.is_some() { "" } else { " (paused)" };
+            self.set_text(format!(
+                "{prefix}{} min{paused}",
+                remaining.as_secs().div_ceil(60)
+            ))
+            .await?;
+            select! {
+                _ = sleep(remaining.min(Duration::from_secs(10))) => (),
+                action = self.actions.recv() => match action.as_deref() {
+                    Some("_middle") => return Ok(false),
+                    Some("_right") => match started.take() {
+                        Some(started) => left = left.saturating_sub(started.elapsed()),
+                        None => started = Some(Instant::now()),
+                    },
+                    Some(_) => (),
+                    None => return Err(Error::new("channel closed")),
+                }
+            }
+        }
+    }
+
     async fn run_pomodoro(
         &mut self,
         task_len: Duration,
@@ -131,28 +193,13 @@ impl Block<'_> {
     ) -> Result<()> {
         for pomodoro in 0..pomodoros {
             // Task timer
-            self.widget.state = State::Idle;
-            let timer = Instant::now();
-            loop {
-                let elapsed = timer.elapsed();
-                if elapsed >= task_len {
-                    break;
-                }
-                let left = task_len - elapsed;
-                let text = if pomodoro == 0 {
-                    format!("{} min", (left.as_secs() + 59) / 60,)
-                } else {
-                    format!(
-                        "{} {} min",
-                        "|".repeat(pomodoro as usize),
-                        (left.as_secs() + 59) / 60,
-                    )
-                };
-                self.set_text(text).await?;
-                select! {
-                    _ = sleep(Duration::from_secs(10)) => (),
-                    _ = self.wait_for_click("_middle") => return Ok(()),
-                }
+            let prefix = if pomodoro == 0 {
+                String::new()
+            } else {
+                format!("{} ", "|".repeat(pomodoro as usize))
+            };
+            if !self.countdown(task_len, &prefix, State::Info).await? {
+                return Ok(());
             }
 
             // Show break message
@@ -178,19 +225,8 @@ impl Block<'_> {
             }
 
             // Break timer
-            let timer = Instant::now();
-            loop {
-                let elapsed = timer.elapsed();
-                if elapsed >= break_len {
-                    break;
-                }
-                let left = break_len - elapsed;
-                self.set_text(format!("Break: {} min", (left.as_secs() + 59) / 60,))
-                    .await?;
-                select! {
-                    _ = sleep(Duration::from_secs(10)) => (),
-                    _ = self.wait_for_click("_middle") => return Ok(()),
-                }
+            if !self.countdown(break_len, "Break: ", State::Good).await? {
+                return Ok(());
             }
 
             // Show task message
@@ -0,0 +1,145 @@
+//! Active power profile
+//!
+//! This block shows the power profile set with
+//! [power-profiles-daemon](https://gitlab.freedesktop.org/upower/power-profiles-daemon), such as
+//! `power-saver`, `balanced` or `performance`, and allows switching between the profiles available
+//! on the system. Changes made by other programs are shown right away.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $profile "`
+//!
+//! Placeholder | Value                                                                             | Type | Unit
+//! ------------|-----------------------------------------------------------------------------------|------|-----
+//! `icon`      | An icon depending on the profile                                                  | Icon | -
+//! `profile`   | The name of the active profile                                                    | Text | -
+//! `degraded`  | Why the performance profile is degraded, e.g. `lap-detected` (absent if it isn't) | Text | -
+//!
+//! The block is set to the warning state while the performance profile is degraded.
+//!
+//! Action         | Description                    | Default button
+//! ---------------|--------------------------------|---------------
+//! `next_profile` | Switch to the next profile     | Left, Wheel Up
+//! `prev_profile` | Switch to the previous profile | Wheel Down
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "power_profile"
+//! format = " $icon{ ($degraded)|} "
+//! ```
+//!
+//! # Icons Used
+//! - `profile_power_saver`
+//! - `profile_balanced`
+//! - `profile_performance`
+
+use zbus::zvariant::OwnedValue;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "next_profile"),
+        (MouseButton::WheelUp, None, "next_profile"),
+        (MouseButton::WheelDown, None, "prev_profile"),
+    ])?;
+
+    let format = config.format.with_default(" $icon $profile ")?;
+
+    let dbus_conn = new_system_dbus_connection().await?;
+    let proxy = PowerProfilesProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create PowerProfilesProxy")?;
+    let mut profile_changes = proxy.receive_active_profile_changed().await;
+    let mut degraded_changes = proxy.receive_performance_degraded_changed().await;
+
+    loop {
+        let profile = proxy
+            .active_profile()
+            .await
+            .error("Failed to get ActiveProfile")?;
+        let degraded = proxy
+            .performance_degraded()
+            .await
+            .error("Failed to get PerformanceDegraded")?;
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if !degraded.is_empty() {
+            widget.state = State::Warning;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon(match profile.as_str() {
+                "power-saver" => "profile_power_saver",
+                "performance" => "profile_performance",
+                _ => "profile_balanced",
+            }),
+            "profile" => Value::text(profile.clone()),
+            [if !degraded.is_empty()] "degraded" => Value::text(degraded),
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = profile_changes.next() => break,
+                _ = degraded_changes.next() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => {
+                    let step = match action.as_ref() {
+                        "next_profile" => 1,
+                        "prev_profile" => -1,
+                        _ => continue,
+                    };
+                    let profiles = profile_names(
+                        &proxy.profiles().await.error("Failed to get Profiles")?,
+                    );
+                    let Some(current) = profiles.iter().position(|p| *p == profile) else {
+                        continue;
+                    };
+                    let next = (current as isize + step).rem_euclid(profiles.len() as isize);
+                    proxy
+                        .set_active_profile(&profiles[next as usize])
+                        .await
+                        .error("Failed to set ActiveProfile")?;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the names of the profiles, ordered from `power-saver` to `performance`
+fn profile_names(profiles: &[HashMap<String, OwnedValue>]) -> Vec<String> {
+    profiles
+        .iter()
+        .filter_map(|p| {
+            let name: &str = p.get("Profile")?.downcast_ref().ok()?;
+            Some(name.to_owned())
+        })
+        .collect()
+}
+
+#[zbus::proxy(
+    interface = "net.hadess.PowerProfiles",
+    default_service = "net.hadess.PowerProfiles",
+    default_path = "/net/hadess/PowerProfiles"
+)]
+trait PowerProfiles {
+    #[zbus(property)]
+    fn active_profile(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn set_active_profile(&self, profile: &str) -> zbus::Result<()>;
+    #[zbus(property)]
+    fn performance_degraded(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn profiles(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+}
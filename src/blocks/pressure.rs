@@ -0,0 +1,148 @@
+//! Pressure stall information
+//!
+//! This block shows the [pressure stall information](https://docs.kernel.org/accounting/psi.html)
+//! (PSI) of a resource, i.e. the share of time in which tasks were stalled waiting for it. Rising
+//! memory pressure is a good indicator of thrashing, usually long before the system becomes
+//! unresponsive. PSI requires Linux 4.20 or later, built with `CONFIG_PSI`.
+//!
+//! The block's state is determined by the `some` pressure over the last 10 seconds.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `resource` | One of `"cpu"`, `"memory"` or `"io"` | `"memory"`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $some_avg10.eng(w:2) "`
+//! `interval` | Update interval in seconds | `5`
+//! `info` | Minimum pressure, where state is set to info | `5.0`
+//! `warning` | Minimum pressure, where state is set to warning | `20.0`
+//! `critical` | Minimum pressure, where state is set to critical | `50.0`
+//!
+//! Placeholder   | Value                                                           | Type   | Unit
+//! --------------|-----------------------------------------------------------------|--------|-----
+//! `icon`        | An icon depending on `resource`                                 | Icon   | -
+//! `some_avg10`  | Share of time some tasks were stalled, over the last 10 seconds | Number | %
+//! `some_avg60`  | Same, over the last 60 seconds                                  | Number | %
+//! `some_avg300` | Same, over the last 300 seconds                                 | Number | %
+//! `full_avg10`  | Share of time all tasks were stalled, over the last 10 seconds  | Number | %
+//! `full_avg60`  | Same, over the last 60 seconds                                  | Number | %
+//! `full_avg300` | Same, over the last 300 seconds                                 | Number | %
+//!
+//! The `full_*` placeholders are absent for `cpu` on kernels older than 5.13.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "pressure"
+//! resource = "io"
+//! format = " $icon IO $some_avg10.eng(w:2)/$full_avg10.eng(w:2) "
+//! ```
+//!
+//! # Icons Used
+//! - `cpu` (for `cpu`)
+//! - `memory_mem` (for `memory`)
+//! - `disk_drive` (for `io`)
+
+use super::prelude::*;
+use crate::util::read_file;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub resource: Resource,
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+    #[default(5.0)]
+    pub info: f64,
+    #[default(20.0)]
+    pub warning: f64,
+    #[default(50.0)]
+    pub critical: f64,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Resource {
+    Cpu,
+    #[default]
+    Memory,
+    Io,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $some_avg10.eng(w:2) ")?;
+    let mut timer = config.interval.timer();
+
+    let (path, icon) = match config.resource {
+        Resource::Cpu => ("/proc/pressure/cpu", "cpu"),
+        Resource::Memory => ("/proc/pressure/memory", "memory_mem"),
+        Resource::Io => ("/proc/pressure/io", "disk_drive"),
+    };
+
+    loop {
+        let content = read_file(path)
+            .await
+            .or_error(|| format!("Failed to read {path}"))?;
+        let some = parse_line(&content, "some").or_error(|| format!("Failed to parse {path}"))?;
+        let full = parse_line(&content, "full");
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match some.0 {
+            x if x >= config.critical => State::Critical,
+            x if x >= config.warning => State::Warning,
+            x if x >= config.info => State::Info,
+            _ => State::Idle,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon(icon),
+            "some_avg10" => Value::percents(some.0),
+            "some_avg60" => Value::percents(some.1),
+            "some_avg300" => Value::percents(some.2),
+            [if let Some(full) = full] "full_avg10" => Value::percents(full.0),
+            [if let Some(full) = full] "full_avg60" => Value::percents(full.1),
+            [if let Some(full) = full] "full_avg300" => Value::percents(full.2),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Parses the `avg10`, `avg60` and `avg300` values of the `some` or `full` line
+fn parse_line(content: &str, kind: &str) -> Option<(f64, f64, f64)> {
+    let line = content
+        .lines()
+        .find_map(|line| line.strip_prefix(kind)?.strip_prefix(' '))?;
+    let mut avg = [None; 3];
+    for field in line.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        let i = match key {
+            "avg10" => 0,
+            "avg60" => 1,
+            "avg300" => 2,
+            _ => continue,
+        };
+        avg[i] = value.parse().ok();
+    }
+    Some((avg[0]?, avg[1]?, avg[2]?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let content = "some avg10=1.50 avg60=0.25 avg300=0.00 total=123456\n\
+                       full avg10=0.10 avg60=0.05 avg300=0.00 total=6543";
+        assert_eq!(parse_line(content, "some"), Some((1.5, 0.25, 0.0)));
+        assert_eq!(parse_line(content, "full"), Some((0.1, 0.05, 0.0)));
+        assert_eq!(parse_line("some avg10=1.50 total=1", "some"), None);
+        assert_eq!(parse_line("some avg10=1.50", "full"), None);
+    }
+}
@@ -1,5 +1,9 @@
 //! Privacy Monitor
 //!
+//! This block shows which devices are being captured, e.g. a microphone or a webcam, and by whom.
+//! The state is set to warning while anything is captured, and to critical while a webcam is in
+//! use.
+//!
 //! # Configuration
 //!
 //! Key        | Values | Default|
@@ -202,7 +206,9 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
                 }
             }
         }
-        if !info.is_empty() {
+        if info.contains_key(&Type::Webcam) {
+            widget.state = State::Critical;
+        } else if !info.is_empty() {
             widget.state = State::Warning;
         }
 
@@ -0,0 +1,260 @@
+//! Whether a process is running
+//!
+//! This block checks whether any process matches `pattern`, similar to `pgrep`. By default the
+//! pattern is matched against the process name, set `full = true` to match against the full
+//! command line instead (like `pgrep -f`).
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `pattern` | A regex to match processes against | **Required**
+//! `full` | Match `pattern` against the full command line | `false`
+//! `running_format` | A string to customise the output of this block when a process is running. See below for available placeholders. | `" $icon $name "`
+//! `not_running_format` | A string to customise the output of this block when no process is running. See below for available placeholders. | `" $icon $name "`
+//! `running_state` | A valid [`State`] | [`State::Idle`]
+//! `not_running_state` | A valid [`State`] | [`State::Critical`]
+//! `interval` | Update interval in seconds | `5`
+//!
+//! Placeholder | Value                                                         | Type   | Unit
+//! ------------|---------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                 | Icon   | -
+//! `name`      | The name of the first matching process, or `pattern` if none | Text   | -
+//! `pid`       | The PID of the first matching process                         | Number | -
+//! `count`     | The number of matching processes                              | Number | -
+//! `cpu`       | The total CPU usage of the matching processes                 | Number | %
+//! `memory`    | The total resident memory of the matching processes           | Number | Bytes
+//!
+//! Only `icon` and `name` are available in `not_running_format`.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "process"
+//! pattern = "^restic$"
+//! running_format = " backup: $cpu.eng(w:2) $memory.eng(w:3,u:B,p:Mi) "
+//! not_running_format = ""
+//! ```
+//!
+//! # Icons Used
+//! - `process`
+
+use super::prelude::*;
+use crate::util::read_file;
+use crate::wrappers::SerdeRegex;
+use std::time::Instant;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub pattern: SerdeRegex,
+    #[serde(default)]
+    pub full: bool,
+    #[serde(default)]
+    pub running_format: FormatConfig,
+    #[serde(default)]
+    pub not_running_format: FormatConfig,
+    #[serde(default)]
+    pub running_state: Option<State>,
+    #[serde(default)]
+    pub not_running_state: Option<State>,
+    #[serde(default = "default_interval")]
+    pub interval: Seconds,
+}
+
+fn default_interval() -> Seconds {
+    5.into()
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let running_format = config.running_format.with_default(" $icon $name ")?;
+    let not_running_format = config.not_running_format.with_default(" $icon $name ")?;
+    let mut timer = config.interval.timer();
+
+    let mut cpu_tracker = CpuTracker::default();
+
+    loop {
+        let processes: Vec<Process> = list_processes(config.full)
+            .await?
+            .into_iter()
+            .filter(|p| {
+                config
+                    .pattern
+                    .0
+                    .is_match(p.cmdline.as_deref().unwrap_or(&p.name))
+            })
+            .collect();
+        let cpu: f64 = cpu_tracker.update(&processes).iter().sum();
+
+        let mut widget = Widget::new();
+        match processes.first() {
+            Some(first) => {
+                widget.set_format(running_format.clone());
+                widget.state = config.running_state.unwrap_or(State::Idle);
+                widget.set_values(map! {
+                    "icon" => Value::icon("process"),
+                    "name" => Value::text(first.name.clone()),
+                    "pid" => Value::number(first.pid),
+                    "count" => Value::number(processes.len()),
+                    "cpu" => Value::percents(cpu),
+                    "memory" => Value::bytes(processes.iter().map(|p| p.rss).sum::<u64>() as f64),
+                });
+            }
+            None => {
+                widget.set_format(not_running_format.clone());
+                widget.state = config.not_running_state.unwrap_or(State::Critical);
+                widget.set_values(map! {
+                    "icon" => Value::icon("process"),
+                    "name" => Value::text(config.pattern.0.as_str().into()),
+                });
+            }
+        }
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// A snapshot of a process' state
+pub(super) struct Process {
+    pub pid: u32,
+    pub name: String,
+    /// The command line with arguments separated by spaces, if requested
+    pub cmdline: Option<String>,
+    /// Time spent on the CPU, in clock ticks
+    pub cpu_time: u64,
+    /// Resident memory, in bytes
+    pub rss: u64,
+    /// When the process was started, in clock ticks since boot
+    pub start_time: u64,
+}
+
+/// Reads all running processes from `/proc`
+pub(super) async fn list_processes(with_cmdline: bool) -> Result<Vec<Process>> {
+    // Safety: sysconf is always safe to call
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+
+    let mut processes = Vec::new();
+    let mut dir = tokio::fs::read_dir("/proc")
+        .await
+        .error("Failed to read /proc")?;
+    while let Some(entry) = dir.next_entry().await.error("Failed to read /proc")? {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        // Processes may exit at any time, so read errors are ignored
+        let Ok(stat) = read_file(format!("/proc/{pid}/stat")).await else {
+            continue;
+        };
+        let Some(Stat {
+            name,
+            cpu_time,
+            rss_pages,
+            start_time,
+        }) = parse_stat(&stat)
+        else {
+            continue;
+        };
+        let cmdline = if with_cmdline {
+            match read_file(format!("/proc/{pid}/cmdline")).await {
+                Ok(cmdline) => Some(cmdline.replace('\0', " ").trim_end().to_string()),
+                Err(_) => continue,
+            }
+        } else {
+            None
+        };
+        processes.push(Process {
+            pid,
+            name: name.to_string(),
+            cmdline,
+            cpu_time,
+            rss: rss_pages * page_size,
+            start_time,
+        });
+    }
+    Ok(processes)
+}
+
+#[derive(Debug, PartialEq)]
+struct Stat<'a> {
+    name: &'a str,
+    cpu_time: u64,
+    rss_pages: u64,
+    start_time: u64,
+}
+
+/// Parses the fields of `/proc/<pid>/stat` used by this module
+fn parse_stat(stat: &str) -> Option<Stat<'_>> {
+    // The name is enclosed in parentheses and may contain spaces and parentheses itself
+    let (name, rest) = stat.split_once(" (")?.1.rsplit_once(") ")?;
+    // Fields are counted from `state`, which is the third field in proc(5)
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    let start_time: u64 = fields.get(19)?.parse().ok()?;
+    let rss: i64 = fields.get(21)?.parse().ok()?;
+    Some(Stat {
+        name,
+        cpu_time: utime + stime,
+        rss_pages: rss.max(0) as u64,
+        start_time,
+    })
+}
+
+/// Computes per-process CPU usage between consecutive snapshots
+#[derive(Default)]
+pub(super) struct CpuTracker {
+    prev: HashMap<u32, u64>,
+    last_update: Option<Instant>,
+}
+
+impl CpuTracker {
+    /// Returns the CPU usage (in percents of one core) of each process since the previous call.
+    /// Processes which were not seen before are reported as idle.
+    pub fn update(&mut self, processes: &[Process]) -> Vec<f64> {
+        // Safety: sysconf is always safe to call
+        let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as f64;
+        let now = Instant::now();
+        let elapsed = self
+            .last_update
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .unwrap_or_default();
+        self.last_update = Some(now);
+
+        let usage = processes
+            .iter()
+            .map(|p| match self.prev.get(&p.pid) {
+                Some(&prev) if elapsed > 0.0 => {
+                    p.cpu_time.saturating_sub(prev) as f64 / ticks_per_sec / elapsed * 100.0
+                }
+                _ => 0.0,
+            })
+            .collect();
+        self.prev = processes.iter().map(|p| (p.pid, p.cpu_time)).collect();
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "1234 (Web Content (x)) S 1 1234 1234 0 -1 4194560 1000 0 0 0 150 50 0 0 20 0 30 0 12345 1000000 2560 18446744073709551615";
+        assert_eq!(
+            parse_stat(stat),
+            Some(Stat {
+                name: "Web Content (x)",
+                cpu_time: 200,
+                rss_pages: 2560,
+                start_time: 12345,
+            })
+        );
+        assert_eq!(parse_stat("1234 (broken"), None);
+    }
+}
@@ -0,0 +1,172 @@
+//! The result of a Prometheus query
+//!
+//! This block evaluates a PromQL instant query against a Prometheus server and displays the
+//! result. If the query returns more than one time series, the first one is used.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The base URL of the Prometheus server, e.g. `"http://localhost:9090"` | **Required**
+//! `query` | The PromQL query to evaluate | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $value.eng(w:3) "`
+//! `interval` | Update interval in seconds | `30`
+//! `username` | Username for HTTP basic authentication | `None`
+//! `password` | Password for HTTP basic authentication | `None`
+//! `bearer_token` | Token for bearer authentication | `None`
+//! `hide_if_empty` | Hide the block if the query returns no data | `false`
+//! `info` | Minimum value, where state is set to info | `None`
+//! `good` | Minimum value, where state is set to good | `None`
+//! `warning` | Minimum value, where state is set to warning | `None`
+//! `critical` | Minimum value, where state is set to critical | `None`
+//!
+//! Placeholder | Value                                  | Type   | Unit
+//! ------------|----------------------------------------|--------|-----
+//! `value`     | The value of the (first) result        | Number | -
+//! `series`    | The number of time series returned     | Number | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "prometheus"
+//! url = "http://prometheus.lan:9090"
+//! query = 'sum(rate(http_requests_total{code=~"5.."}[5m]))'
+//! format = " 5xx: $value.eng(w:3)/s "
+//! warning = 1
+//! critical = 10
+//! ```
+
+use super::prelude::*;
+use crate::util::state_from_thresholds;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub url: String,
+    pub query: String,
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default = "default_interval")]
+    pub interval: Seconds,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub bearer_token: Option<String>,
+    #[serde(default)]
+    pub hide_if_empty: bool,
+    pub info: Option<f64>,
+    pub good: Option<f64>,
+    pub warning: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+fn default_interval() -> Seconds {
+    Seconds::new(30)
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $value.eng(w:3) ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let fetch = || query(config);
+        let result = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let values = result.values();
+        match values.first() {
+            None if config.hide_if_empty => api.hide()?,
+            None => return Err(Error::new("The query returned no data")),
+            Some(&value) => {
+                let mut widget = Widget::new().with_format(format.clone());
+                widget.state = state_from_thresholds(
+                    value,
+                    config.info,
+                    config.good,
+                    config.warning,
+                    config.critical,
+                );
+                widget.set_values(map! {
+                    "value" => Value::number(value),
+                    "series" => Value::number(values.len()),
+                });
+                api.set_widget(widget)?;
+            }
+        }
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum ApiResponse {
+    Success { data: QueryResult },
+    Error { error: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "resultType", content = "result", rename_all = "lowercase")]
+enum QueryResult {
+    Vector(Vec<Series>),
+    Matrix(Vec<RangeSeries>),
+    Scalar(Sample),
+    String(Sample),
+}
+
+#[derive(Deserialize, Debug)]
+struct Series {
+    value: Sample,
+}
+
+#[derive(Deserialize, Debug)]
+struct RangeSeries {
+    values: Vec<Sample>,
+}
+
+/// A `[timestamp, "value"]` pair; the timestamp is not needed
+#[derive(Deserialize, Debug)]
+struct Sample(serde::de::IgnoredAny, String);
+
+impl QueryResult {
+    fn values(&self) -> Vec<f64> {
+        match self {
+            Self::Vector(series) => series
+                .iter()
+                .filter_map(|s| s.value.1.parse().ok())
+                .collect(),
+            Self::Matrix(series) => series
+                .iter()
+                .filter_map(|s| s.values.last()?.1.parse().ok())
+                .collect(),
+            Self::Scalar(sample) | Self::String(sample) => sample.1.parse().into_iter().collect(),
+        }
+    }
+}
+
+async fn query(config: &Config) -> Result<QueryResult> {
+    let mut request = REQWEST_CLIENT
+        .get(format!("{}/api/v1/query", config.url.trim_end_matches('/')))
+        .query(&[("query", &config.query)]);
+    if let Some(username) = &config.username {
+        request = request.basic_auth(username, config.password.as_ref());
+    }
+    if let Some(token) = &config.bearer_token {
+        request = request.bearer_auth(token);
+    }
+
+    let response: ApiResponse = request
+        .send()
+        .await
+        .error("Failed to send request")?
+        .json()
+        .await
+        .error("Failed to parse JSON")?;
+
+    match response {
+        ApiResponse::Success { data } => Ok(data),
+        ApiResponse::Error { error } => Err(Error::new(format!("Query failed: {error}"))),
+    }
+}
@@ -0,0 +1,247 @@
+//! Public transport departures
+//!
+//! This block shows the next departures from a public transport stop. Departure data is fetched
+//! from a provider (see below). As the next departure approaches, the block refreshes more often,
+//! so delays announced at the last minute are picked up: it updates at least every quarter of the
+//! time left until the next departure, but never more often than every 30 seconds.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `service` | The configuration of a departure provider (see below). | `{ name = "transport_rest" }`
+//! `stop` | The ID of the stop, as used by the provider | **Required**
+//! `lines` | If not empty, only departures of these lines (e.g. `["S3", "U2"]`) are shown. Whitespace is ignored, so `"S3"` also matches `"S 3"`. | `[]`
+//! `walking_time` | Departures leaving sooner than this, in seconds, are skipped | `0`
+//! `delay_warning` | Delay, in seconds, from which the block is set to the warning state | `300`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $line $direction.str(max_w:15) $minutes min "`
+//! `format_no_departures` | Same as `format`, but for when there are no upcoming departures. Only `icon` is available. | `" $icon none "`
+//! `interval` | Update interval in seconds, used while the next departure is far away | `300`
+//!
+//! # transport.rest Options
+//!
+//! Uses one of the [transport.rest](https://transport.rest) APIs, which don't require an API key.
+//! The default is the Deutsche Bahn API, which covers most of Germany. Stop IDs can be looked up
+//! with the `/locations?query=...` endpoint of the API, e.g.
+//! `https://v6.db.transport.rest/locations?query=berlin+hbf`.
+//!
+//! Key | Values | Required | Default
+//! ----|--------|----------|--------
+//! `name` | `transport_rest`. | Yes | None
+//! `base_url` | The URL of the API, e.g. `"https://v6.vbb.transport.rest"` | No | `"https://v6.db.transport.rest"`
+//!
+//! # Available Format Keys
+//!
+//! Placeholder    | Value                                                          | Type     | Unit
+//! ---------------|----------------------------------------------------------------|----------|-----
+//! `icon`         | A static icon                                                  | Icon     | -
+//! `line`         | The name of the line of the next departure                     | Text     | -
+//! `direction`    | The direction of the next departure                            | Text     | -
+//! `minutes`      | Minutes until the next departure, including the delay          | Number   | -
+//! `time`         | The time of the next departure, including the delay            | Datetime | -
+//! `delay`        | The delay of the next departure in minutes (absent if unknown) | Number   | -
+//! `platform`     | The platform of the next departure (absent if unknown)         | Text     | -
+//! `cancelled`    | Present if the next departure is cancelled                     | Flag     | -
+//! `next_line`    | The name of the line of the departure after that               | Text     | -
+//! `next_minutes` | Minutes until the departure after that                         | Number   | -
+//!
+//! The block is set to the critical state if the next departure is cancelled and to the warning
+//! state if it is delayed by at least `delay_warning`. If there are no departures within the next
+//! two hours, the block is set to the idle state and only `icon` is available.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "public_transport"
+//! stop = "8011160"
+//! lines = ["S3", "S5"]
+//! walking_time = 300
+//! format = " $icon $line $minutes min{ +$delay|}{ | $next_minutes min|} "
+//! ```
+//!
+//! ```toml
+//! [[block]]
+//! block = "public_transport"
+//! stop = "900100003"
+//! [block.service]
+//! name = "transport_rest"
+//! base_url = "https://v6.vbb.transport.rest"
+//! ```
+//!
+//! # Icons Used
+//! - `transit`
+
+pub mod transport_rest;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::prelude::*;
+
+/// Departures further away than this are not requested from the provider
+const LOOKAHEAD_MINUTES: u32 = 120;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub service: DepartureService,
+    pub stop: String,
+    pub lines: Vec<String>,
+    #[default(0.into())]
+    pub walking_time: Seconds,
+    #[default(300.into())]
+    pub delay_warning: Seconds,
+    pub format: FormatConfig,
+    pub format_no_departures: FormatConfig,
+    #[default(300.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum DepartureService {
+    #[default]
+    TransportRest(transport_rest::Config),
+}
+
+#[async_trait]
+trait DepartureProvider {
+    /// Returns the departures from `stop` within the next `minutes`, in no particular order
+    async fn get_departures(&self, stop: &str, minutes: u32) -> Result<Vec<Departure>>;
+}
+
+#[derive(Debug)]
+struct Departure {
+    line: String,
+    direction: String,
+    /// The expected time of departure, including the delay if known
+    when: DateTime<Utc>,
+    /// The delay in seconds, if known
+    delay: Option<i64>,
+    platform: Option<String>,
+    cancelled: bool,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $line $direction.str(max_w:15) $minutes min ")?;
+    let format_no_departures = config.format_no_departures.with_default(" $icon none ")?;
+
+    if config.stop.is_empty() {
+        return Err(Error::new("`stop` is required"));
+    }
+
+    let lines: Vec<String> = config.lines.iter().map(|l| normalize_line(l)).collect();
+
+    let provider: Box<dyn DepartureProvider + Send + Sync> = match &config.service {
+        DepartureService::TransportRest(service_config) => {
+            Box::new(transport_rest::Service::new(service_config))
+        }
+    };
+
+    loop {
+        let fetch = || provider.get_departures(&config.stop, LOOKAHEAD_MINUTES);
+        let departures = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let now = Utc::now();
+        let earliest = now + Duration::from_secs(config.walking_time.seconds());
+        let mut departures: Vec<Departure> = departures
+            .into_iter()
+            .filter(|d| d.when >= earliest)
+            .filter(|d| lines.is_empty() || lines.contains(&normalize_line(&d.line)))
+            .collect();
+        departures.sort_by_key(|d| d.when);
+
+        let mut widget = Widget::new();
+        let mut values = map! {
+            "icon" => Value::icon("transit"),
+        };
+        match departures.first() {
+            Some(first) => {
+                widget.set_format(format.clone());
+                widget.state = if first.cancelled {
+                    State::Critical
+                } else if first
+                    .delay
+                    .is_some_and(|d| d >= config.delay_warning.seconds() as i64)
+                {
+                    State::Warning
+                } else {
+                    State::Idle
+                };
+                values.extend(map! {
+                    "line" => Value::text(first.line.clone()),
+                    "direction" => Value::text(first.direction.clone()),
+                    "minutes" => Value::number(minutes_until(now, first.when)),
+                    "time" => Value::datetime(first.when, None),
+                    [if let Some(delay) = first.delay] "delay" => Value::number(delay / 60),
+                    [if let Some(platform) = &first.platform] "platform" => Value::text(platform.clone()),
+                    [if first.cancelled] "cancelled" => Value::flag(),
+                });
+                if let Some(next) = departures.get(1) {
+                    values.extend(map! {
+                        "next_line" => Value::text(next.line.clone()),
+                        "next_minutes" => Value::number(minutes_until(now, next.when)),
+                    });
+                }
+            }
+            None => {
+                widget.set_format(format_no_departures.clone());
+                widget.state = State::Idle;
+            }
+        }
+        widget.set_values(values);
+        api.set_widget(widget)?;
+
+        let delay = refresh_delay(
+            departures.first().map(|d| d.when - now),
+            config.interval.seconds(),
+        );
+        select! {
+            _ = sleep(delay) => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Removes all whitespace, since providers differ in whether they write e.g. `S3` or `S 3`
+fn normalize_line(line: &str) -> String {
+    line.split_whitespace().collect()
+}
+
+fn minutes_until(now: DateTime<Utc>, when: DateTime<Utc>) -> i64 {
+    (when - now).num_minutes().max(0)
+}
+
+/// Returns how long to wait before fetching the departures again, given the time left until the
+/// next departure
+fn refresh_delay(until_next: Option<TimeDelta>, interval: u64) -> Duration {
+    let secs = match until_next {
+        Some(until) => (until.num_seconds().max(0) as u64 / 4).clamp(30, interval.max(30)),
+        None => interval,
+    };
+    Duration::from_secs(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_delay() {
+        let mins = TimeDelta::try_minutes;
+        assert_eq!(refresh_delay(None, 300), Duration::from_secs(300));
+        assert_eq!(refresh_delay(mins(60), 300), Duration::from_secs(300));
+        assert_eq!(refresh_delay(mins(8), 300), Duration::from_secs(120));
+        assert_eq!(refresh_delay(mins(1), 300), Duration::from_secs(30));
+        assert_eq!(refresh_delay(mins(-1), 300), Duration::from_secs(30));
+        assert_eq!(refresh_delay(mins(8), 10), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_normalize_line() {
+        assert_eq!(normalize_line("S 3"), normalize_line("S3"));
+        assert_eq!(normalize_line(" Bus  M41 "), "BusM41");
+    }
+}
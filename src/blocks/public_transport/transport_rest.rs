@@ -0,0 +1,147 @@
+use super::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("https://v6.db.transport.rest".into())]
+    base_url: String,
+}
+
+pub(super) struct Service {
+    base_url: String,
+}
+
+impl Service {
+    pub(super) fn new(config: &Config) -> Self {
+        Self {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+/// Version 6 of the APIs wraps the departures in an object, older versions return them directly
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ApiResponse {
+    Wrapped { departures: Vec<ApiDeparture> },
+    Plain(Vec<ApiDeparture>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiDeparture {
+    when: Option<String>,
+    planned_when: Option<String>,
+    delay: Option<i64>,
+    platform: Option<String>,
+    planned_platform: Option<String>,
+    direction: Option<String>,
+    line: Option<Line>,
+    #[serde(default)]
+    cancelled: bool,
+}
+
+#[derive(Deserialize)]
+struct Line {
+    name: Option<String>,
+}
+
+#[async_trait]
+impl DepartureProvider for Service {
+    async fn get_departures(&self, stop: &str, minutes: u32) -> Result<Vec<Departure>> {
+        // https://v6.db.transport.rest/api.html#get-stopsiddepartures
+        let response: ApiResponse = REQWEST_CLIENT
+            .get(format!("{}/stops/{stop}/departures", self.base_url))
+            .query(&[
+                ("duration", minutes.to_string().as_str()),
+                ("remarks", "false"),
+            ])
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to get departures")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+
+        let departures = match response {
+            ApiResponse::Wrapped { departures } | ApiResponse::Plain(departures) => departures,
+        };
+        Ok(departures.into_iter().filter_map(convert).collect())
+    }
+}
+
+/// Converts a departure of the API, skipping it if its time is unknown
+fn convert(departure: ApiDeparture) -> Option<Departure> {
+    // Cancelled departures only have a planned time
+    let when = departure.when.or(departure.planned_when)?;
+    Some(Departure {
+        line: departure
+            .line
+            .and_then(|l| l.name)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        direction: departure.direction.unwrap_or_default(),
+        when: DateTime::parse_from_rfc3339(&when)
+            .ok()?
+            .with_timezone(&Utc),
+        delay: departure.delay,
+        platform: departure.platform.or(departure.planned_platform),
+        cancelled: departure.cancelled,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_departures() {
+        let json = r#"{
+            "departures": [
+                {
+                    "when": "2025-06-10T12:04:00+02:00",
+                    "plannedWhen": "2025-06-10T12:02:00+02:00",
+                    "delay": 120,
+                    "platform": "5",
+                    "plannedPlatform": "4",
+                    "direction": "S Spandau",
+                    "line": { "name": "S 3", "product": "suburban" }
+                },
+                {
+                    "when": null,
+                    "plannedWhen": "2025-06-10T12:10:00+02:00",
+                    "delay": null,
+                    "platform": null,
+                    "plannedPlatform": "2",
+                    "direction": "Erkner",
+                    "line": { "name": "S3" },
+                    "cancelled": true
+                },
+                { "when": null, "plannedWhen": null, "direction": "Nowhere" }
+            ],
+            "realtimeDataUpdatedAt": 1749549600
+        }"#;
+        let ApiResponse::Wrapped { departures } = serde_json::from_str(json).unwrap() else {
+            panic!("expected a wrapped response");
+        };
+        let departures: Vec<_> = departures.into_iter().filter_map(convert).collect();
+        assert_eq!(departures.len(), 2);
+
+        assert_eq!(departures[0].line, "S 3");
+        assert_eq!(departures[0].direction, "S Spandau");
+        assert_eq!(
+            departures[0].when,
+            DateTime::parse_from_rfc3339("2025-06-10T10:04:00Z").unwrap()
+        );
+        assert_eq!(departures[0].delay, Some(120));
+        assert_eq!(departures[0].platform.as_deref(), Some("5"));
+        assert!(!departures[0].cancelled);
+
+        assert_eq!(departures[1].delay, None);
+        assert_eq!(departures[1].platform.as_deref(), Some("2"));
+        assert!(departures[1].cancelled);
+    }
+}
@@ -0,0 +1,352 @@
+//! New items of RSS and Atom feeds
+//!
+//! This block polls one or more feeds and shows the number of items which haven't been marked as
+//! seen yet, along with the title of the newest one. Long titles can be scrolled using the
+//! `rot_interval` argument of the `str` formatter.
+//!
+//! Seen items are remembered in `seen_file`, one ID per line. If it is not set, all the items that
+//! are present when the block starts are considered seen.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `feeds` | List of feed URLs | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $unseen.eng(w:1) "`
+//! `interval` | Update interval in seconds | `1800`
+//! `seen_file` | File to store the IDs of seen items in. Supports path expansions e.g. `~`. | `None`
+//! `hide_if_none_unseen` | Hide the block if there are no unseen items | `false`
+//!
+//! Placeholder  | Value                                                      | Type   | Unit
+//! -------------|------------------------------------------------------------|--------|-----
+//! `icon`       | A static icon                                              | Icon   | -
+//! `unseen`     | The number of unseen items                                 | Number | -
+//! `total`      | The number of items in all feeds                           | Number | -
+//! `title`      | The title of the newest unseen item (absent if none)       | Text   | -
+//! `feed_title` | The title of the feed of the newest unseen item            | Text   | -
+//!
+//! The block is set to the info state if there are unseen items.
+//!
+//! Action      | Description                                              | Default button
+//! ------------|----------------------------------------------------------|---------------
+//! `mark_seen` | Mark all items as seen                                   | Left
+//! `open`      | Open the newest unseen item using `xdg-open` and mark it as seen | Right
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "rss"
+//! feeds = [
+//!     "https://blog.rust-lang.org/feed.xml",
+//!     "https://this-week-in-rust.org/rss.xml",
+//! ]
+//! seen_file = "~/.cache/i3status-rust/rss-seen"
+//! format = " $icon $unseen.eng(w:1){ $title.str(max_w:30,rot_interval:0.5)|} "
+//! hide_if_none_unseen = true
+//! ```
+//!
+//! # Icons Used
+//! - `rss`
+
+use super::prelude::*;
+use crate::subprocess::spawn_process;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub feeds: Vec<String>,
+    pub format: FormatConfig,
+    #[default(1800.into())]
+    pub interval: Seconds,
+    pub seen_file: Option<ShellString>,
+    pub hide_if_none_unseen: bool,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "mark_seen"),
+        (MouseButton::Right, None, "open"),
+    ])?;
+
+    let format = config.format.with_default(" $icon $unseen.eng(w:1) ")?;
+    let mut timer = config.interval.timer();
+
+    if config.feeds.is_empty() {
+        return Err(Error::new("`feeds` is empty"));
+    }
+
+    let seen_file = config.seen_file.as_ref().map(|f| f.expand()).transpose()?;
+    let mut seen: Option<HashSet<String>> = match &seen_file {
+        Some(path) => Some(match tokio::fs::read_to_string(&**path).await {
+            Ok(content) => content.lines().map(String::from).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(Error::new(format!("Failed to read seen_file: {e}"))),
+        }),
+        None => None,
+    };
+
+    loop {
+        let mut items = Vec::new();
+        for url in &config.feeds {
+            let fetch = || get_feed(url);
+            items.extend(fetch.retry(&ExponentialBuilder::default()).await?);
+        }
+        let total = items.len();
+
+        let seen = seen.get_or_insert_with(|| items.iter().map(|i| i.id.clone()).collect());
+        let mut unseen: Vec<&Item> = items.iter().filter(|i| !seen.contains(&i.id)).collect();
+        // Items without a date are considered the oldest
+        unseen.sort_by_key(|i| std::cmp::Reverse(i.date));
+
+        if unseen.is_empty() && config.hide_if_none_unseen {
+            api.hide()?;
+        } else {
+            let newest = unseen.first();
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = if unseen.is_empty() {
+                State::Idle
+            } else {
+                State::Info
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("rss"),
+                "unseen" => Value::number(unseen.len()),
+                "total" => Value::number(total),
+                [if let Some(item) = newest] "title" => Value::text(item.title.clone()),
+                [if let Some(item) = newest] "feed_title" => Value::text(item.feed_title.clone()),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+            Some(action) = actions.recv() => {
+                match action.as_ref() {
+                    "mark_seen" => {
+                        seen.extend(unseen.iter().map(|i| i.id.clone()));
+                    }
+                    "open" => {
+                        if let Some(item) = unseen.first() {
+                            if let Some(link) = &item.link {
+                                spawn_process("xdg-open", &[link]).error("Failed to run xdg-open")?;
+                            }
+                            seen.insert(item.id.clone());
+                        }
+                    }
+                    _ => (),
+                }
+                if let Some(path) = &seen_file {
+                    // Forget items which are no longer in the feeds
+                    let ids: HashSet<&str> = items.iter().map(|i| i.id.as_str()).collect();
+                    seen.retain(|id| ids.contains(id.as_str()));
+                    let mut content: Vec<&str> = seen.iter().map(String::as_str).collect();
+                    content.sort_unstable();
+                    tokio::fs::write(&**path, content.join("\n"))
+                        .await
+                        .error("Failed to write seen_file")?;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Item {
+    id: String,
+    title: String,
+    link: Option<String>,
+    date: Option<DateTime<Utc>>,
+    feed_title: String,
+}
+
+async fn get_feed(url: &str) -> Result<Vec<Item>> {
+    let body = REQWEST_CLIENT
+        .get(url)
+        .send()
+        .await
+        .error("Failed to send request")?
+        .error_for_status()
+        .or_error(|| format!("Failed to get feed {url}"))?
+        .text()
+        .await
+        .error("Failed to get response body")?;
+    Ok(parse_feed(&body))
+}
+
+/// Extracts the items of an RSS 2.0 or Atom feed
+fn parse_feed(xml: &str) -> Vec<Item> {
+    let item_regex = regex!(r"(?s)<(item|entry)\b[^>]*>(.*?)</(?:item|entry)>");
+    let atom_link_regex = regex!(r#"<link\b([^>]*)/?>"#);
+    let href_regex = regex!(r#"\bhref\s*=\s*"([^"]*)""#);
+    let rel_regex = regex!(r#"\brel\s*=\s*"([^"]*)""#);
+
+    let header = match item_regex.find(xml) {
+        Some(m) => &xml[..m.start()],
+        None => xml,
+    };
+    let feed_title = element_text(header, "title").unwrap_or_default();
+
+    item_regex
+        .captures_iter(xml)
+        .filter_map(|captures| {
+            let body = captures.get(2)?.as_str();
+            let title = element_text(body, "title").unwrap_or_default();
+            let (id, link, date) = if &captures[1] == "item" {
+                let date = element_text(body, "pubDate")
+                    .and_then(|d| DateTime::parse_from_rfc2822(&d).ok());
+                (element_text(body, "guid"), element_text(body, "link"), date)
+            } else {
+                let link = atom_link_regex
+                    .captures_iter(body)
+                    .map(|link| link[1].to_string())
+                    .find(|attrs| {
+                        rel_regex
+                            .captures(attrs)
+                            .is_none_or(|rel| &rel[1] == "alternate")
+                    })
+                    .and_then(|attrs| Some(unescape(&href_regex.captures(&attrs)?[1])));
+                let date = element_text(body, "updated")
+                    .or_else(|| element_text(body, "published"))
+                    .and_then(|d| DateTime::parse_from_rfc3339(&d).ok());
+                (element_text(body, "id"), link, date)
+            };
+            Some(Item {
+                id: id.or_else(|| link.clone()).unwrap_or_else(|| title.clone()),
+                title,
+                link,
+                date: date.map(|d| d.with_timezone(&Utc)),
+                feed_title: feed_title.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the unescaped text of the first `<tag>` element
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{tag}"))?;
+    let rest = &xml[start + tag.len() + 1..];
+    // Don't match e.g. `<titles>`
+    if !rest.starts_with(|c: char| c == '>' || c.is_whitespace()) {
+        return None;
+    }
+    let rest = &rest[rest.find('>')? + 1..];
+    let text = &rest[..rest.find(&format!("</{tag}>"))?];
+    let text = text.trim();
+    Some(match text.strip_prefix("<![CDATA[") {
+        Some(cdata) => cdata
+            .strip_suffix("]]>")
+            .unwrap_or(cdata)
+            .trim()
+            .to_string(),
+        None => unescape(text),
+    })
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(|code| char::from_u32(code.ok()?)),
+        };
+        match c {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+  <title>Example &amp; Co</title>
+  <link>https://example.com/</link>
+  <item>
+    <title><![CDATA[First <post>]]></title>
+    <link>https://example.com/1</link>
+    <guid isPermaLink="false">post-1</guid>
+    <pubDate>Tue, 10 Jun 2025 04:00:00 +0000</pubDate>
+  </item>
+  <item>
+    <title>Second &#8220;post&#8221;</title>
+    <link>https://example.com/2</link>
+  </item>
+</channel>
+</rss>"#;
+        let items = parse_feed(xml);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "post-1");
+        assert_eq!(items[0].title, "First <post>");
+        assert_eq!(items[0].feed_title, "Example & Co");
+        assert_eq!(
+            items[0].date,
+            Some(
+                DateTime::parse_from_rfc3339("2025-06-10T04:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+        assert_eq!(items[1].id, "https://example.com/2");
+        assert_eq!(items[1].title, "Second \u{201c}post\u{201d}");
+        assert_eq!(items[1].date, None);
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">Atom example</title>
+  <entry>
+    <title>An entry</title>
+    <link rel="self" href="https://example.org/self"/>
+    <link rel="alternate" href="https://example.org/entry?a=1&amp;b=2"/>
+    <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+    <updated>2025-12-13T18:30:02Z</updated>
+  </entry>
+</feed>"#;
+        let items = parse_feed(xml);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a");
+        assert_eq!(items[0].title, "An entry");
+        assert_eq!(items[0].feed_title, "Atom example");
+        assert_eq!(
+            items[0].link.as_deref(),
+            Some("https://example.org/entry?a=1&b=2")
+        );
+        assert!(items[0].date.is_some());
+    }
+}
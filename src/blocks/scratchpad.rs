@@ -0,0 +1,104 @@
+//! Windows in the scratchpad of sway or i3
+//!
+//! This block shows how many windows are in the scratchpad, so that they are not forgotten there.
+//! It is hidden while the scratchpad is empty.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count "`
+//!
+//! Placeholder | Value                                                                   | Type   | Unit
+//! ------------|-------------------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                           | Icon   | -
+//! `count`     | The number of windows in the scratchpad                                 | Number | -
+//! `titles`    | The titles of the windows in the scratchpad, separated by vertical bars | Text   | -
+//!
+//! Action | Description                                                | Default button
+//! -------|------------------------------------------------------------|---------------
+//! `show` | Show the next window of the scratchpad (`scratchpad show`) | Left
+//!
+//! # Example
+//!
+//! Cycle through the titles of the hidden windows:
+//!
+//! ```toml
+//! [[block]]
+//! block = "scratchpad"
+//! format = " $icon $count $titles.str(max_w:25,rot_interval:0.5) "
+//! ```
+//!
+//! # Icons Used
+//! - `scratchpad`
+
+use swayipc_async::{Connection, EventType};
+
+use super::prelude::*;
+use super::sway_tree::windows;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "show")])?;
+
+    let format = config.format.with_default(" $icon $count ")?;
+
+    let mut connection = Connection::new()
+        .await
+        .error("Failed to open swayipc connection")?;
+    let mut events = Connection::new()
+        .await
+        .error("Failed to open swayipc connection")?
+        .subscribe([EventType::Window])
+        .await
+        .error("Failed to subscribe to events")?;
+
+    loop {
+        let tree = connection
+            .get_tree()
+            .await
+            .error("Failed to get the tree")?;
+        let windows = tree
+            .find_as_ref(|n| n.name.as_deref() == Some("__i3_scratch"))
+            .map(windows)
+            .unwrap_or_default();
+
+        if windows.is_empty() {
+            api.hide()?;
+        } else {
+            let titles: Vec<&str> = windows.iter().filter_map(|w| w.name.as_deref()).collect();
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.set_values(map! {
+                "icon" => Value::icon("scratchpad"),
+                "count" => Value::number(windows.len()),
+                "titles" => Value::text(titles.join(" | ")),
+            });
+            api.set_widget(widget)?;
+        }
+
+        loop {
+            select! {
+                event = events.next() => {
+                    event.error("swayipc channel closed")?.error("bad event")?;
+                    break;
+                }
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "show" => {
+                        connection
+                            .run_command("scratchpad show")
+                            .await
+                            .error("Failed to run 'scratchpad show'")?;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Screen recording indicator
+//!
+//! This block shows whether the screen is being recorded, and for how long. A recording is
+//! detected by matching the command lines of the running processes against `pattern`, which by
+//! default matches `wf-recorder`, `wl-screenrec`, `gpu-screen-recorder` and `ffmpeg` grabbing the
+//! screen. The block is hidden while nothing is recording. OBS is not matched by default, because
+//! it is often left running without recording, and its command line doesn't show whether it is.
+//!
+//! Clicking the block stops the recording by running `stop_command`, or, if it is not set, by
+//! sending `SIGINT` to the recording processes, which makes the tools above finish the file
+//! properly.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `pattern` | A regex to match the command lines of the recording processes against | See above
+//! `stop_command` | A shell command to stop the recording | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $time "`
+//! `interval` | Update interval in seconds. Each update scans all processes in `/proc`. | `5`
+//!
+//! Placeholder | Value                                              | Type   | Unit
+//! ------------|----------------------------------------------------|--------|--------
+//! `icon`      | A static icon                                      | Icon   | -
+//! `time`      | For how long the oldest recording has been running | Number | Seconds
+//! `name`      | The name of the oldest recording process           | Text   | -
+//! `count`     | The number of recording processes                  | Number | -
+//!
+//! Action | Description        | Default button
+//! -------|--------------------|---------------
+//! `stop` | Stop the recording | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "screen_recording"
+//! pattern = "^wf-recorder"
+//! stop_command = "pkill -INT wf-recorder && notify-send 'Recording saved'"
+//! format = " $icon $name "
+//! ```
+//!
+//! # Icons Used
+//! - `recording`
+
+use super::prelude::*;
+use super::process::list_processes;
+use crate::subprocess::spawn_shell;
+use crate::util::read_file;
+use crate::wrappers::SerdeRegex;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub pattern: Option<SerdeRegex>,
+    pub stop_command: Option<String>,
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "stop")])?;
+
+    let format = config.format.with_default(" $icon $time ")?;
+    let mut timer = config.interval.timer();
+
+    let pattern = match &config.pattern {
+        Some(pattern) => &pattern.0,
+        None => regex!(
+            r"^(\S*/)?(wf-recorder|wl-screenrec|gpu-screen-recorder|ffmpeg .*-f (x11grab|kmsgrab))\b"
+        ),
+    };
+    // Safety: sysconf is always safe to call
+    let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as f64;
+
+    loop {
+        let mut recordings: Vec<_> = list_processes(true)
+            .await?
+            .into_iter()
+            .filter(|p| p.cmdline.as_deref().is_some_and(|c| pattern.is_match(c)))
+            .collect();
+        recordings.sort_by_key(|p| p.start_time);
+
+        match recordings.first() {
+            None => api.hide()?,
+            Some(oldest) => {
+                let uptime = uptime().await?;
+                let started = oldest.start_time as f64 / ticks_per_sec;
+                let mut widget = Widget::new().with_format(format.clone());
+                widget.state = State::Critical;
+                widget.set_values(map! {
+                    "icon" => Value::icon("recording"),
+                    "time" => Value::seconds((uptime - started).max(0.0).round()),
+                    "name" => Value::text(oldest.name.clone()),
+                    "count" => Value::number(recordings.len()),
+                });
+                api.set_widget(widget)?;
+            }
+        }
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "stop" => {
+                        match &config.stop_command {
+                            Some(cmd) => {
+                                spawn_shell(cmd).or_error(|| format!("Failed to run '{cmd}'"))?;
+                            }
+                            None => {
+                                for p in &recordings {
+                                    // Safety: kill is always safe to call
+                                    unsafe { libc::kill(p.pid as libc::pid_t, libc::SIGINT) };
+                                }
+                            }
+                        }
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the time since boot, in seconds
+async fn uptime() -> Result<f64> {
+    read_file("/proc/uptime")
+        .await
+        .error("Failed to read /proc/uptime")?
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse().ok())
+        .error("/proc/uptime has invalid content")
+}
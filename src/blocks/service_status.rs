@@ -1,6 +1,9 @@
 //! Display the status of a service
 //!
-//! Right now only `systemd` is supported.
+//! Right now only `systemd` is supported. Both system and user (`user = true`) services can be
+//! watched. The block is updated as soon as the state of the service changes, and the service can
+//! be controlled using the actions listed below. Controlling system services may require
+//! additional privileges (e.g. a polkit rule).
 //!
 //! # Configuration
 //!
@@ -8,14 +11,24 @@
 //! ----|--------|--------
 //! `driver` | Which init system is running the service. Available drivers are: `"systemd"` | `"systemd"`
 //! `service` | The name of the service | **Required**
+//! `user` | Whether the service is a user service | `false`
 //! `active_format` | A string to customise the output of this block. See below for available placeholders. | `" $service active "`
 //! `inactive_format` | A string to customise the output of this block. See below for available placeholders. | `" $service inactive "`
 //! `active_state` | A valid [`State`] | [`State::Idle`]
 //! `inactive_state` | A valid [`State`]  | [`State::Critical`]
+//! `failed_state` | A valid [`State`], used if the service has failed | [`State::Critical`]
 //!
-//! Placeholder    | Value                     | Type   | Unit
-//! ---------------|---------------------------|--------|-----
-//! `service`      | The name of the service   | Text   | -
+//! Placeholder    | Value                                                       | Type   | Unit
+//! ---------------|-------------------------------------------------------------|--------|-----
+//! `service`      | The name of the service                                     | Text   | -
+//! `active_state` | The `ActiveState` of the service, e.g. `failed` or `activating` | Text   | -
+//!
+//! Action    | Description                | Default button
+//! ----------|----------------------------|---------------
+//! `start`   | Start the service          | -
+//! `stop`    | Stop the service           | -
+//! `restart` | Restart the service        | -
+//! `toggle`  | Start or stop the service  | -
 //!
 //! # Example
 //!
@@ -40,6 +53,20 @@
 //! inactive_state = "Warning"
 //! ```
 //!
+//! Example watching a user service, which is restarted on left click:
+//!
+//! ```toml
+//! [[block]]
+//! block = "service_status"
+//! service = "syncthing"
+//! user = true
+//! active_format = " sync "
+//! inactive_format = " sync $active_state "
+//! [[block.click]]
+//! button = "left"
+//! action = "restart"
+//! ```
+//!
 
 use super::prelude::*;
 use zbus::PropertyStream;
@@ -49,10 +76,12 @@ use zbus::PropertyStream;
 pub struct Config {
     pub driver: DriverType,
     pub service: String,
+    pub user: bool,
     pub active_format: FormatConfig,
     pub inactive_format: FormatConfig,
     pub active_state: Option<State>,
     pub inactive_state: Option<State>,
+    pub failed_state: Option<State>,
 }
 
 #[derive(Deserialize, Debug, SmartDefault)]
@@ -63,43 +92,67 @@ pub enum DriverType {
 }
 
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+
     let active_format = config.active_format.with_default(" $service active ")?;
     let inactive_format = config.inactive_format.with_default(" $service inactive ")?;
 
     let active_state = config.active_state.unwrap_or(State::Idle);
     let inactive_state = config.inactive_state.unwrap_or(State::Critical);
+    let failed_state = config.failed_state.unwrap_or(State::Critical);
 
     let mut driver: Box<dyn Driver> = match config.driver {
-        DriverType::Systemd => Box::new(SystemdDriver::new(config.service.clone()).await?),
+        DriverType::Systemd => {
+            Box::new(SystemdDriver::new(config.service.clone(), config.user).await?)
+        }
     };
 
     loop {
-        let service_active_state = driver.is_active().await?;
+        let service_active_state = driver.active_state().await?;
+        let is_active = service_active_state == "active";
 
         let mut widget = Widget::new();
 
-        if service_active_state {
+        if is_active {
             widget.state = active_state;
             widget.set_format(active_format.clone());
         } else {
-            widget.state = inactive_state;
+            widget.state = if service_active_state == "failed" {
+                failed_state
+            } else {
+                inactive_state
+            };
             widget.set_format(inactive_format.clone());
         };
 
         widget.set_values(map! {
             "service" =>Value::text(config.service.clone()),
+            "active_state" => Value::text(service_active_state),
         });
 
         api.set_widget(widget)?;
 
-        driver.wait_for_change().await?;
+        let action = select! {
+            res = driver.wait_for_change() => { res?; None },
+            Some(action) = actions.recv() => Some(action),
+        };
+        match action.as_deref() {
+            Some("toggle") if is_active => driver.stop().await?,
+            Some("start" | "toggle") => driver.start().await?,
+            Some("stop") => driver.stop().await?,
+            Some("restart") => driver.restart().await?,
+            _ => (),
+        }
     }
 }
 
 #[async_trait]
 trait Driver {
-    async fn is_active(&self) -> Result<bool>;
+    async fn active_state(&self) -> Result<String>;
     async fn wait_for_change(&mut self) -> Result<()>;
+    async fn start(&self) -> Result<()>;
+    async fn stop(&self) -> Result<()>;
+    async fn restart(&self) -> Result<()>;
 }
 
 struct SystemdDriver {
@@ -108,8 +161,12 @@ struct SystemdDriver {
 }
 
 impl SystemdDriver {
-    async fn new(service: String) -> Result<Self> {
-        let dbus_conn = new_system_dbus_connection().await?;
+    async fn new(service: String, user: bool) -> Result<Self> {
+        let dbus_conn = if user {
+            new_dbus_connection().await?
+        } else {
+            new_system_dbus_connection().await?
+        };
 
         if !service.is_ascii() {
             return Err(Error::new(format!(
@@ -148,18 +205,41 @@ impl SystemdDriver {
 
 #[async_trait]
 impl Driver for SystemdDriver {
-    async fn is_active(&self) -> Result<bool> {
+    async fn active_state(&self) -> Result<String> {
         self.proxy
             .active_state()
             .await
             .error("Could not get active_state")
-            .map(|state| state == "active")
     }
 
     async fn wait_for_change(&mut self) -> Result<()> {
         self.active_state_changed.next().await;
         Ok(())
     }
+
+    async fn start(&self) -> Result<()> {
+        self.proxy
+            .start("replace")
+            .await
+            .error("Failed to start service")?;
+        Ok(())
+    }
+
+    async fn stop(&self) -> Result<()> {
+        self.proxy
+            .stop("replace")
+            .await
+            .error("Failed to stop service")?;
+        Ok(())
+    }
+
+    async fn restart(&self) -> Result<()> {
+        self.proxy
+            .restart("replace")
+            .await
+            .error("Failed to restart service")?;
+        Ok(())
+    }
 }
 
 #[zbus::proxy(
@@ -167,6 +247,10 @@ impl Driver for SystemdDriver {
     default_service = "org.freedesktop.systemd1"
 )]
 trait Unit {
+    fn start(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    fn stop(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    fn restart(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
     #[zbus(property)]
     fn active_state(&self) -> zbus::Result<String>;
 }
@@ -0,0 +1,146 @@
+//! Pending snap refreshes
+//!
+//! This block queries snapd for snaps with pending refreshes. Refreshes of running applications
+//! are postponed by snapd, but only for a limited time, after which the application is closed and
+//! refreshed anyway. The block shows when the earliest of these forced refreshes is going to
+//! happen, so it doesn't come as a surprise.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count{ forced $forced_at.datetime(f:'%a %R')\|} "`
+//! `format_up_to_date` | Same as `format`, but for when no refreshes are pending | `" $icon $count "`
+//! `interval` | Update interval in seconds | `600`
+//! `socket_path` | The path to the snapd socket | `"/run/snapd.socket"`
+//! `warning_forced_in` | If a forced refresh is due in less than this many seconds, the state is set to warning | `86400`
+//!
+//! Placeholder | Value                                                         | Type     | Unit
+//! ------------|---------------------------------------------------------------|----------|--------
+//! `icon`      | A static icon                                                 | Icon     | -
+//! `count`     | The number of snaps with pending refreshes                    | Number   | -
+//! `names`     | A comma separated list of the snaps                           | Text     | -
+//! `forced_at` | The time of the earliest forced refresh (absent if none)      | Datetime | -
+//! `forced_in` | The time until the earliest forced refresh (absent if none)   | Number   | Seconds
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "snap"
+//! format = " $icon $names{ (forced $forced_at.datetime(f:'%a %R'))|} "
+//! format_up_to_date = ""
+//! [[block.click]]
+//! button = "left"
+//! cmd = "snap-store --mode=updates"
+//! ```
+//!
+//! # Icons Used
+//! - `update`
+
+use super::prelude::*;
+use crate::util::get_over_unix_socket;
+use chrono::{DateTime, Utc};
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    pub format_up_to_date: FormatConfig,
+    #[default(600.into())]
+    pub interval: Seconds,
+    #[default("/run/snapd.socket".into())]
+    pub socket_path: ShellString,
+    #[default(86400)]
+    pub warning_forced_in: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct Response<T> {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "status-code")]
+    status_code: u16,
+    result: T,
+}
+
+#[derive(Deserialize, Debug)]
+struct Snap {
+    name: String,
+    #[serde(rename = "refresh-inhibit")]
+    refresh_inhibit: Option<RefreshInhibit>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RefreshInhibit {
+    #[serde(rename = "proceed-time")]
+    proceed_time: String,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $count{ forced $forced_at.datetime(f:'%a %R')|} ")?;
+    let format_up_to_date = config.format_up_to_date.with_default(" $icon $count ")?;
+    let socket_path = config.socket_path.expand()?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let refreshes: Response<serde_json::Value> =
+            get_over_unix_socket(&*socket_path, "/v2/find?select=refresh").await?;
+        let mut names: Vec<String> = match (refreshes.kind.as_str(), refreshes.status_code) {
+            // snapd reports an error if no refreshes are available
+            ("error", 404) => Vec::new(),
+            ("error", _) => {
+                return Err(Error::new(format!(
+                    "snapd returned an error: {}",
+                    refreshes.result["message"]
+                )))
+            }
+            _ => serde_json::from_value::<Vec<Snap>>(refreshes.result)
+                .error("Failed to parse snapd response")?
+                .into_iter()
+                .map(|snap| snap.name)
+                .collect(),
+        };
+        names.sort_unstable();
+
+        let installed: Response<Vec<Snap>> =
+            get_over_unix_socket(&*socket_path, "/v2/snaps").await?;
+        let forced_at = installed
+            .result
+            .iter()
+            .filter_map(|snap| {
+                let time = &snap.refresh_inhibit.as_ref()?.proceed_time;
+                DateTime::parse_from_rfc3339(time).ok()
+            })
+            .map(|time| time.with_timezone(&Utc))
+            .min();
+        let forced_in = forced_at.map(|time| (time - Utc::now()).num_seconds().max(0) as u64);
+
+        let mut widget = Widget::new();
+        if names.is_empty() && forced_at.is_none() {
+            widget.set_format(format_up_to_date.clone());
+        } else {
+            widget.set_format(format.clone());
+            widget.state = if forced_in.is_some_and(|s| s < config.warning_forced_in) {
+                State::Warning
+            } else {
+                State::Info
+            };
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon("update"),
+            "count" => Value::number(names.len()),
+            "names" => Value::text(names.join(", ")),
+            [if let Some(time) = forced_at] "forced_at" => Value::datetime(time, None),
+            [if let Some(secs) = forced_in] "forced_in" => Value::seconds(secs),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
@@ -1,19 +1,36 @@
 //! Ping, download, and upload speeds
 //!
-//! This block which requires [`speedtest-cli`](https://github.com/sivel/speedtest-cli).
+//! This block runs a bandwidth test every `interval` seconds or when clicked. It requires one of
+//! the following tools, selected with `tool`:
+//!
+//! - `speedtest_cli`: [`speedtest-cli`](https://github.com/sivel/speedtest-cli)
+//! - `ookla`: the official [Speedtest CLI](https://www.speedtest.net/apps/cli) by Ookla
+//! - `librespeed`: [`librespeed-cli`](https://github.com/librespeed/speedtest-cli), which can also
+//!   test against a self-hosted LibreSpeed server
 //!
 //! # Configuration
 //!
 //! Key | Values | Default
 //! ----|--------|--------
-//! `format` | A string to customise the output of this block. See below for available placeholders. | `" ^icon_ping $ping ^icon_net_down $speed_down ^icon_net_up $speed_up "`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" ^icon_ping {$ping ^icon_net_down $speed_down ^icon_net_up $speed_up\|...} \"</code>
 //! `interval` | Update interval in seconds | `1800`
+//! `tool` | One of `"speedtest_cli"`, `"ookla"` or `"librespeed"` | `"speedtest_cli"`
+//! `server_list` | librespeed only: URL or path of a JSON list of LibreSpeed servers, passed as `--server-json` | `None`
+//!
+//! Placeholder  | Value                            | Type     | Unit
+//! -------------|----------------------------------|----------|---------------
+//! `ping`       | Ping delay                       | Number   | Seconds
+//! `speed_down` | Download speed                   | Number   | Bits per second
+//! `speed_up`   | Upload speed                     | Number   | Bits per second
+//! `time`       | When the last test finished      | Datetime | -
+//! `running`    | Present while a test is running  | Flag     | -
 //!
-//! Placeholder  | Value          | Type   | Unit
-//! -------------|----------------|--------|---------------
-//! `ping`       | Ping delay     | Number | Seconds
-//! `speed_down` | Download speed | Number | Bits per second
-//! `speed_up`   | Upload speed   | Number | Bits per second
+//! While a test is running, the results of the previous one are shown and the block is set to the
+//! info state. Until the first test has finished, only `running` is set.
+//!
+//! Action | Description       | Default button
+//! -------|-------------------|---------------
+//! `run`  | Run a test now    | Left
 //!
 //! # Example
 //!
@@ -35,56 +52,163 @@
 //! format = " $speed_down.eng(w:4,u:B) $speed_up(w:4,u:B) "
 //! ```
 //!
+//! Test against a self-hosted LibreSpeed server and show when the test ran
+//!
+//! ```toml
+//! [[block]]
+//! block = "speedtest"
+//! interval = 7200
+//! tool = "librespeed"
+//! server_list = "https://speed.example.com/servers.json"
+//! format = " ^icon_net_down $speed_down ^icon_net_up $speed_up ($time.datetime(f:'%H:%M')) "
+//! ```
+//!
 //! # Icons Used
 //! - `ping`
 //! - `net_down`
 //! - `net_up`
 
-use super::prelude::*;
+use chrono::{DateTime, Utc};
 use tokio::process::Command;
 
+use super::prelude::*;
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 pub struct Config {
     pub format: FormatConfig,
     #[default(1800.into())]
     pub interval: Seconds,
+    pub tool: Tool,
+    pub server_list: Option<String>,
 }
 
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Tool {
+    #[default]
+    SpeedtestCli,
+    Ookla,
+    Librespeed,
+}
+
+const DEFAULT_FORMAT: &str =
+    " ^icon_ping {$ping ^icon_net_down $speed_down ^icon_net_up $speed_up|...} ";
+
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
-    let format = config
-        .format
-        .with_default(" ^icon_ping $ping ^icon_net_down $speed_down ^icon_net_up $speed_up ")?;
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "run")])?;
 
-    let mut command = Command::new("speedtest-cli");
-    command.arg("--json");
+    let format = config.format.with_default(DEFAULT_FORMAT)?;
+
+    let mut timer = config.interval.timer();
+    let mut last: Option<(Measurement, DateTime<Utc>)> = None;
 
     loop {
-        let output = command
-            .output()
-            .await
-            .error("failed to run 'speedtest-cli'")?
-            .stdout;
-        let output =
-            std::str::from_utf8(&output).error("'speedtest-cli' produced non-UTF8 output")?;
-        let output: SpeedtestCliOutput =
-            serde_json::from_str(output).error("'speedtest-cli' produced wrong JSON")?;
-
-        let mut widget = Widget::new().with_format(format.clone());
-        widget.set_values(map! {
-            "ping" => Value::seconds(output.ping * 1e-3),
-            "speed_down" => Value::bits(output.download),
-            "speed_up" => Value::bits(output.upload),
-        });
-        api.set_widget(widget)?;
-
-        select! {
-            _ = sleep(config.interval.0) => (),
-            _ = api.wait_for_update_request() => (),
+        let set_widget = |running: bool, last: &Option<(Measurement, DateTime<Utc>)>| {
+            let mut widget = Widget::new().with_format(format.clone());
+            if running {
+                widget.state = State::Info;
+            }
+            if let Some((m, time)) = last {
+                widget.set_values(map! {
+                    "ping" => Value::seconds(m.ping * 1e-3),
+                    "speed_down" => Value::bits(m.download),
+                    "speed_up" => Value::bits(m.upload),
+                    "time" => Value::datetime(*time, None),
+                    [if running] "running" => Value::flag(),
+                });
+            } else if running {
+                widget.set_values(map! {
+                    "running" => Value::flag(),
+                });
+            }
+            api.set_widget(widget)
+        };
+
+        set_widget(true, &last)?;
+        last = Some((measure(config).await?, Utc::now()));
+        set_widget(false, &last)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "run" => {
+                        timer.reset();
+                        break;
+                    }
+                    _ => (),
+                }
+            }
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Measurement {
+    /// Ping time in ms
+    ping: f64,
+    /// Download speed in bits per second
+    download: f64,
+    /// Upload speed in bits per second
+    upload: f64,
+}
+
+async fn measure(config: &Config) -> Result<Measurement> {
+    let (program, mut command) = match config.tool {
+        Tool::SpeedtestCli => ("speedtest-cli", Command::new("speedtest-cli")),
+        Tool::Ookla => ("speedtest", Command::new("speedtest")),
+        Tool::Librespeed => ("librespeed-cli", Command::new("librespeed-cli")),
+    };
+    match config.tool {
+        Tool::SpeedtestCli | Tool::Librespeed => command.arg("--json"),
+        Tool::Ookla => command.args(["--format=json", "--accept-license", "--accept-gdpr"]),
+    };
+    if let (Tool::Librespeed, Some(server_list)) = (config.tool, &config.server_list) {
+        command.args(["--server-json", server_list]);
+    }
+
+    let output = command
+        .output()
+        .await
+        .or_error(|| format!("failed to run '{program}'"))?
+        .stdout;
+    let output = std::str::from_utf8(&output)
+        .or_error(|| format!("'{program}' produced non-UTF8 output"))?;
+    parse_output(config.tool, output).or_error(|| format!("'{program}' produced wrong JSON"))
+}
+
+fn parse_output(tool: Tool, output: &str) -> serde_json::Result<Measurement> {
+    Ok(match tool {
+        Tool::SpeedtestCli => {
+            let o: SpeedtestCliOutput = serde_json::from_str(output)?;
+            Measurement {
+                ping: o.ping,
+                download: o.download,
+                upload: o.upload,
+            }
+        }
+        Tool::Ookla => {
+            let o: OoklaOutput = serde_json::from_str(output)?;
+            Measurement {
+                ping: o.ping.latency,
+                download: o.download.bandwidth * 8.0,
+                upload: o.upload.bandwidth * 8.0,
+            }
+        }
+        Tool::Librespeed => {
+            let [o]: [LibrespeedOutput; 1] = serde_json::from_str(output)?;
+            Measurement {
+                ping: o.ping,
+                download: o.download * 1e6,
+                upload: o.upload * 1e6,
+            }
+        }
+    })
+}
+
 #[derive(Deserialize, Debug, Clone, Copy)]
 struct SpeedtestCliOutput {
     /// Download speed in bits per second
@@ -94,3 +218,79 @@ struct SpeedtestCliOutput {
     /// Ping time in ms
     ping: f64,
 }
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct OoklaOutput {
+    ping: OoklaPing,
+    download: OoklaBandwidth,
+    upload: OoklaBandwidth,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct OoklaPing {
+    /// Ping time in ms
+    latency: f64,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct OoklaBandwidth {
+    /// Speed in bytes per second
+    bandwidth: f64,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct LibrespeedOutput {
+    /// Ping time in ms
+    ping: f64,
+    /// Download speed in megabits per second
+    download: f64,
+    /// Upload speed in megabits per second
+    upload: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SharedConfig;
+
+    #[test]
+    fn test_default_format_while_running() {
+        let format = FormatConfig::default()
+            .with_default(DEFAULT_FORMAT)
+            .unwrap();
+        let values = map! {
+            "running" => Value::flag(),
+        };
+        let (full, _) = format.render(&values, &SharedConfig::default()).unwrap();
+        let text: String = full.iter().map(|f| f.text.as_str()).collect();
+        assert!(text.ends_with(" ... "));
+    }
+
+    #[test]
+    fn test_parse_output() {
+        let expected = Measurement {
+            ping: 12.5,
+            download: 80e6,
+            upload: 16e6,
+        };
+        assert_eq!(
+            parse_output(
+                Tool::Ookla,
+                r#"{"type":"result","ping":{"jitter":0.5,"latency":12.5},
+                    "download":{"bandwidth":10000000,"bytes":1},
+                    "upload":{"bandwidth":2000000,"bytes":1}}"#
+            )
+            .unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_output(
+                Tool::Librespeed,
+                r#"[{"timestamp":"2024-06-10T12:00:00Z","ping":12.5,"jitter":0.5,
+                     "upload":16,"download":80,"share":""}]"#
+            )
+            .unwrap(),
+            expected
+        );
+    }
+}
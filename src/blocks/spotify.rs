@@ -0,0 +1,393 @@
+//! The current track of Spotify
+//!
+//! This block shows the track playing in the Spotify desktop client, which it controls over
+//! MPRIS. It is hidden while Spotify isn't running. For players in general, see the
+//! [`music`](super::music) block.
+//!
+//! Optionally, the block can show whether the track is in your "Liked Songs" and add it to them,
+//! using the [Web API](https://developer.spotify.com/documentation/web-api). This requires an app
+//! created on the [dashboard](https://developer.spotify.com/dashboard) and a refresh token with the
+//! `user-library-read` and `user-library-modify` scopes, obtained with the authorization code
+//! flow. The refresh token must be passed using the `I3RS_SPOTIFY_REFRESH_TOKEN` environment
+//! variable or the `refresh_token` configuration option. Refresh tokens obtained with PKCE are not
+//! supported: they can only be used once, and the block can't save the new one across restarts.
+//!
+//! If the Web API can't be reached, `$liked` is absent until the next update.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$combo.str(max_w:25,rot_interval:0.5) \|}\"</code>
+//! `separator` | String to insert between artist and title | `" - "`
+//! `client_id` | The client id of the app, required to use the Web API | `None`
+//! `client_secret` | The client secret of the app, required to use the Web API | `None`
+//! `refresh_token` | A refresh token of the app | `None`
+//!
+//! Placeholder | Value                                                                  | Type | Unit
+//! ------------|------------------------------------------------------------------------|------|-----
+//! `icon`      | A static icon                                                          | Icon | -
+//! `title`     | The title of the current track                                         | Text | -
+//! `artist`    | The artists of the current track, separated by commas                  | Text | -
+//! `album`     | The album of the current track                                         | Text | -
+//! `combo`     | `$artist[separator]$title`, or only the title if there is no artist    | Text | -
+//! `playing`   | Present while playing                                                  | Flag | -
+//! `liked`     | Present if the track is in your Liked Songs (requires the Web API)     | Flag | -
+//!
+//! All placeholders except `icon` and `playing` are absent if not known, e.g. while an ad plays.
+//!
+//! Action         | Description                                                   | Default button
+//! ---------------|---------------------------------------------------------------|---------------
+//! `play_pause`   | Toggle playback                                               | Left
+//! `next`         | Play the next track                                           | Right
+//! `prev`         | Play the previous track                                       | -
+//! `toggle_liked` | Add the track to your Liked Songs or remove it (Web API only) | Middle
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "spotify"
+//! format = " $icon {$combo.str(max_w:25){ $liked ♥|}|} "
+//! client_id = "0123456789abcdef0123456789abcdef"
+//! client_secret = "fedcba9876543210fedcba9876543210"
+//! ```
+//!
+//! # Icons Used
+//! - `music`
+
+use std::time::Instant;
+
+use zbus::fdo::DBusProxy;
+use zbus::zvariant::{Array, ObjectPath, OwnedValue};
+
+use super::prelude::*;
+
+make_log_macro!(debug, "spotify");
+
+const SPOTIFY_BUS_NAME: &str = "org.mpris.MediaPlayer2.spotify";
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(" - ".into())]
+    pub separator: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub refresh_token: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "play_pause"),
+        (MouseButton::Right, None, "next"),
+        (MouseButton::Middle, None, "toggle_liked"),
+    ])?;
+
+    let format = config
+        .format
+        .with_default(" $icon {$combo.str(max_w:25,rot_interval:0.5) |}")?;
+
+    let mut web_api = match &config.client_id {
+        Some(client_id) => Some(WebApi {
+            client_id: client_id.clone(),
+            client_secret: config
+                .client_secret
+                .clone()
+                .error("`client_secret` is required to use the Web API")?,
+            refresh_token: config
+                .refresh_token
+                .clone()
+                .or_else(|| std::env::var("I3RS_SPOTIFY_REFRESH_TOKEN").ok())
+                .error("Spotify refresh token not found")?,
+            access_token: None,
+        }),
+        None => None,
+    };
+
+    let dbus_conn = new_dbus_connection().await?;
+    let dbus_proxy = DBusProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create DBusProxy")?;
+    let mut owner_changes = dbus_proxy
+        .receive_name_owner_changed_with_args(&[(0, SPOTIFY_BUS_NAME)])
+        .await
+        .error("Failed to monitor Spotify")?;
+
+    loop {
+        let running = dbus_proxy
+            .name_has_owner(SPOTIFY_BUS_NAME.try_into().unwrap())
+            .await
+            .error("Failed to check whether Spotify is running")?;
+        if !running {
+            api.hide()?;
+            owner_changes.next().await;
+            continue;
+        }
+
+        let player = PlayerProxy::new(&dbus_conn)
+            .await
+            .error("Failed to create PlayerProxy")?;
+        let mut metadata_changes = player.receive_metadata_changed().await;
+        let mut status_changes = player.receive_playback_status_changed().await;
+        // Whether the track with the given id is liked, to avoid asking on every update
+        let mut liked_cache: Option<(String, bool)> = None;
+
+        loop {
+            // Spotify may have been closed in the meantime
+            let (Ok(metadata), Ok(status)) =
+                (player.metadata().await, player.playback_status().await)
+            else {
+                break;
+            };
+            let track = Track::from_metadata(&metadata);
+
+            let liked = match (&mut web_api, &track.id) {
+                (Some(web_api), Some(id)) => match &liked_cache {
+                    Some((cached_id, liked)) if cached_id == id => Some(*liked),
+                    _ => match web_api.is_saved(id).await {
+                        Ok(liked) => {
+                            liked_cache = Some((id.clone(), liked));
+                            Some(liked)
+                        }
+                        Err(e) => {
+                            debug!("{e}");
+                            None
+                        }
+                    },
+                },
+                _ => None,
+            };
+            let playing = status == "Playing";
+
+            let mut widget = Widget::new().with_format(format.clone());
+            if playing {
+                widget.state = State::Info;
+            }
+            widget.set_values(map! {
+                "icon" => Value::icon("music"),
+                [if let Some(t) = &track.title] "title" => Value::text(t.clone()),
+                [if let Some(a) = &track.artist] "artist" => Value::text(a.clone()),
+                [if let Some(a) = &track.album] "album" => Value::text(a.clone()),
+                [if let Some(c) = track.combo(&config.separator)] "combo" => Value::text(c),
+                [if playing] "playing" => Value::flag(),
+                [if liked == Some(true)] "liked" => Value::flag(),
+            });
+            api.set_widget(widget)?;
+
+            select! {
+                _ = metadata_changes.next() => (),
+                _ = status_changes.next() => (),
+                _ = owner_changes.next() => break,
+                _ = api.wait_for_update_request() => liked_cache = None,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "play_pause" => {
+                        player.play_pause().await.error("Failed to toggle playback")?;
+                    }
+                    "next" => {
+                        player.next().await.error("Failed to play the next track")?;
+                    }
+                    "prev" => {
+                        player.previous().await.error("Failed to play the previous track")?;
+                    }
+                    "toggle_liked" => {
+                        if let (Some(web_api), Some(id), Some(liked)) = (&mut web_api, &track.id, liked) {
+                            match web_api.set_saved(id, !liked).await {
+                                Ok(()) => liked_cache = Some((id.clone(), !liked)),
+                                Err(e) => {
+                                    debug!("{e}");
+                                }
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Track {
+    /// The id used by the Web API, absent for ads and local files
+    id: Option<String>,
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+}
+
+impl Track {
+    fn from_metadata(metadata: &HashMap<String, OwnedValue>) -> Self {
+        let get_str = |key: &str| {
+            let value: &str = metadata.get(key)?.downcast_ref().ok()?;
+            (!value.is_empty()).then(|| value.to_owned())
+        };
+        let track_id = metadata.get("mpris:trackid").and_then(|id| {
+            let id = match id.downcast_ref::<ObjectPath>() {
+                Ok(path) => path.to_string(),
+                Err(_) => id.downcast_ref::<&str>().ok()?.to_owned(),
+            };
+            parse_track_id(&id).map(str::to_owned)
+        });
+        let artists = metadata
+            .get("xesam:artist")
+            .and_then(|a| a.downcast_ref::<&Array>().ok())
+            .map(|a| {
+                a.inner()
+                    .iter()
+                    .filter_map(|a| a.downcast_ref::<&str>().ok())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .filter(|a| !a.is_empty());
+        Self {
+            id: track_id,
+            title: get_str("xesam:title"),
+            artist: artists,
+            album: get_str("xesam:album"),
+        }
+    }
+
+    fn combo(&self, separator: &str) -> Option<String> {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => Some(format!("{artist}{separator}{title}")),
+            (None, Some(title)) => Some(title.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the id of a track from its MPRIS track id, which is either `/com/spotify/track/<id>` or
+/// `spotify:track:<id>` depending on the version of Spotify
+fn parse_track_id(track_id: &str) -> Option<&str> {
+    let id = track_id
+        .strip_prefix("/com/spotify/track/")
+        .or_else(|| track_id.strip_prefix("spotify:track:"))?;
+    (!id.is_empty()).then_some(id)
+}
+
+struct WebApi {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    /// The access token and when it expires
+    access_token: Option<(String, Instant)>,
+}
+
+impl WebApi {
+    async fn access_token(&mut self) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Response {
+            access_token: String,
+            expires_in: u64,
+            refresh_token: Option<String>,
+        }
+
+        if let Some((token, expires_at)) = &self.access_token {
+            if Instant::now() < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let response: Response = REQWEST_CLIENT
+            .post("https://accounts.spotify.com/api/token")
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", &self.refresh_token),
+            ])
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to refresh the access token")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+
+        // Spotify may issue a new refresh token, which replaces the old one
+        if let Some(refresh_token) = response.refresh_token {
+            self.refresh_token = refresh_token;
+        }
+        // Refresh the token a minute early, so that it doesn't expire during a request
+        let expires_at =
+            Instant::now() + Duration::from_secs(response.expires_in.saturating_sub(60));
+        self.access_token = Some((response.access_token.clone(), expires_at));
+        Ok(response.access_token)
+    }
+
+    async fn is_saved(&mut self, id: &str) -> Result<bool> {
+        let token = self.access_token().await?;
+        let saved: Vec<bool> = REQWEST_CLIENT
+            .get("https://api.spotify.com/v1/me/tracks/contains")
+            .query(&[("ids", id)])
+            .bearer_auth(token)
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to check whether the track is saved")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+        Ok(saved.first().copied().unwrap_or(false))
+    }
+
+    async fn set_saved(&mut self, id: &str, saved: bool) -> Result<()> {
+        let token = self.access_token().await?;
+        let method = if saved {
+            reqwest::Method::PUT
+        } else {
+            reqwest::Method::DELETE
+        };
+        REQWEST_CLIENT
+            .request(method, "https://api.spotify.com/v1/me/tracks")
+            .query(&[("ids", id)])
+            .bearer_auth(token)
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Failed to save the track")?;
+        Ok(())
+    }
+}
+
+#[zbus::proxy(
+    interface = "org.mpris.MediaPlayer2.Player",
+    default_service = "org.mpris.MediaPlayer2.spotify",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+trait Player {
+    fn play_pause(&self) -> zbus::Result<()>;
+    fn next(&self) -> zbus::Result<()>;
+    fn previous(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn metadata(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+    #[zbus(property)]
+    fn playback_status(&self) -> zbus::Result<String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_track_id() {
+        assert_eq!(
+            parse_track_id("/com/spotify/track/4uLU6hMCjMI75M1A2tKUQC"),
+            Some("4uLU6hMCjMI75M1A2tKUQC")
+        );
+        assert_eq!(
+            parse_track_id("spotify:track:4uLU6hMCjMI75M1A2tKUQC"),
+            Some("4uLU6hMCjMI75M1A2tKUQC")
+        );
+        assert_eq!(
+            parse_track_id("spotify:ad:000000012c603a6600000020316a17a1"),
+            None
+        );
+    }
+}
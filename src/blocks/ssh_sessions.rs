@@ -0,0 +1,99 @@
+//! The number of active remote SSH sessions
+//!
+//! This block uses systemd-logind to count the sessions opened by the SSH daemon, so it requires
+//! `sshd` to be configured with `UsePAM yes` (the default on most distributions). The block is
+//! updated as soon as a session is opened or closed.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count "`
+//! `hide_if_none` | Hide the block if there are no sessions | `false`
+//!
+//! Placeholder | Value                                                  | Type   | Unit
+//! ------------|--------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                          | Icon   | -
+//! `count`     | The number of SSH sessions                             | Number | -
+//! `users`     | A comma separated list of the logged in users          | Text   | -
+//! `hosts`     | A comma separated list of the remote hosts             | Text   | -
+//!
+//! The block is set to the warning state if there is at least one session.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "ssh_sessions"
+//! format = " $icon $count{ ($hosts)|} "
+//! hide_if_none = true
+//! ```
+//!
+//! # Icons Used
+//! - `ssh`
+
+use super::logind::{list_sessions, LoginManagerProxy, SessionInfo};
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    pub hide_if_none: bool,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $count ")?;
+
+    let dbus_conn = new_system_dbus_connection().await?;
+    let manager = LoginManagerProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create LoginManagerProxy")?;
+    let mut session_new = manager
+        .receive_session_new()
+        .await
+        .error("Couldn't create stream")?;
+    let mut session_removed = manager
+        .receive_session_removed()
+        .await
+        .error("Couldn't create stream")?;
+
+    loop {
+        let sessions: Vec<SessionInfo> = list_sessions(&manager)
+            .await?
+            .into_iter()
+            .filter(|s| s.remote && s.service == "sshd")
+            .collect();
+
+        if sessions.is_empty() && config.hide_if_none {
+            api.hide()?;
+        } else {
+            let mut users: Vec<&str> = sessions.iter().map(|s| s.user.as_str()).collect();
+            users.sort_unstable();
+            users.dedup();
+            let mut hosts: Vec<&str> = sessions.iter().map(|s| s.remote_host.as_str()).collect();
+            hosts.sort_unstable();
+            hosts.dedup();
+
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = if sessions.is_empty() {
+                State::Idle
+            } else {
+                State::Warning
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("ssh"),
+                "count" => Value::number(sessions.len()),
+                "users" => Value::text(users.join(", ")),
+                "hosts" => Value::text(hosts.join(", ")),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            _ = session_new.next() => (),
+            _ = session_removed.next() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
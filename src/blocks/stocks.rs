@@ -0,0 +1,184 @@
+//! Stock quotes
+//!
+//! This block shows quotes of stocks (or any other symbol supported by the provider). If more than
+//! one symbol is configured, the block rotates through them; the mouse wheel switches between
+//! them manually.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `service` | The configuration of a quote provider (see below). | `{ name = "yahoo" }`
+//! `symbols` | List of symbols, e.g. `["AAPL", "MSFT"]` | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $symbol $price.eng(w:5) $change.eng(w:3) "`
+//! `interval` | Update interval in seconds | `300`
+//! `rotation_interval` | Time after which the next symbol is shown, in seconds | `10`
+//!
+//! # Yahoo Finance Options
+//!
+//! Uses the unofficial Yahoo Finance API, which doesn't require an API key.
+//!
+//! Key | Values | Required | Default
+//! ----|--------|----------|--------
+//! `name` | `yahoo`. | Yes | None
+//!
+//! # Alpha Vantage Options
+//!
+//! Requires a (free) [API key](https://www.alphavantage.co/support/#api-key). The free tier only
+//! allows a few requests per day, with one request per symbol per update, so set `interval`
+//! accordingly. Alpha Vantage doesn't report whether the market is open, so `open` is never set.
+//!
+//! Key | Values | Required | Default
+//! ----|--------|----------|--------
+//! `name` | `alphavantage`. | Yes | None
+//! `api_key` | Your Alpha Vantage API key. Can also be provided using the `ALPHAVANTAGE_API_KEY` environment variable. | Yes | None
+//!
+//! # Available Format Keys
+//!
+//! Placeholder | Value                                                  | Type   | Unit
+//! ------------|--------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                          | Icon   | -
+//! `symbol`    | The symbol                                             | Text   | -
+//! `price`     | The last price                                         | Number | -
+//! `change`    | The change since the previous close                    | Number | %
+//! `currency`  | The currency of the price (absent if unknown)          | Text   | -
+//! `open`      | Present if the market is open                          | Flag   | -
+//!
+//! The block is set to the good state if the price went up since the previous close, to the
+//! warning state if it went down, and to the idle state while the market is closed.
+//!
+//! Action | Description                | Default button
+//! -------|----------------------------|---------------
+//! `next` | Show the next symbol       | Wheel Up
+//! `prev` | Show the previous symbol   | Wheel Down
+//!
+//! # Examples
+//!
+//! ```toml
+//! [[block]]
+//! block = "stocks"
+//! symbols = ["AAPL", "MSFT", "^GSPC"]
+//! format = " $icon $symbol $price.eng(w:5) $change.eng(w:3){$open| (closed)}"
+//! ```
+//!
+//! ```toml
+//! [[block]]
+//! block = "stocks"
+//! symbols = ["IBM"]
+//! interval = 3600
+//! [block.service]
+//! name = "alphavantage"
+//! api_key = "XXX"
+//! ```
+//!
+//! # Icons Used
+//! - `stocks`
+
+pub mod alpha_vantage;
+pub mod yahoo;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub service: StockService,
+    pub symbols: Vec<String>,
+    pub format: FormatConfig,
+    #[default(300.into())]
+    pub interval: Seconds,
+    #[default(10.into())]
+    pub rotation_interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(tag = "name", rename_all = "lowercase")]
+pub enum StockService {
+    #[default]
+    Yahoo,
+    AlphaVantage(alpha_vantage::Config),
+}
+
+#[async_trait]
+trait QuoteProvider {
+    async fn get_quote(&self, symbol: &str) -> Result<Quote>;
+}
+
+#[derive(Debug)]
+struct Quote {
+    price: f64,
+    change: f64,
+    currency: Option<String>,
+    market_open: Option<bool>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::WheelUp, None, "next"),
+        (MouseButton::WheelDown, None, "prev"),
+    ])?;
+
+    let format = config
+        .format
+        .with_default(" $icon $symbol $price.eng(w:5) $change.eng(w:3) ")?;
+
+    if config.symbols.is_empty() {
+        return Err(Error::new("`symbols` is empty"));
+    }
+
+    let provider: Box<dyn QuoteProvider + Send + Sync> = match &config.service {
+        StockService::Yahoo => Box::new(yahoo::Service),
+        StockService::AlphaVantage(service_config) => {
+            Box::new(alpha_vantage::Service::new(service_config)?)
+        }
+    };
+
+    let mut timer = config.interval.timer();
+    let mut rotation = config.rotation_interval.timer();
+    let mut index = 0;
+
+    loop {
+        let mut quotes = Vec::with_capacity(config.symbols.len());
+        for symbol in &config.symbols {
+            let fetch = || provider.get_quote(symbol);
+            quotes.push(fetch.retry(&ExponentialBuilder::default()).await?);
+        }
+
+        loop {
+            let symbol = &config.symbols[index];
+            let quote = &quotes[index];
+
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = match quote.market_open {
+                Some(false) => State::Idle,
+                _ if quote.change > 0.0 => State::Good,
+                _ if quote.change < 0.0 => State::Warning,
+                _ => State::Idle,
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("stocks"),
+                "symbol" => Value::text(symbol.clone()),
+                "price" => Value::number(quote.price),
+                "change" => Value::percents(quote.change),
+                [if let Some(currency) = &quote.currency] "currency" => Value::text(currency.clone()),
+                [if quote.market_open == Some(true)] "open" => Value::flag(),
+            });
+            api.set_widget(widget)?;
+
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                _ = rotation.tick() => index = (index + 1) % quotes.len(),
+                Some(action) = actions.recv() => {
+                    match action.as_ref() {
+                        "next" => index = (index + 1) % quotes.len(),
+                        "prev" => index = (index + quotes.len() - 1) % quotes.len(),
+                        _ => (),
+                    }
+                    rotation.reset();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use super::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    api_key: Option<String>,
+}
+
+pub(super) struct Service {
+    api_key: String,
+}
+
+impl Service {
+    pub(super) fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            api_key: config
+                .api_key
+                .clone()
+                .or_else(|| std::env::var("ALPHAVANTAGE_API_KEY").ok())
+                .error("Alpha Vantage API key not found")?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    #[serde(rename = "Global Quote")]
+    quote: Option<GlobalQuote>,
+    #[serde(rename = "Note")]
+    note: Option<String>,
+    #[serde(rename = "Information")]
+    information: Option<String>,
+    #[serde(rename = "Error Message")]
+    error_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GlobalQuote {
+    #[serde(rename = "05. price")]
+    price: String,
+    #[serde(rename = "10. change percent")]
+    change_percent: String,
+}
+
+#[async_trait]
+impl QuoteProvider for Service {
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        // https://www.alphavantage.co/documentation/#latestprice
+        let response: ApiResponse = REQWEST_CLIENT
+            .get("https://www.alphavantage.co/query")
+            .query(&[
+                ("function", "GLOBAL_QUOTE"),
+                ("symbol", symbol),
+                ("apikey", &self.api_key),
+            ])
+            .send()
+            .await
+            .error("Failed to send request")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+
+        // Errors, including hitting the rate limit, are reported with a successful status code
+        if let Some(message) = response
+            .error_message
+            .or(response.note)
+            .or(response.information)
+        {
+            return Err(Error::new(format!("API error: {message}")));
+        }
+        let quote = response
+            .quote
+            .or_error(|| format!("No quote for '{symbol}'"))?;
+
+        Ok(Quote {
+            price: quote.price.parse().error("Failed to parse price")?,
+            change: quote
+                .change_percent
+                .trim_end_matches('%')
+                .parse()
+                .error("Failed to parse change")?,
+            currency: None,
+            market_open: None,
+        })
+    }
+}
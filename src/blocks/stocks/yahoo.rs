@@ -0,0 +1,92 @@
+use super::*;
+
+pub(super) struct Service;
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    chart: Chart,
+}
+
+#[derive(Deserialize)]
+struct Chart {
+    result: Option<Vec<ChartResult>>,
+    error: Option<ApiError>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct ChartResult {
+    meta: Meta,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    currency: Option<String>,
+    regular_market_price: f64,
+    chart_previous_close: Option<f64>,
+    previous_close: Option<f64>,
+    current_trading_period: Option<TradingPeriods>,
+}
+
+#[derive(Deserialize)]
+struct TradingPeriods {
+    regular: TradingPeriod,
+}
+
+#[derive(Deserialize)]
+struct TradingPeriod {
+    start: i64,
+    end: i64,
+}
+
+#[async_trait]
+impl QuoteProvider for Service {
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        let response: ApiResponse = REQWEST_CLIENT
+            .get(format!(
+                "https://query1.finance.yahoo.com/v8/finance/chart/{symbol}"
+            ))
+            .query(&[("interval", "1d"), ("range", "1d")])
+            .send()
+            .await
+            .error("Failed to send request")?
+            .json()
+            .await
+            .error("Failed to parse JSON")?;
+
+        if let Some(error) = response.chart.error {
+            return Err(Error::new(format!("API error: {}", error.description)));
+        }
+        let meta = response
+            .chart
+            .result
+            .and_then(|r| r.into_iter().next())
+            .or_error(|| format!("No quote for '{symbol}'"))?
+            .meta;
+
+        let previous_close = meta
+            .previous_close
+            .or(meta.chart_previous_close)
+            .unwrap_or(meta.regular_market_price);
+        let change = if previous_close == 0.0 {
+            0.0
+        } else {
+            (meta.regular_market_price - previous_close) / previous_close * 100.0
+        };
+        let now = chrono::Utc::now().timestamp();
+
+        Ok(Quote {
+            price: meta.regular_market_price,
+            change,
+            currency: meta.currency,
+            market_open: meta
+                .current_trading_period
+                .map(|p| (p.regular.start..p.regular.end).contains(&now)),
+        })
+    }
+}
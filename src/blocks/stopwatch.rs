@@ -0,0 +1,106 @@
+//! Stopwatch
+//!
+//! A stopwatch controlled by mouse clicks. For a countdown, see the `tea_timer` block.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$hours:$minutes:$seconds \|}\"</code>
+//!
+//! Placeholder | Value                                 | Type   | Unit
+//! ------------|---------------------------------------|--------|-----
+//! `icon`      | A static icon                         | Icon   | -
+//! `hours`     | The hours elapsed                     | Text   | h
+//! `minutes`   | The minutes elapsed                   | Text   | mn
+//! `seconds`   | The seconds elapsed                   | Text   | s
+//! `paused`    | Present if the stopwatch is paused    | Flag   | -
+//!
+//! `hours`, `minutes`, and `seconds` are unset when the stopwatch has not been started or was
+//! reset. The block is in the info state while running and in the warning state while paused.
+//!
+//! Action   | Description                     | Default button
+//! ---------|---------------------------------|---------------
+//! `toggle` | Start or pause the stopwatch    | Left
+//! `reset`  | Stop and reset the stopwatch    | Right
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "stopwatch"
+//! format = " $icon {$minutes:$seconds{ (paused)|} |}"
+//! ```
+//!
+//! # Icons Used
+//! - `stopwatch`
+
+use super::prelude::*;
+use std::time::Instant;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "toggle"),
+        (MouseButton::Right, None, "reset"),
+    ])?;
+
+    let interval: Seconds = 1.into();
+    let mut timer = interval.timer();
+
+    let format = config
+        .format
+        .with_default(" $icon {$hours:$minutes:$seconds |}")?;
+
+    // Time elapsed before the stopwatch was paused the last time
+    let mut elapsed = Duration::ZERO;
+    // `None` while paused
+    let mut started: Option<Instant> = None;
+
+    loop {
+        let total = elapsed + started.map_or(Duration::ZERO, |s| s.elapsed());
+        let is_running = started.is_some();
+        let is_reset = !is_running && elapsed.is_zero();
+
+        let secs = total.as_secs();
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match (is_running, is_reset) {
+            (true, _) => State::Info,
+            (false, false) => State::Warning,
+            (false, true) => State::Idle,
+        };
+        widget.set_values(map!(
+            "icon" => Value::icon("stopwatch"),
+            [if !is_reset] "hours" => Value::text(format!("{:02}", secs / 3600)),
+            [if !is_reset] "minutes" => Value::text(format!("{:02}", secs / 60 % 60)),
+            [if !is_reset] "seconds" => Value::text(format!("{:02}", secs % 60)),
+            [if !is_running && !is_reset] "paused" => Value::flag(),
+        ));
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick(), if is_running => (),
+            _ = api.wait_for_update_request() => (),
+            Some(action) = actions.recv() => match action.as_ref() {
+                "toggle" => match started.take() {
+                    Some(started) => elapsed += started.elapsed(),
+                    None => {
+                        started = Some(Instant::now());
+                        timer.reset();
+                    }
+                },
+                "reset" => {
+                    started = None;
+                    elapsed = Duration::ZERO;
+                }
+                _ => (),
+            }
+        }
+    }
+}
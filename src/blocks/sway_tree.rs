@@ -0,0 +1,19 @@
+//! Helpers for walking the sway/i3 tree, shared by the `scratchpad` and `workspace_windows` blocks
+
+use swayipc_async::{Node, NodeType};
+
+/// Returns the windows in a container, that is its descendants which have no children
+pub(super) fn windows(node: &Node) -> Vec<&Node> {
+    let mut windows = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if node.nodes.is_empty()
+            && node.floating_nodes.is_empty()
+            && matches!(node.node_type, NodeType::Con | NodeType::FloatingCon)
+        {
+            windows.push(node);
+        }
+        stack.extend(node.nodes.iter().chain(&node.floating_nodes).rev());
+    }
+    windows
+}
@@ -0,0 +1,215 @@
+//! Next run of systemd timers
+//!
+//! This block shows which of the given systemd timers is due next and when, so that scheduled
+//! maintenance jobs such as backups are visible. It is set to the critical state if the last run
+//! of any of them failed, i.e. the service triggered by the timer is in the `failed` state.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `timers` | The names of the timers, e.g. `["fstrim", "backup.timer"]` | **Required**
+//! `user` | Whether the timers are user timers | `false`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon{ $next $next_at.datetime(f:'%a %R')\|} \"</code>
+//! `interval` | Update interval in seconds | `60`
+//!
+//! Placeholder    | Value                                                                 | Type     | Unit
+//! ---------------|-----------------------------------------------------------------------|----------|-----
+//! `icon`         | A static icon                                                         | Icon     | -
+//! `next`         | The name of the timer which is due next (absent if none is scheduled) | Text     | -
+//! `next_at`      | When that timer is due                                                | Datetime | -
+//! `failed`       | The number of timers whose last run failed                            | Number   | -
+//! `failed_names` | The names of the timers whose last run failed, separated by commas    | Text     | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "systemd_timers"
+//! timers = ["backup", "fstrim"]
+//! format = " $icon{ $next $next_at.datetime(f:'%a %R')|}{ ($failed_names failed)|} "
+//! ```
+//!
+//! # Icons Used
+//! - `time`
+
+use chrono::{DateTime, TimeDelta, Utc};
+use zbus::zvariant::OwnedObjectPath;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub timers: Vec<String>,
+    pub user: bool,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+struct TimerStatus {
+    name: String,
+    next: Option<DateTime<Utc>>,
+    failed: bool,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon{ $next $next_at.datetime(f:'%a %R')|} ")?;
+    let mut timer = config.interval.timer();
+
+    if config.timers.is_empty() {
+        return Err(Error::new("timers must not be empty"));
+    }
+
+    let dbus_conn = if config.user {
+        new_dbus_connection().await?
+    } else {
+        new_system_dbus_connection().await?
+    };
+    let manager = ManagerProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create ManagerProxy")?;
+
+    loop {
+        let mut timers = Vec::with_capacity(config.timers.len());
+        for name in &config.timers {
+            timers.push(timer_status(&dbus_conn, &manager, name).await?);
+        }
+
+        let next = timers
+            .iter()
+            .filter_map(|t| Some((t.name.as_str(), t.next?)))
+            .min_by_key(|(_, next)| *next);
+        let failed: Vec<&str> = timers
+            .iter()
+            .filter(|t| t.failed)
+            .map(|t| t.name.as_str())
+            .collect();
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if !failed.is_empty() {
+            widget.state = State::Critical;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon("time"),
+            [if let Some((name, _)) = next] "next" => Value::text(name.into()),
+            [if let Some((_, at)) = next] "next_at" => Value::datetime(at, None),
+            "failed" => Value::number(failed.len()),
+            [if !failed.is_empty()] "failed_names" => Value::text(failed.join(", ")),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn timer_status(
+    dbus_conn: &zbus::Connection,
+    manager: &ManagerProxy<'_>,
+    name: &str,
+) -> Result<TimerStatus> {
+    let name = name.strip_suffix(".timer").unwrap_or(name);
+    let unit = format!("{name}.timer");
+
+    let path = manager
+        .load_unit(&unit)
+        .await
+        .or_error(|| format!("Failed to load {unit}"))?;
+    let timer = TimerProxy::builder(dbus_conn)
+        .path(path)
+        .error("Could not set path")?
+        .cache_properties(zbus::CacheProperties::No)
+        .build()
+        .await
+        .error("Failed to create TimerProxy")?;
+
+    // Both are 0 if the timer has no elapse point of that kind
+    let realtime = timer
+        .next_elapse_usec_realtime()
+        .await
+        .or_error(|| format!("Failed to get the next elapse of {unit}"))?;
+    let monotonic = timer
+        .next_elapse_usec_monotonic()
+        .await
+        .or_error(|| format!("Failed to get the next elapse of {unit}"))?;
+    let realtime = (realtime > 0)
+        .then(|| DateTime::from_timestamp_micros(realtime as i64))
+        .flatten();
+    let monotonic = (monotonic > 0).then(|| monotonic_to_realtime(monotonic));
+    let next = realtime.into_iter().chain(monotonic).min();
+
+    let service = timer
+        .unit()
+        .await
+        .or_error(|| format!("Failed to get the unit of {unit}"))?;
+    let service_path = manager
+        .load_unit(&service)
+        .await
+        .or_error(|| format!("Failed to load {service}"))?;
+    let failed = UnitProxy::builder(dbus_conn)
+        .path(service_path)
+        .error("Could not set path")?
+        .cache_properties(zbus::CacheProperties::No)
+        .build()
+        .await
+        .error("Failed to create UnitProxy")?
+        .active_state()
+        .await
+        .or_error(|| format!("Failed to get the state of {service}"))?
+        == "failed";
+
+    Ok(TimerStatus {
+        name: name.into(),
+        next,
+        failed,
+    })
+}
+
+/// Converts a `CLOCK_MONOTONIC` timestamp in microseconds to wall clock time
+fn monotonic_to_realtime(usec: u64) -> DateTime<Utc> {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // Safety: `now` is a valid timespec
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+    let now = now.tv_sec * 1_000_000 + now.tv_nsec / 1_000;
+    Utc::now() + TimeDelta::microseconds(usec as i64 - now)
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait Manager {
+    fn load_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.systemd1.Timer",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait Timer {
+    #[zbus(property, name = "NextElapseUSecRealtime")]
+    fn next_elapse_usec_realtime(&self) -> zbus::Result<u64>;
+    #[zbus(property, name = "NextElapseUSecMonotonic")]
+    fn next_elapse_usec_monotonic(&self) -> zbus::Result<u64>;
+    #[zbus(property)]
+    fn unit(&self) -> zbus::Result<String>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.systemd1.Unit",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait Unit {
+    #[zbus(property)]
+    fn active_state(&self) -> zbus::Result<String>;
+}
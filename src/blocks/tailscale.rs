@@ -0,0 +1,257 @@
+//! Tailscale status
+//!
+//! This block shows the state of [Tailscale](https://tailscale.com/), the MagicDNS name of this
+//! machine and the exit node in use, as reported by `tailscale status --json`. Clicking the block
+//! brings Tailscale up or down and the mouse wheel switches between the available exit nodes.
+//!
+//! Switching exit nodes and bringing Tailscale up or down requires the current user to be the
+//! operator of the Tailscale daemon, see `tailscale set --operator`.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon{ $exit_node\|} \"</code>
+//! `interval` | Update interval in seconds | `10`
+//!
+//! Placeholder | Value                                                              | Type | Unit
+//! ------------|--------------------------------------------------------------------|------|-----
+//! `icon`      | `net_vpn` while running, `net_down` otherwise                      | Icon | -
+//! `state`     | The backend state, e.g. `Running`, `Stopped` or `NeedsLogin`       | Text | -
+//! `dns_name`  | The MagicDNS name of this machine                                  | Text | -
+//! `ip`        | The first Tailscale IP address of this machine (absent if none)    | Text | -
+//! `tailnet`   | The name of the tailnet (absent if unknown)                        | Text | -
+//! `exit_node` | The host name of the exit node in use (absent if none)             | Text | -
+//!
+//! The block is set to the good state while Tailscale is running and to the warning state if it
+//! needs attention, e.g. because a login is required.
+//!
+//! Action           | Description                                           | Default button
+//! -----------------|-------------------------------------------------------|---------------
+//! `toggle`         | Run `tailscale up` or `tailscale down`                | Left
+//! `next_exit_node` | Use the next available exit node (or none)            | Wheel Up
+//! `prev_exit_node` | Use the previous available exit node (or none)        | Wheel Down
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "tailscale"
+//! format = " $icon $dns_name.str(max_w:20){ via $exit_node|} "
+//! ```
+//!
+//! # Icons Used
+//! - `net_vpn`
+//! - `net_down`
+
+use tokio::process::Command;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Status {
+    backend_state: String,
+    #[serde(rename = "Self")]
+    self_: Option<Peer>,
+    #[serde(default)]
+    peer: Option<HashMap<String, Peer>>,
+    current_tailnet: Option<Tailnet>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Peer {
+    #[serde(default)]
+    host_name: String,
+    #[serde(default, rename = "DNSName")]
+    dns_name: String,
+    #[serde(default, rename = "TailscaleIPs")]
+    tailscale_ips: Option<Vec<String>>,
+    #[serde(default)]
+    exit_node: bool,
+    #[serde(default)]
+    exit_node_option: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Tailnet {
+    name: String,
+}
+
+impl Status {
+    fn peers(&self) -> impl Iterator<Item = &Peer> {
+        self.peer.iter().flat_map(|p| p.values())
+    }
+
+    fn exit_node(&self) -> Option<&Peer> {
+        self.peers().find(|p| p.exit_node)
+    }
+
+    /// Host names of the peers that can be used as exit nodes, sorted by name
+    fn exit_node_options(&self) -> Vec<&str> {
+        let mut options: Vec<&str> = self
+            .peers()
+            .filter(|p| p.exit_node_option)
+            .map(|p| p.host_name.as_str())
+            .collect();
+        options.sort_unstable();
+        options
+    }
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "toggle"),
+        (MouseButton::WheelUp, None, "next_exit_node"),
+        (MouseButton::WheelDown, None, "prev_exit_node"),
+    ])?;
+
+    let format = config.format.with_default(" $icon{ $exit_node|} ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let status = get_status().await?;
+        let running = status.backend_state == "Running";
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match status.backend_state.as_str() {
+            "Running" => State::Good,
+            "Stopped" => State::Idle,
+            _ => State::Warning,
+        };
+        let dns_name = status
+            .self_
+            .as_ref()
+            .map(|s| s.dns_name.trim_end_matches('.').to_owned())
+            .unwrap_or_default();
+        let ip = status
+            .self_
+            .as_ref()
+            .and_then(|s| s.tailscale_ips.as_ref()?.first().cloned());
+        widget.set_values(map! {
+            "icon" => Value::icon(if running { "net_vpn" } else { "net_down" }),
+            "state" => Value::text(status.backend_state.clone()),
+            "dns_name" => Value::text(dns_name),
+            [if let Some(ip) = ip] "ip" => Value::text(ip),
+            [if let Some(t) = &status.current_tailnet] "tailnet" => Value::text(t.name.clone()),
+            [if let Some(e) = status.exit_node()] "exit_node" => Value::text(e.host_name.clone()),
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => {
+                    let offset = match action.as_ref() {
+                        "toggle" => {
+                            tailscale(&[if running { "down" } else { "up" }]).await?;
+                            break;
+                        }
+                        "next_exit_node" => 1,
+                        "prev_exit_node" => -1,
+                        _ => continue,
+                    };
+                    let options = status.exit_node_options();
+                    let current = status.exit_node().map(|p| p.host_name.as_str());
+                    let exit_node = cycle_exit_node(&options, current, offset);
+                    tailscale(&["set", &format!("--exit-node={}", exit_node.unwrap_or(""))]).await?;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the exit node `offset` positions away from `current`, where `None` (no exit node) is
+/// treated as an additional position before the first option
+fn cycle_exit_node<'a>(
+    options: &[&'a str],
+    current: Option<&str>,
+    offset: isize,
+) -> Option<&'a str> {
+    let len = options.len() as isize + 1;
+    let position = current
+        .and_then(|c| options.iter().position(|o| *o == c))
+        .map_or(0, |i| i as isize + 1);
+    let new = (position + offset).rem_euclid(len);
+    (new != 0).then(|| options[new as usize - 1])
+}
+
+async fn get_status() -> Result<Status> {
+    let output = Command::new("tailscale")
+        .args(["status", "--json"])
+        .output()
+        .await
+        .error("Failed to run tailscale")?;
+    serde_json::from_slice(&output.stdout).map_err(|_| {
+        Error::new(format!(
+            "tailscale status failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    })
+}
+
+async fn tailscale(args: &[&str]) -> Result<()> {
+    let output = Command::new("tailscale")
+        .args(args)
+        .output()
+        .await
+        .error("Failed to run tailscale")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "tailscale {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let status: Status = serde_json::from_str(
+            r#"{
+                "BackendState": "Running",
+                "Self": {
+                    "HostName": "laptop",
+                    "DNSName": "laptop.tail1234.ts.net.",
+                    "TailscaleIPs": ["100.64.0.1", "fd7a:115c:a1e0::1"]
+                },
+                "Peer": {
+                    "nodekey:1": { "HostName": "nas", "ExitNodeOption": false },
+                    "nodekey:2": { "HostName": "vps-fra", "ExitNode": true, "ExitNodeOption": true },
+                    "nodekey:3": { "HostName": "vps-ams", "ExitNodeOption": true }
+                },
+                "CurrentTailnet": { "Name": "example.com" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(status.exit_node().unwrap().host_name, "vps-fra");
+
+        let options = status.exit_node_options();
+        assert_eq!(options, ["vps-ams", "vps-fra"]);
+        assert_eq!(cycle_exit_node(&options, Some("vps-fra"), 1), None);
+        assert_eq!(
+            cycle_exit_node(&options, Some("vps-fra"), -1),
+            Some("vps-ams")
+        );
+        assert_eq!(cycle_exit_node(&options, None, 1), Some("vps-ams"));
+        assert_eq!(cycle_exit_node(&options, None, -1), Some("vps-fra"));
+    }
+}
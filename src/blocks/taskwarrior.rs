@@ -20,6 +20,11 @@
 //! `icon`        | A static icon                               | Icon   | -
 //! `count`       | The number of tasks matching current filter | Number | -
 //! `filter_name` | The name of current filter                  | Text   | -
+//! `description` | The most urgent matching task's description | Text   | -
+//! `urgency`     | The most urgent matching task's urgency     | Number | -
+//!
+//! `description` and `urgency` are absent if no task matches current filter. The most urgent task
+//! is only queried if `description` or `urgency` is used in the format.
 //!
 //! Action        | Default button
 //! --------------|---------------
@@ -46,6 +51,20 @@
 //! filter = "project:some-project +PENDING"
 //! ```
 //!
+//! Show the most urgent pending task:
+//!
+//! ```toml
+//! [[block]]
+//! block = "taskwarrior"
+//! format = " $icon $count.eng(w:1){ $description.str(max_w:25,rot_interval:0.5)|} "
+//! [[block.filters]]
+//! name = "pending"
+//! filter = "+PENDING"
+//! [[block.filters]]
+//! name = "next"
+//! filter = "+PENDING +next"
+//! ```
+//!
 //! # Icons Used
 //! - `tasks`
 
@@ -108,8 +127,17 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         .into_event_stream([0; 1024])
         .error("Failed to create event stream")?;
 
+    let need_top_task = [&format, &format_singular, &format_everything_done]
+        .iter()
+        .any(|f| f.contains_key("description") || f.contains_key("urgency"));
+
     loop {
         let number_of_tasks = get_number_of_tasks(&filter.filter).await?;
+        let top_task = if need_top_task {
+            get_most_urgent_task(&filter.filter).await?
+        } else {
+            None
+        };
 
         let mut widget = Widget::new();
 
@@ -123,6 +151,8 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             "icon" => Value::icon("tasks"),
             "count" => Value::number(number_of_tasks),
             "filter_name" => Value::text(filter.name.clone()),
+            [if let Some(task) = &top_task] "description" => Value::text(task.description.clone()),
+            [if let Some(task) = &top_task] "urgency" => Value::number(task.urgency),
         });
 
         widget.state = match number_of_tasks {
@@ -161,6 +191,27 @@ async fn get_number_of_tasks(filter: &str) -> Result<u32> {
         .error("could not parse the result of taskwarrior")
 }
 
+#[derive(Deserialize, Debug)]
+struct Task {
+    description: String,
+    #[serde(default)]
+    urgency: f64,
+}
+
+async fn get_most_urgent_task(filter: &str) -> Result<Option<Task>> {
+    let output = Command::new("task")
+        .args(["rc.gc=off", "rc.json.array=on", filter, "export"])
+        .output()
+        .await
+        .error("failed to run taskwarrior for exporting tasks")?
+        .stdout;
+    let tasks: Vec<Task> = serde_json::from_slice(&output)
+        .error("could not parse the tasks exported by taskwarrior")?;
+    Ok(tasks
+        .into_iter()
+        .max_by(|a, b| a.urgency.total_cmp(&b.urgency)))
+}
+
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Filter {
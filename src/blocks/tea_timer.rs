@@ -1,5 +1,8 @@
 //! Timer
 //!
+//! If `presets` are configured, the mouse wheel selects a preset and a left click starts the timer
+//! with its duration, instead of changing the remaining time.
+//!
 //! # Configuration
 //!
 //! Key | Values | Default
@@ -7,6 +10,7 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$minutes:$seconds \|}\"</code>
 //! `increment` | The numbers of seconds to add each time the block is clicked. | 30
 //! `done_cmd` | A command to run in `sh` when timer finishes. | None
+//! `presets` | A list of tables with the keys `name` and `duration` (in seconds). | `[]`
 //!
 //! Placeholder      | Value                                                          | Type   | Unit
 //! -----------------|----------------------------------------------------------------|--------|---------------
@@ -14,14 +18,23 @@
 //! `hours`          | The hours remaining on the timer                               | Text   | h
 //! `minutes`        | The minutes remaining on the timer                             | Text   | mn
 //! `seconds`        | The seconds remaining on the timer                             | Text   | s
+//! `time`           | The time remaining on the timer                                | Number | s
+//! `preset`         | The name of the selected preset                                | Text   | -
+//!
+//! `hours`, `minutes`, `seconds` and `time` are unset when the timer is inactive. `preset` is unset
+//! if no presets are configured.
 //!
-//! `hours`, `minutes`, and `seconds` are unset when the timer is inactive.
+//! When the timer reaches zero, `done_cmd` is run and the block is set to the critical state until
+//! it is clicked.
 //!
-//! Action      | Default button
-//! ------------|---------------
-//! `increment` | Left / Wheel Up
-//! `decrement` | Wheel Down
-//! `reset`     | Right
+//! Action        | Default button
+//! --------------|---------------
+//! `increment`   | Left / Wheel Up (without presets)
+//! `decrement`   | Wheel Down (without presets)
+//! `start`       | Left (with presets)
+//! `next_preset` | Wheel Up (with presets)
+//! `prev_preset` | Wheel Down (with presets)
+//! `reset`       | Right
 //!
 //! # Example
 //!
@@ -32,6 +45,33 @@
 //! done_cmd = "notify-send 'Timer Finished'"
 //! ```
 //!
+//! A kitchen timer, set in steps of one minute:
+//!
+//! ```toml
+//! [[block]]
+//! block = "tea_timer"
+//! increment = 60
+//! done_cmd = "notify-send -u critical 'Time is up'"
+//! ```
+//!
+//! Presets for different kinds of tea:
+//!
+//! ```toml
+//! [[block]]
+//! block = "tea_timer"
+//! format = " $icon {$minutes:$seconds|$preset} "
+//! done_cmd = "notify-send 'Tea is ready'"
+//! [[block.presets]]
+//! name = "green"
+//! duration = 120
+//! [[block.presets]]
+//! name = "black"
+//! duration = 240
+//! [[block.presets]]
+//! name = "herbal"
+//! duration = 420
+//! ```
+//!
 //! # Icons Used
 //! - `tea`
 
@@ -45,16 +85,33 @@ pub struct Config {
     pub format: FormatConfig,
     pub increment: Option<i64>,
     pub done_cmd: Option<String>,
+    pub presets: Vec<Preset>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    pub name: String,
+    pub duration: Seconds<false>,
 }
 
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let mut actions = api.get_actions()?;
-    api.set_default_actions(&[
-        (MouseButton::Left, None, "increment"),
-        (MouseButton::WheelUp, None, "increment"),
-        (MouseButton::WheelDown, None, "decrement"),
-        (MouseButton::Right, None, "reset"),
-    ])?;
+    if config.presets.is_empty() {
+        api.set_default_actions(&[
+            (MouseButton::Left, None, "increment"),
+            (MouseButton::WheelUp, None, "increment"),
+            (MouseButton::WheelDown, None, "decrement"),
+            (MouseButton::Right, None, "reset"),
+        ])?;
+    } else {
+        api.set_default_actions(&[
+            (MouseButton::Left, None, "start"),
+            (MouseButton::WheelUp, None, "next_preset"),
+            (MouseButton::WheelDown, None, "prev_preset"),
+            (MouseButton::Right, None, "reset"),
+        ])?;
+    }
 
     let interval: Seconds = 1.into();
     let mut timer = interval.timer();
@@ -64,14 +121,17 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let increment =
         Duration::try_seconds(config.increment.unwrap_or(30)).error("invalid increment value")?;
     let mut timer_end = Utc::now();
+    let mut preset = 0;
 
     let mut timer_was_active = false;
+    let mut finished = false;
 
     loop {
         let remaining_time = timer_end - Utc::now();
         let is_timer_active = remaining_time > Duration::zero();
 
         if !is_timer_active && timer_was_active {
+            finished = true;
             if let Some(cmd) = &config.done_cmd {
                 spawn_shell(cmd).error("done_cmd error")?;
             }
@@ -89,12 +149,17 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         };
 
         let mut widget = Widget::new().with_format(format.clone());
+        if finished {
+            widget.state = State::Critical;
+        }
 
         widget.set_values(map!(
             "icon" => Value::icon("tea"),
             [if is_timer_active] "hours" => Value::text(format!("{hours:02}")),
             [if is_timer_active] "minutes" => Value::text(format!("{minutes:02}")),
             [if is_timer_active] "seconds" => Value::text(format!("{seconds:02}")),
+            [if is_timer_active] "time" => Value::seconds(remaining_time.num_seconds()),
+            [if let Some(preset) = config.presets.get(preset)] "preset" => Value::text(preset.name.clone()),
         ));
 
         api.set_widget(widget)?;
@@ -103,12 +168,26 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             _ = timer.tick(), if is_timer_active => (),
             _ = api.wait_for_update_request() => (),
             Some(action) = actions.recv() => {
+                finished = false;
                 let now = Utc::now();
                 match action.as_ref() {
                     "increment" if is_timer_active => timer_end += increment,
                     "increment" => timer_end = now + increment,
                     "decrement" if is_timer_active => timer_end -= increment,
                     "reset" => timer_end = now,
+                    "start" => {
+                        if let Some(preset) = config.presets.get(preset) {
+                            timer_end = now
+                                + Duration::from_std(preset.duration.0)
+                                    .error("invalid preset duration")?;
+                        }
+                    }
+                    "next_preset" if !config.presets.is_empty() => {
+                        preset = (preset + 1) % config.presets.len();
+                    }
+                    "prev_preset" if !config.presets.is_empty() => {
+                        preset = (preset + config.presets.len() - 1) % config.presets.len();
+                    }
                     _ => (),
                 }
             }
@@ -0,0 +1,155 @@
+//! Thermal throttling indicator
+//!
+//! This block shows whether the CPU is being throttled to keep its temperature down, and is set to
+//! the critical state while it is. The CPU is considered throttled when
+//!
+//! - the `thermal_throttle` counters of any CPU in sysfs increased since the last update, or
+//! - any thermal zone reached one of its `passive` trip points, above which the kernel reduces the
+//!   CPU frequency.
+//!
+//! The throttle counters are provided by the `therm_throt` driver on Intel CPUs. Reading the
+//! counters directly from the MSRs would require root, so they are not used.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon{$throttling throttled\|} "`
+//! `interval` | Update interval in seconds | `5`
+//!
+//! Placeholder  | Value                                                                      | Type   | Unit
+//! -------------|----------------------------------------------------------------------------|--------|--------
+//! `icon`       | A static icon                                                              | Icon   | -
+//! `throttling` | Present while the CPU is throttled                                         | Flag   | -
+//! `cpus`       | The number of CPUs whose throttle counters increased since the last update | Number | -
+//! `zone`       | The type of the thermal zone above its passive trip point (absent if none) | Text   | -
+//! `temp`       | The temperature of that thermal zone                                       | Number | Degrees
+//!
+//! # Example
+//!
+//! Only show the block while the CPU is throttled:
+//!
+//! ```toml
+//! [[block]]
+//! block = "throttling"
+//! format = "{ $icon $zone $temp |}"
+//! interval = 2
+//! ```
+//!
+//! # Icons Used
+//! - `thermometer`
+
+use std::path::Path;
+
+use super::prelude::*;
+use crate::util::read_file;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon{$throttling throttled|} ")?;
+    let mut timer = config.interval.timer();
+
+    let mut prev_counts = throttle_counts().await;
+
+    loop {
+        let counts = throttle_counts().await;
+        let cpus = counts
+            .iter()
+            .filter(|(cpu, count)| prev_counts.get(*cpu).is_some_and(|prev| *count > prev))
+            .count();
+        prev_counts = counts;
+        let zone = throttling_zone().await;
+        let throttling = cpus > 0 || zone.is_some();
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if throttling {
+            widget.state = State::Critical;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon("thermometer"),
+            [if throttling] "throttling" => Value::flag(),
+            "cpus" => Value::number(cpus),
+            [if let Some((name, _)) = &zone] "zone" => Value::text(name.clone()),
+            [if let Some((_, temp)) = zone] "temp" => Value::degrees(temp),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+/// Returns the sum of the core and package throttle counters of each CPU
+async fn throttle_counts() -> HashMap<String, u64> {
+    let mut counts = HashMap::new();
+    let Ok(mut dir) = tokio::fs::read_dir("/sys/devices/system/cpu").await else {
+        return counts;
+    };
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name
+            .strip_prefix("cpu")
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        {
+            continue;
+        }
+        let path = entry.path().join("thermal_throttle");
+        let core = read_number::<u64>(path.join("core_throttle_count")).await;
+        let package = read_number::<u64>(path.join("package_throttle_count")).await;
+        if core.is_some() || package.is_some() {
+            counts.insert(name, core.unwrap_or(0) + package.unwrap_or(0));
+        }
+    }
+    counts
+}
+
+/// Returns the type and the temperature of the first thermal zone which reached a passive trip
+/// point
+async fn throttling_zone() -> Option<(String, f64)> {
+    let mut dir = tokio::fs::read_dir("/sys/class/thermal").await.ok()?;
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with("thermal_zone")
+        {
+            continue;
+        }
+        let path = entry.path();
+        let Some(temp) = read_number::<i64>(path.join("temp")).await else {
+            continue;
+        };
+        for i in 0.. {
+            let Ok(kind) = read_file(path.join(format!("trip_point_{i}_type"))).await else {
+                break;
+            };
+            if kind != "passive" {
+                continue;
+            }
+            let trip = read_number::<i64>(path.join(format!("trip_point_{i}_temp"))).await;
+            // Disabled trip points have a temperature of 0 or less
+            if trip.is_some_and(|trip| trip > 0 && temp >= trip) {
+                let name = read_file(path.join("type"))
+                    .await
+                    .unwrap_or_else(|_| entry.file_name().to_string_lossy().into_owned());
+                return Some((name, temp as f64 / 1000.0));
+            }
+        }
+    }
+    None
+}
+
+async fn read_number<T: std::str::FromStr>(path: impl AsRef<Path>) -> Option<T> {
+    read_file(path).await.ok()?.parse().ok()
+}
@@ -0,0 +1,179 @@
+//! TLP power mode
+//!
+//! This block shows which mode [TLP](https://linrunner.de/tlp/) applied its settings for (AC or
+//! battery), whether that mode was forced with `tlp ac` or `tlp bat`, the charge thresholds of the
+//! battery and whether USB autosuspend is enabled. The state is read from the files TLP writes to
+//! `/run/tlp` and from sysfs, so `tlp-stat` (which requires root) is not needed.
+//!
+//! Forcing a mode requires root. Set `use_sudo` to run `tlp` with `sudo -n`, which requires a
+//! matching `sudoers` entry.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `battery` | The battery to read the charge thresholds of | `"BAT0"`
+//! `use_sudo` | Whether to run `tlp` with `sudo -n` | `false`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon $mode{ $start-$end\|} \"</code>
+//! `interval` | Update interval in seconds | `10`
+//!
+//! Placeholder       | Value                                                                 | Type   | Unit
+//! ------------------|-----------------------------------------------------------------------|--------|-----
+//! `icon`            | `bat_charging` in AC mode, `bat` otherwise                            | Icon   | -
+//! `mode`            | `AC` or `BAT`                                                         | Text   | -
+//! `manual`          | Present if the mode was forced                                        | Flag   | -
+//! `start`           | The charge start threshold (absent if not supported)                  | Number | %
+//! `end`             | The charge stop threshold (absent if not supported)                   | Number | %
+//! `usb_autosuspend` | Present if USB autosuspend is enabled for any device other than a hub | Flag   | -
+//!
+//! The block is set to the info state while a mode is forced, and hidden if TLP is not running.
+//!
+//! Action   | Description                                              | Default button
+//! ---------|----------------------------------------------------------|---------------
+//! `toggle` | Force the other mode (`tlp ac` or `tlp bat`)             | Left
+//! `auto`   | Go back to choosing the mode automatically (`tlp start`) | Right
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "tlp"
+//! format = " $icon $mode{$manual (forced)|}{$usb_autosuspend usb|} "
+//! use_sudo = true
+//! ```
+//!
+//! # Icons Used
+//! - `bat`
+//! - `bat_charging`
+
+use super::prelude::*;
+use crate::util::{command_maybe_sudo, read_file};
+
+const LAST_PWR_PATH: &str = "/run/tlp/last_pwr";
+const MANUAL_MODE_PATH: &str = "/run/tlp/manual_mode";
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default("BAT0".into())]
+    pub battery: String,
+    pub use_sudo: bool,
+    pub format: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "toggle"),
+        (MouseButton::Right, None, "auto"),
+    ])?;
+
+    let format = config.format.with_default(" $icon $mode{ $start-$end|} ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        // TLP writes the mode it applied when it starts and when the power source changes
+        let mode = read_file(LAST_PWR_PATH)
+            .await
+            .ok()
+            .and_then(|s| parse_mode(&s));
+        let manual = read_file(MANUAL_MODE_PATH).await.is_ok();
+
+        match mode {
+            None => api.hide()?,
+            Some(mode) => {
+                let threshold = |name: &'static str| async move {
+                    read_file(format!(
+                        "/sys/class/power_supply/{}/charge_control_{name}_threshold",
+                        config.battery
+                    ))
+                    .await
+                    .ok()
+                    .and_then(|s| s.parse::<f64>().ok())
+                };
+                let start = threshold("start").await;
+                let end = threshold("end").await;
+                let usb_autosuspend = usb_autosuspend().await;
+
+                let mut widget = Widget::new().with_format(format.clone());
+                if manual {
+                    widget.state = State::Info;
+                }
+                widget.set_values(map! {
+                    "icon" => Value::icon(if mode == "AC" { "bat_charging" } else { "bat" }),
+                    "mode" => Value::text(mode.into()),
+                    [if manual] "manual" => Value::flag(),
+                    [if let Some(s) = start] "start" => Value::percents(s),
+                    [if let Some(e) = end] "end" => Value::percents(e),
+                    [if usb_autosuspend] "usb_autosuspend" => Value::flag(),
+                });
+                api.set_widget(widget)?;
+            }
+        }
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "toggle" => {
+                        tlp(config, if mode == Some("AC") { "bat" } else { "ac" }).await?;
+                        break;
+                    }
+                    "auto" => {
+                        tlp(config, "start").await?;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Parses the contents of `/run/tlp/last_pwr`
+fn parse_mode(last_pwr: &str) -> Option<&'static str> {
+    match last_pwr.split_whitespace().next()? {
+        "0" => Some("AC"),
+        "1" => Some("BAT"),
+        _ => None,
+    }
+}
+
+/// Whether runtime power management is enabled for any USB device. Hubs are skipped, since the
+/// kernel enables it for them by default.
+async fn usb_autosuspend() -> bool {
+    let Ok(mut dir) = tokio::fs::read_dir("/sys/bus/usb/devices").await else {
+        return false;
+    };
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        let path = entry.path();
+        if read_file(path.join("bDeviceClass"))
+            .await
+            .is_ok_and(|class| class != "09")
+            && read_file(path.join("power/control"))
+                .await
+                .is_ok_and(|control| control == "auto")
+        {
+            return true;
+        }
+    }
+    false
+}
+
+async fn tlp(config: &Config, arg: &str) -> Result<()> {
+    let output = command_maybe_sudo("tlp", config.use_sudo)
+        .arg(arg)
+        .output()
+        .await
+        .error("Failed to run tlp")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "tlp {arg} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! The running Toggl Track time entry
+//!
+//! This block shows the description and duration of the running [Toggl Track](https://toggl.com/track/)
+//! time entry. An API token, which can be found on the Toggl Track profile page, is required and
+//! must be passed using the `I3RS_TOGGL_TOKEN` environment variable or `token` configuration
+//! option.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `token` | A Toggl Track API token | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$description $hours:$minutes \|}\"</code>
+//! `interval` | Update interval in seconds | `60`
+//!
+//! Placeholder   | Value                                      | Type   | Unit
+//! --------------|--------------------------------------------|--------|-----
+//! `icon`        | A static icon                              | Icon   | -
+//! `description` | The description of the running time entry  | Text   | -
+//! `tags`        | The tags of the running time entry         | Text   | -
+//! `duration`    | The duration of the running time entry     | Number | s
+//! `hours`       | The hours of the duration                  | Text   | h
+//! `minutes`     | The minutes of the duration                | Text   | mn
+//!
+//! All placeholders except `icon` are absent if no time entry is running. The block is in the info
+//! state while a time entry is running.
+//!
+//! Action | Description                       | Default button
+//! -------|-----------------------------------|---------------
+//! `stop` | Stop the running time entry       | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "toggl"
+//! format = " $icon {$description.str(max_w:20) $hours:$minutes|idle} "
+//! ```
+//!
+//! # Icons Used
+//! - `time`
+
+use super::prelude::*;
+use chrono::{DateTime, Utc};
+
+const API_URL: &str = "https://api.track.toggl.com/api/v9";
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub token: Option<String>,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug)]
+struct TimeEntry {
+    id: u64,
+    workspace_id: u64,
+    description: Option<String>,
+    start: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "stop")])?;
+
+    let format = config
+        .format
+        .with_default(" $icon {$description $hours:$minutes |}")?;
+    let mut timer = config.interval.timer();
+
+    let token = config
+        .token
+        .clone()
+        .or_else(|| std::env::var("I3RS_TOGGL_TOKEN").ok())
+        .error("Toggl token not found")?;
+
+    loop {
+        let fetch = || get_current_entry(&token);
+        let entry = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let mut widget = Widget::new().with_format(format.clone());
+        match &entry {
+            Some(entry) => {
+                let start = DateTime::parse_from_rfc3339(&entry.start)
+                    .error("Failed to parse start time")?;
+                let secs = (Utc::now() - start.with_timezone(&Utc))
+                    .num_seconds()
+                    .max(0);
+                widget.state = State::Info;
+                widget.set_values(map! {
+                    "icon" => Value::icon("time"),
+                    "description" => Value::text(entry.description.clone().unwrap_or_default()),
+                    [if !entry.tags.is_empty()] "tags" => Value::text(entry.tags.join(" ")),
+                    "duration" => Value::seconds(secs),
+                    "hours" => Value::text(format!("{:02}", secs / 3600)),
+                    "minutes" => Value::text(format!("{:02}", secs / 60 % 60)),
+                });
+            }
+            None => {
+                widget.set_values(map! {
+                    "icon" => Value::icon("time"),
+                });
+            }
+        }
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "stop" => {
+                        if let Some(entry) = &entry {
+                            stop_entry(&token, entry).await?;
+                            break;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+async fn get_current_entry(token: &str) -> Result<Option<TimeEntry>> {
+    // https://engineering.toggl.com/docs/api/time_entries
+    REQWEST_CLIENT
+        .get(format!("{API_URL}/me/time_entries/current"))
+        .basic_auth(token, Some("api_token"))
+        .send()
+        .await
+        .error("Failed to send request")?
+        .error_for_status()
+        .error("Failed to get the current time entry")?
+        .json()
+        .await
+        .error("Failed to parse JSON")
+}
+
+async fn stop_entry(token: &str, entry: &TimeEntry) -> Result<()> {
+    REQWEST_CLIENT
+        .patch(format!(
+            "{API_URL}/workspaces/{}/time_entries/{}/stop",
+            entry.workspace_id, entry.id
+        ))
+        .basic_auth(token, Some("api_token"))
+        .send()
+        .await
+        .error("Failed to send request")?
+        .error_for_status()
+        .error("Failed to stop the time entry")?;
+    Ok(())
+}
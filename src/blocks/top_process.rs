@@ -0,0 +1,110 @@
+//! The most expensive process
+//!
+//! This block samples `/proc` and shows the process with the highest CPU or memory usage. CPU
+//! usage is measured between two updates and is relative to a single core, like in `top`, so it
+//! may exceed 100% for multi-threaded processes.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $name $cpu.eng(w:2) "`
+//! `sort_by` | Either `"cpu"` or `"memory"` | `"cpu"`
+//! `interval` | Update interval in seconds | `5`
+//! `warning_cpu` | CPU usage, in percents, above which the state is set to warning | `80.0`
+//! `critical_cpu` | CPU usage, in percents, above which the state is set to critical | `95.0`
+//!
+//! Placeholder | Value                              | Type   | Unit
+//! ------------|------------------------------------|--------|-----
+//! `icon`      | A static icon                      | Icon   | -
+//! `name`      | The name of the process            | Text   | -
+//! `pid`       | The PID of the process             | Number | -
+//! `cpu`       | The CPU usage of the process       | Number | %
+//! `memory`    | The resident memory of the process | Number | Bytes
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "top_process"
+//! sort_by = "memory"
+//! format = " $icon $name $memory.eng(w:3,u:B,p:Mi) "
+//! ```
+//!
+//! # Icons Used
+//! - `process`
+
+use super::prelude::*;
+use super::process::{list_processes, CpuTracker};
+
+/// CPU usage is measured between two samples, so the first one is taken this long before the
+/// block is shown
+const BASELINE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    pub sort_by: SortBy,
+    #[default(5.into())]
+    pub interval: Seconds,
+    #[default(80.0)]
+    pub warning_cpu: f64,
+    #[default(95.0)]
+    pub critical_cpu: f64,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortBy {
+    #[default]
+    Cpu,
+    Memory,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $name $cpu.eng(w:2) ")?;
+
+    let mut cpu_tracker = CpuTracker::default();
+    cpu_tracker.update(&list_processes(false).await?);
+    sleep(BASELINE_DELAY).await;
+
+    let mut timer = config.interval.timer();
+
+    loop {
+        let processes = list_processes(false).await?;
+        let usage = cpu_tracker.update(&processes);
+
+        let top = processes
+            .iter()
+            .zip(usage)
+            .max_by(|(a, a_cpu), (b, b_cpu)| match config.sort_by {
+                SortBy::Cpu => a_cpu.total_cmp(b_cpu),
+                SortBy::Memory => a.rss.cmp(&b.rss),
+            });
+
+        if let Some((process, cpu)) = top {
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = if cpu >= config.critical_cpu {
+                State::Critical
+            } else if cpu >= config.warning_cpu {
+                State::Warning
+            } else {
+                State::Idle
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("process"),
+                "name" => Value::text(process.name.clone()),
+                "pid" => Value::number(process.pid),
+                "cpu" => Value::percents(cpu),
+                "memory" => Value::bytes(process.rss as f64),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
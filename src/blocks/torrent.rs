@@ -0,0 +1,174 @@
+//! Torrent client status
+//!
+//! This block shows the torrents of [Transmission](https://transmissionbt.com/) or
+//! [Deluge](https://deluge-torrent.org/), using their RPC APIs: how many are downloading, the
+//! overall download and upload speeds, and the progress of the torrent added last.
+//!
+//! With Transmission, clicking the block toggles the alternative speed limits ("turtle mode"). For
+//! Deluge, the web UI must be enabled and connected to the daemon.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `driver` | One of `"transmission"` or `"deluge"` | `"transmission"`
+//! `url` | The URL of the RPC endpoint | `"http://localhost:9091/transmission/rpc"` for Transmission, `"http://localhost:8112/json"` for Deluge
+//! `username` | Transmission only: the username for basic authentication | `None`
+//! `password` | The password of the RPC API or the web UI | `None` for Transmission, `"deluge"` for Deluge
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $downloading ^icon_net_down $down_speed.eng(prefix:K) ^icon_net_up $up_speed.eng(prefix:K) "`
+//! `interval` | Update interval in seconds | `5`
+//!
+//! Placeholder       | Value                                                         | Type   | Unit
+//! ------------------|---------------------------------------------------------------|--------|-----------------
+//! `icon`            | A static icon                                                 | Icon   | -
+//! `count`           | The number of torrents                                        | Number | -
+//! `downloading`     | The number of torrents being downloaded                       | Number | -
+//! `down_speed`      | The overall download speed                                    | Number | Bytes per second
+//! `up_speed`        | The overall upload speed                                      | Number | Bytes per second
+//! `latest`          | The name of the torrent added last (absent if there are none) | Text   | -
+//! `latest_progress` | The progress of the torrent added last                        | Number | %
+//! `alt_speed`       | Present while the alternative speed limits are enabled        | Flag   | -
+//!
+//! The block is set to the info state while the alternative speed limits are enabled.
+//!
+//! Action             | Description                                             | Default button
+//! -------------------|---------------------------------------------------------|---------------
+//! `toggle_alt_speed` | Toggle the alternative speed limits (Transmission only) | Left
+//!
+//! # Examples
+//!
+//! ```toml
+//! [[block]]
+//! block = "torrent"
+//! format = " $icon $downloading{ $latest.str(max_w:15) $latest_progress|}{ $alt_speed slow|} "
+//! ```
+//!
+//! ```toml
+//! [[block]]
+//! block = "torrent"
+//! driver = "deluge"
+//! url = "http://nas.local:8112/json"
+//! password = "deluge"
+//! ```
+//!
+//! # Icons Used
+//! - `torrent`
+//! - `net_down`
+//! - `net_up`
+
+mod deluge;
+mod transmission;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub driver: TorrentDriver,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub format: FormatConfig,
+    #[default(5.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TorrentDriver {
+    #[default]
+    Transmission,
+    Deluge,
+}
+
+#[derive(Debug)]
+struct Torrent {
+    name: String,
+    /// Between 0 and 1
+    progress: f64,
+    /// A unix timestamp
+    added: i64,
+    downloading: bool,
+    /// Bytes per second
+    down_speed: f64,
+    /// Bytes per second
+    up_speed: f64,
+}
+
+#[async_trait]
+trait Client {
+    async fn torrents(&mut self) -> Result<Vec<Torrent>>;
+    /// Returns whether the alternative speed limits are enabled, or `None` if not supported
+    async fn alt_speed(&mut self) -> Result<Option<bool>>;
+    async fn set_alt_speed(&mut self, enabled: bool) -> Result<()>;
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "toggle_alt_speed")])?;
+
+    let format = config.format.with_default(
+        " $icon $downloading ^icon_net_down $down_speed.eng(prefix:K) ^icon_net_up $up_speed.eng(prefix:K) ",
+    )?;
+    let mut timer = config.interval.timer();
+
+    let mut client: Box<dyn Client + Send> = match config.driver {
+        TorrentDriver::Transmission => Box::new(transmission::Transmission::new(
+            config
+                .url
+                .as_deref()
+                .unwrap_or("http://localhost:9091/transmission/rpc"),
+            config.username.as_deref(),
+            config.password.as_deref(),
+        )),
+        TorrentDriver::Deluge => Box::new(deluge::Deluge::new(
+            config
+                .url
+                .as_deref()
+                .unwrap_or("http://localhost:8112/json"),
+            config.password.as_deref().unwrap_or("deluge"),
+        )),
+    };
+
+    loop {
+        let torrents = client.torrents().await?;
+        let alt_speed = client.alt_speed().await?;
+
+        let downloading = torrents.iter().filter(|t| t.downloading).count();
+        let down_speed: f64 = torrents.iter().map(|t| t.down_speed).sum();
+        let up_speed: f64 = torrents.iter().map(|t| t.up_speed).sum();
+        let latest = torrents.iter().max_by_key(|t| t.added);
+
+        let mut widget = Widget::new().with_format(format.clone());
+        if alt_speed == Some(true) {
+            widget.state = State::Info;
+        }
+        widget.set_values(map! {
+            "icon" => Value::icon("torrent"),
+            "count" => Value::number(torrents.len()),
+            "downloading" => Value::number(downloading),
+            "down_speed" => Value::bytes(down_speed),
+            "up_speed" => Value::bytes(up_speed),
+            [if let Some(t) = latest] "latest" => Value::text(t.name.clone()),
+            [if let Some(t) = latest] "latest_progress" => Value::percents(t.progress * 100.0),
+            [if alt_speed == Some(true)] "alt_speed" => Value::flag(),
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "toggle_alt_speed" => {
+                        if let Some(enabled) = alt_speed {
+                            client.set_alt_speed(!enabled).await?;
+                            break;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
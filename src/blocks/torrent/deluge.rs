@@ -0,0 +1,153 @@
+//! A client for the JSON-RPC API of Deluge's web UI
+//!
+//! <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+
+use serde::de::DeserializeOwned;
+
+use super::{Client, Torrent};
+use crate::blocks::prelude::*;
+
+/// The error code returned if the session expired
+const ERROR_NOT_AUTHENTICATED: i64 = 1;
+
+pub(super) struct Deluge {
+    url: String,
+    password: String,
+    /// The session cookie, set after logging in
+    cookie: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+    code: i64,
+}
+
+impl Deluge {
+    pub(super) fn new(url: &str, password: &str) -> Self {
+        Self {
+            url: url.into(),
+            password: password.into(),
+            cookie: None,
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<std::result::Result<T, RpcError>> {
+        #[derive(Deserialize)]
+        struct Response<T> {
+            result: Option<T>,
+            error: Option<RpcError>,
+        }
+
+        let mut request = REQWEST_CLIENT
+            .post(&self.url)
+            .json(&serde_json::json!({ "method": method, "params": params, "id": 1 }));
+        if let Some(cookie) = &self.cookie {
+            request = request.header(reqwest::header::COOKIE, cookie);
+        }
+        let response = request
+            .send()
+            .await
+            .error("Failed to send request")?
+            .error_for_status()
+            .error("Deluge returned an error")?;
+        if let Some(cookie) = response
+            .headers()
+            .get(reqwest::header::SET_COOKIE)
+            .and_then(|c| c.to_str().ok())
+            .and_then(|c| c.split(';').next())
+        {
+            self.cookie = Some(cookie.into());
+        }
+        let response: Response<T> = response.json().await.error("Failed to parse JSON")?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Ok(Err(error)),
+            (Some(result), None) => Ok(Ok(result)),
+            (None, None) => Err(Error::new(format!("{method} returned no result"))),
+        }
+    }
+
+    async fn login(&mut self) -> Result<()> {
+        let password = self.password.clone();
+        let success: bool = self
+            .call("auth.login", serde_json::json!([password]))
+            .await?
+            .map_err(|e| Error::new(e.message))?;
+        if !success {
+            return Err(Error::new("Wrong password"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Client for Deluge {
+    async fn torrents(&mut self) -> Result<Vec<Torrent>> {
+        #[derive(Deserialize)]
+        struct Ui {
+            torrents: HashMap<String, DelugeTorrent>,
+        }
+
+        #[derive(Deserialize)]
+        struct DelugeTorrent {
+            name: String,
+            /// Between 0 and 100
+            progress: f64,
+            time_added: f64,
+            state: String,
+            download_payload_rate: f64,
+            upload_payload_rate: f64,
+        }
+
+        let params = serde_json::json!([
+            [
+                "name",
+                "progress",
+                "time_added",
+                "state",
+                "download_payload_rate",
+                "upload_payload_rate",
+            ],
+            {},
+        ]);
+        if self.cookie.is_none() {
+            self.login().await?;
+        }
+        let ui: Ui = match self.call("web.update_ui", params.clone()).await? {
+            Ok(ui) => ui,
+            Err(e) if e.code == ERROR_NOT_AUTHENTICATED => {
+                self.login().await?;
+                self.call("web.update_ui", params)
+                    .await?
+                    .map_err(|e| Error::new(e.message))?
+            }
+            Err(e) => return Err(Error::new(e.message)),
+        };
+
+        Ok(ui
+            .torrents
+            .into_values()
+            .map(|t| Torrent {
+                name: t.name,
+                progress: t.progress / 100.0,
+                added: t.time_added as i64,
+                downloading: t.state == "Downloading",
+                down_speed: t.download_payload_rate,
+                up_speed: t.upload_payload_rate,
+            })
+            .collect())
+    }
+
+    /// Deluge has no alternative speed limits
+    async fn alt_speed(&mut self) -> Result<Option<bool>> {
+        Ok(None)
+    }
+
+    async fn set_alt_speed(&mut self, _enabled: bool) -> Result<()> {
+        Ok(())
+    }
+}
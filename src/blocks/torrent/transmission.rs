@@ -0,0 +1,150 @@
+//! A client for Transmission's RPC API
+//!
+//! <https://github.com/transmission/transmission/blob/main/docs/rpc-spec.md>
+
+use serde::de::DeserializeOwned;
+
+use super::{Client, Torrent};
+use crate::blocks::prelude::*;
+
+const SESSION_ID_HEADER: &str = "X-Transmission-Session-Id";
+
+/// The status of a torrent which is being downloaded
+const STATUS_DOWNLOAD: u8 = 4;
+
+pub(super) struct Transmission {
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+    session_id: Option<String>,
+}
+
+impl Transmission {
+    pub(super) fn new(url: &str, username: Option<&str>, password: Option<&str>) -> Self {
+        Self {
+            url: url.into(),
+            username: username.map(Into::into),
+            password: password.map(Into::into),
+            session_id: None,
+        }
+    }
+
+    async fn rpc<T: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        arguments: serde_json::Value,
+    ) -> Result<T> {
+        #[derive(Deserialize)]
+        struct Response<T> {
+            result: String,
+            arguments: Option<T>,
+        }
+
+        // The first request of a session is rejected with a new session id, which must be sent
+        // with the following requests
+        for _ in 0..2 {
+            let mut request = REQWEST_CLIENT
+                .post(&self.url)
+                .json(&serde_json::json!({ "method": method, "arguments": arguments }));
+            if let Some(session_id) = &self.session_id {
+                request = request.header(SESSION_ID_HEADER, session_id);
+            }
+            if let Some(username) = &self.username {
+                request = request.basic_auth(username, self.password.as_ref());
+            }
+            let response = request.send().await.error("Failed to send request")?;
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                self.session_id = response
+                    .headers()
+                    .get(SESSION_ID_HEADER)
+                    .and_then(|id| id.to_str().ok())
+                    .map(Into::into);
+                continue;
+            }
+            let response: Response<T> = response
+                .error_for_status()
+                .error("Transmission returned an error")?
+                .json()
+                .await
+                .error("Failed to parse JSON")?;
+            if response.result != "success" {
+                return Err(Error::new(format!("{method} failed: {}", response.result)));
+            }
+            return response.arguments.error("Response has no arguments");
+        }
+        Err(Error::new("Failed to get a session id"))
+    }
+}
+
+#[async_trait]
+impl Client for Transmission {
+    async fn torrents(&mut self) -> Result<Vec<Torrent>> {
+        #[derive(Deserialize)]
+        struct Arguments {
+            torrents: Vec<TransmissionTorrent>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct TransmissionTorrent {
+            name: String,
+            percent_done: f64,
+            added_date: i64,
+            status: u8,
+            rate_download: f64,
+            rate_upload: f64,
+        }
+
+        let arguments: Arguments = self
+            .rpc(
+                "torrent-get",
+                serde_json::json!({ "fields": [
+                    "name",
+                    "percentDone",
+                    "addedDate",
+                    "status",
+                    "rateDownload",
+                    "rateUpload",
+                ] }),
+            )
+            .await?;
+        Ok(arguments
+            .torrents
+            .into_iter()
+            .map(|t| Torrent {
+                name: t.name,
+                progress: t.percent_done,
+                added: t.added_date,
+                downloading: t.status == STATUS_DOWNLOAD,
+                down_speed: t.rate_download,
+                up_speed: t.rate_upload,
+            })
+            .collect())
+    }
+
+    async fn alt_speed(&mut self) -> Result<Option<bool>> {
+        #[derive(Deserialize)]
+        struct Arguments {
+            #[serde(rename = "alt-speed-enabled")]
+            alt_speed_enabled: bool,
+        }
+
+        let arguments: Arguments = self
+            .rpc(
+                "session-get",
+                serde_json::json!({ "fields": ["alt-speed-enabled"] }),
+            )
+            .await?;
+        Ok(Some(arguments.alt_speed_enabled))
+    }
+
+    async fn set_alt_speed(&mut self, enabled: bool) -> Result<()> {
+        let _: serde_json::Value = self
+            .rpc(
+                "session-set",
+                serde_json::json!({ "alt-speed-enabled": enabled }),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+//! UPS status
+//!
+//! This block shows the status of an uninterruptible power supply, read from a
+//! [NUT](https://networkupstools.org/) server (`upsd`) or from the network information server of
+//! [apcupsd](http://www.apcupsd.org/). The block goes critical while the UPS runs on battery.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `driver` | One of `"nut"` or `"apcupsd"` | `"nut"`
+//! `address` | The `hostname:port` of the server | `"localhost:3493"` for NUT, `"localhost:3551"` for apcupsd
+//! `ups` | NUT only: the name of the UPS | The first UPS reported by the server
+//! `format` | A string to customise the output of this block. See below for available placeholders. | <code>\" $icon {$percentage\|$status}{ $time\|} \"</code>
+//! `interval` | Update interval in seconds | `10`
+//!
+//! Placeholder  | Value                                                  | Type   | Unit
+//! -------------|--------------------------------------------------------|--------|--------
+//! `icon`       | Icon based on the battery charge                       | Icon   | -
+//! `status`     | `online`, `on battery` or `low battery`                | Text   | -
+//! `on_battery` | Present if the UPS runs on battery                     | Flag   | -
+//! `percentage` | Battery charge (absent if not reported)                | Number | %
+//! `load`       | Load of the UPS (absent if not reported)               | Number | %
+//! `time`       | Estimated runtime on battery (absent if not reported)  | Number | Seconds
+//!
+//! # Examples
+//!
+//! ```toml
+//! [[block]]
+//! block = "ups"
+//! ups = "eaton"
+//! format = " $icon $percentage $load.eng(w:2){ $on_battery $time|} "
+//! ```
+//!
+//! ```toml
+//! [[block]]
+//! block = "ups"
+//! driver = "apcupsd"
+//! address = "nas.local:3551"
+//! ```
+//!
+//! # Icons Used
+//! - `bat` (as a progression)
+
+mod apcupsd;
+mod nut;
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub driver: UpsDriver,
+    pub address: Option<String>,
+    pub ups: Option<String>,
+    pub format: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum UpsDriver {
+    #[default]
+    Nut,
+    Apcupsd,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct UpsStatus {
+    on_battery: bool,
+    low_battery: bool,
+    charge: Option<f64>,
+    load: Option<f64>,
+    /// Estimated runtime in seconds
+    runtime: Option<f64>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon {$percentage|$status}{ $time|} ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let status = match config.driver {
+            UpsDriver::Nut => {
+                let address = config.address.as_deref().unwrap_or("localhost:3493");
+                nut::get_status(address, config.ups.as_deref()).await?
+            }
+            UpsDriver::Apcupsd => {
+                let address = config.address.as_deref().unwrap_or("localhost:3551");
+                apcupsd::get_status(address).await?
+            }
+        };
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if status.on_battery || status.low_battery {
+            State::Critical
+        } else {
+            State::Idle
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon_progression("bat", status.charge.unwrap_or(100.0) / 100.0),
+            "status" => Value::text(
+                if status.low_battery {
+                    "low battery"
+                } else if status.on_battery {
+                    "on battery"
+                } else {
+                    "online"
+                }
+                .into()
+            ),
+            [if status.on_battery] "on_battery" => Value::flag(),
+            [if let Some(c) = status.charge] "percentage" => Value::percents(c),
+            [if let Some(l) = status.load] "load" => Value::percents(l),
+            [if let Some(r) = status.runtime] "time" => Value::seconds(r),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
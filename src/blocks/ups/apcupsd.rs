@@ -0,0 +1,67 @@
+//! A minimal client for the network information server of apcupsd
+
+use tokio::net::TcpStream;
+
+use super::UpsStatus;
+use crate::blocks::prelude::*;
+
+pub(super) async fn get_status(address: &str) -> Result<UpsStatus> {
+    let mut stream = TcpStream::connect(address)
+        .await
+        .error("Failed to connect to apcupsd")?;
+
+    // Every message is prefixed with its length as a big endian u16
+    let msg = b"status";
+    stream
+        .write_u16(msg.len() as u16)
+        .await
+        .error("Failed to write to apcupsd")?;
+    stream
+        .write_all(msg)
+        .await
+        .error("Failed to write to apcupsd")?;
+
+    let mut properties = HashMap::new();
+    let mut buf = Vec::new();
+    loop {
+        let len = stream
+            .read_u16()
+            .await
+            .error("Failed to read from apcupsd")?;
+        if len == 0 {
+            break;
+        }
+        buf.resize(len.into(), 0);
+        stream
+            .read_exact(&mut buf)
+            .await
+            .error("Failed to read from apcupsd")?;
+        let line = std::str::from_utf8(&buf).error("apcupsd sent invalid UTF-8")?;
+        if let Some((key, value)) = line.split_once(':') {
+            properties.insert(key.trim().to_owned(), value.trim().to_owned());
+        }
+    }
+
+    if properties.get("STATUS").is_none_or(|s| s == "COMMLOST") {
+        return Err(Error::new("apcupsd lost the connection to the UPS"));
+    }
+    Ok(parse_properties(&properties))
+}
+
+fn parse_properties(properties: &HashMap<String, String>) -> UpsStatus {
+    let flags: Vec<&str> = properties
+        .get("STATUS")
+        .map(|s| s.split_whitespace().collect())
+        .unwrap_or_default();
+    // Values have the form `<number> <unit>`, e.g. `30.0 Minutes`
+    let get =
+        |key: &str| -> Option<f64> { properties.get(key)?.split_whitespace().next()?.parse().ok() };
+
+    UpsStatus {
+        on_battery: flags.contains(&"ONBATT"),
+        low_battery: flags.contains(&"LOWBATT"),
+        charge: get("BCHARGE"),
+        load: get("LOADPCT"),
+        runtime: get("TIMELEFT").map(|minutes| minutes * 60.0),
+    }
+}
@@ -0,0 +1,134 @@
+//! A minimal client for the NUT network protocol
+//!
+//! <https://networkupstools.org/docs/developer-guide.chunked/net-protocol.html>
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+
+use super::UpsStatus;
+use crate::blocks::prelude::*;
+
+struct NutConnection {
+    reader: BufReader<TcpStream>,
+}
+
+impl NutConnection {
+    async fn connect(address: &str) -> Result<Self> {
+        let stream = TcpStream::connect(address)
+            .await
+            .error("Failed to connect to upsd")?;
+        Ok(Self {
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Sends a `LIST` command and returns the lines between `BEGIN LIST` and `END LIST`
+    async fn list(&mut self, query: &str) -> Result<Vec<String>> {
+        self.reader
+            .get_mut()
+            .write_all(format!("LIST {query}\n").as_bytes())
+            .await
+            .error("Failed to write to upsd")?;
+
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self
+                .reader
+                .read_line(&mut line)
+                .await
+                .error("Failed to read from upsd")?
+                == 0
+            {
+                return Err(Error::new("upsd closed the connection"));
+            }
+            let line = line.trim_end();
+            if let Some(err) = line.strip_prefix("ERR ") {
+                return Err(Error::new(format!("upsd returned an error: {err}")));
+            }
+            if line.starts_with("BEGIN LIST") {
+                continue;
+            }
+            if line.starts_with("END LIST") {
+                return Ok(lines);
+            }
+            lines.push(line.to_owned());
+        }
+    }
+}
+
+pub(super) async fn get_status(address: &str, ups: Option<&str>) -> Result<UpsStatus> {
+    let mut conn = NutConnection::connect(address).await?;
+
+    let ups = match ups {
+        Some(ups) => ups.to_owned(),
+        None => conn
+            .list("UPS")
+            .await?
+            .iter()
+            .find_map(|line| line.strip_prefix("UPS ")?.split(' ').next())
+            .map(String::from)
+            .error("upsd reports no UPS")?,
+    };
+
+    let vars = conn.list(&format!("VAR {ups}")).await?;
+    Ok(parse_vars(&vars))
+}
+
+/// Parses lines of the form `VAR <ups> <name> "<value>"`
+fn parse_vars(lines: &[String]) -> UpsStatus {
+    let vars: HashMap<&str, &str> = lines
+        .iter()
+        .filter_map(|line| {
+            let mut parts = line.strip_prefix("VAR ")?.splitn(3, ' ');
+            let _ups = parts.next()?;
+            let name = parts.next()?;
+            let value = parts.next()?.trim_matches('"');
+            Some((name, value))
+        })
+        .collect();
+
+    let flags: Vec<&str> = vars
+        .get("ups.status")
+        .map(|s| s.split_whitespace().collect())
+        .unwrap_or_default();
+    let get = |name| vars.get(name).and_then(|v| v.parse().ok());
+
+    UpsStatus {
+        on_battery: flags.contains(&"OB"),
+        low_battery: flags.contains(&"LB"),
+        charge: get("battery.charge"),
+        load: get("ups.load"),
+        runtime: get("battery.runtime"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vars() {
+        let lines: Vec<String> = [
+            r#"VAR eaton battery.charge "87""#,
+            r#"VAR eaton battery.runtime "1530""#,
+            r#"VAR eaton device.model "Eaton 5E 850i""#,
+            r#"VAR eaton ups.load "23""#,
+            r#"VAR eaton ups.status "OB DISCHRG""#,
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        assert_eq!(
+            parse_vars(&lines),
+            UpsStatus {
+                on_battery: true,
+                low_battery: false,
+                charge: Some(87.0),
+                load: Some(23.0),
+                runtime: Some(1530.0),
+            }
+        );
+    }
+}
@@ -3,12 +3,16 @@
 //! This block displays system uptime in terms of two biggest units, so minutes and seconds, or
 //! hours and minutes or days and hours or weeks and days.
 //!
+//! If `reboot_after` is set, the block's state is set to warning once the system has been up for
+//! that many days, as a reminder to reboot.
+//!
 //! # Configuration
 //!
 //! Key        | Values                     | Default
 //! -----------|----------------------------|--------
 //! `format` | A string to customise the output of this block. See below for available placeholders | `" $icon $text "`
 //! `interval` | Update interval in seconds | `60`
+//! `reboot_after` | Number of days of uptime after which the state is set to warning | `None`
 //!
 //! Placeholder   | Value                   | Type   | Unit
 //! --------------|-------------------------|--------|-----
@@ -21,6 +25,7 @@
 //! [[block]]
 //! block = "uptime"
 //! interval = 3600 # update every hour
+//! reboot_after = 14
 //! ```
 //!
 //! # Used Icons
@@ -38,6 +43,7 @@ pub struct Config {
     pub format: FormatConfig,
     #[default(60.into())]
     pub interval: Seconds,
+    pub reboot_after: Option<u64>,
 }
 
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
@@ -47,12 +53,14 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         let uptime = read_to_string("/proc/uptime")
             .await
             .error("Failed to read /proc/uptime")?;
-        let mut seconds: u64 = uptime
+        let uptime_secs: u64 = uptime
             .split('.')
             .next()
             .and_then(|u| u.parse().ok())
             .error("/proc/uptime has invalid content")?;
 
+        let mut seconds = uptime_secs;
+
         let weeks = seconds / 604_800;
         seconds %= 604_800;
         let days = seconds / 86_400;
@@ -73,6 +81,12 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         };
 
         let mut widget = Widget::new().with_format(format.clone());
+        if config
+            .reboot_after
+            .is_some_and(|days| uptime_secs >= days * 86_400)
+        {
+            widget.state = State::Warning;
+        }
         widget.set_values(map! {
           "icon" => Value::icon("uptime"),
           "text" => Value::text(text)
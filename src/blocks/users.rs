@@ -0,0 +1,101 @@
+//! The number of logged in users
+//!
+//! This block uses systemd-logind to count the distinct users with an open session, both local
+//! and remote. Sessions of display managers (greeters) are not counted. The block is updated as
+//! soon as a session is opened or closed.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count "`
+//! `format_alt` | If set, block will switch between `format` and `format_alt` on every click | `" $icon $names "`
+//!
+//! Placeholder | Value                                           | Type   | Unit
+//! ------------|-------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                   | Icon   | -
+//! `count`     | The number of logged in users                   | Number | -
+//! `sessions`  | The number of sessions                          | Number | -
+//! `names`     | A comma separated list of the logged in users   | Text   | -
+//!
+//! Action          | Description                               | Default button
+//! ----------------|-------------------------------------------|---------------
+//! `toggle_format` | Toggles between `format` and `format_alt` | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "users"
+//! format = " $icon $count/$sessions "
+//! ```
+//!
+//! # Icons Used
+//! - `user`
+
+use super::logind::{list_sessions, LoginManagerProxy};
+use super::prelude::*;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    pub format_alt: FormatConfig,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "toggle_format")])?;
+
+    let mut format = config.format.with_default(" $icon $count ")?;
+    let mut format_alt = config.format_alt.with_default(" $icon $names ")?;
+
+    let dbus_conn = new_system_dbus_connection().await?;
+    let manager = LoginManagerProxy::new(&dbus_conn)
+        .await
+        .error("Failed to create LoginManagerProxy")?;
+    let mut session_new = manager
+        .receive_session_new()
+        .await
+        .error("Couldn't create stream")?;
+    let mut session_removed = manager
+        .receive_session_removed()
+        .await
+        .error("Couldn't create stream")?;
+
+    loop {
+        let sessions = list_sessions(&manager).await?;
+        let mut names: Vec<&str> = sessions
+            .iter()
+            .filter(|s| s.class != "greeter")
+            .map(|s| s.user.as_str())
+            .collect();
+        let session_count = names.len();
+        names.sort_unstable();
+        names.dedup();
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.set_values(map! {
+            "icon" => Value::icon("user"),
+            "count" => Value::number(names.len()),
+            "sessions" => Value::number(session_count),
+            "names" => Value::text(names.join(", ")),
+        });
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = session_new.next() => break,
+                _ = session_removed.next() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "toggle_format" => {
+                        std::mem::swap(&mut format_alt, &mut format);
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! UV index
+//!
+//! This block displays the current and today's maximum UV index for a location, using the
+//! [Open-Meteo](https://open-meteo.com/) forecast API (no API key required).
+//!
+//! The location is configured the same way as for the `weather` block: either set `coordinates`
+//! or enable `autolocate`.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $uv.eng(w:1) "`
+//! `interval` | Update interval, in seconds. | `900`
+//! `autolocate` | Gets your location using the ipapi.co IP location service (no API key required). If the API call fails then the block will fallback to `coordinates`. | `false`
+//! `autolocate_interval` | Update interval for `autolocate` in seconds or "once" | `interval`
+//! `coordinates` | GPS latitude longitude coordinates as a tuple, example: `["39.2362","9.3317"]` | Required if `autolocate = false`
+//! `warning` | Minimum current UV index, where state is set to warning | `6.0`
+//! `critical` | Minimum current UV index, where state is set to critical | `8.0`
+//!
+//! Placeholder | Value                                                              | Type   | Unit
+//! ------------|--------------------------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                                      | Icon   | -
+//! `uv`        | Current UV index                                                   | Number | -
+//! `uv_max`    | Today's maximum UV index                                           | Number | -
+//! `risk`      | WHO exposure category of the current UV index, e.g. "Moderate"     | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "uv_index"
+//! format = " $icon $uv.eng(w:1) (max $uv_max.eng(w:1)) "
+//! coordinates = ["39.2362", "9.3317"]
+//! warning = 5
+//! ```
+//!
+//! # Icons Used
+//! - `weather_sun`
+
+use super::prelude::*;
+use super::weather::resolve_coordinates;
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(900.into())]
+    pub interval: Seconds,
+    pub autolocate: bool,
+    pub autolocate_interval: Option<Seconds>,
+    pub coordinates: Option<(String, String)>,
+    #[default(6.0)]
+    pub warning: f64,
+    #[default(8.0)]
+    pub critical: f64,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $uv.eng(w:1) ")?;
+
+    let autolocate_interval = config.autolocate_interval.unwrap_or(config.interval);
+    let mut timer = config.interval.timer();
+
+    loop {
+        let (lat, lon) = resolve_coordinates(
+            config.autolocate,
+            autolocate_interval.0,
+            config.coordinates.as_ref(),
+        )
+        .await?;
+
+        let fetch = || get_uv_index(&lat, &lon);
+        let (uv, uv_max) = fetch.retry(&ExponentialBuilder::default()).await?;
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = match uv {
+            x if x >= config.critical => State::Critical,
+            x if x >= config.warning => State::Warning,
+            _ => State::Idle,
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("weather_sun"),
+            "uv" => Value::number(uv),
+            "uv_max" => Value::number(uv_max),
+            "risk" => Value::text(risk(uv).into()),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn get_uv_index(lat: &str, lon: &str) -> Result<(f64, f64)> {
+    #[derive(Deserialize)]
+    struct ApiResponse {
+        current: Current,
+        daily: Daily,
+    }
+
+    #[derive(Deserialize)]
+    struct Current {
+        uv_index: f64,
+    }
+
+    #[derive(Deserialize)]
+    struct Daily {
+        uv_index_max: Vec<f64>,
+    }
+
+    let response: ApiResponse = REQWEST_CLIENT
+        .get(format!(
+            "{OPEN_METEO_URL}?latitude={lat}&longitude={lon}&current=uv_index&daily=uv_index_max&forecast_days=1&timezone=auto"
+        ))
+        .send()
+        .await
+        .error("Failed to send request")?
+        .json()
+        .await
+        .error("Failed to parse JSON")?;
+
+    let uv_max = response
+        .daily
+        .uv_index_max
+        .first()
+        .copied()
+        .error("No daily forecast available")?;
+
+    Ok((response.current.uv_index, uv_max))
+}
+
+fn risk(uv: f64) -> &'static str {
+    match uv {
+        x if x < 3.0 => "Low",
+        x if x < 6.0 => "Moderate",
+        x if x < 8.0 => "High",
+        x if x < 11.0 => "Very High",
+        _ => "Extreme",
+    }
+}
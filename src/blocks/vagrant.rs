@@ -0,0 +1,108 @@
+//! Running Vagrant machines
+//!
+//! This block reads Vagrant's global machine index and shows the number of running machines.
+//! The index is updated by Vagrant whenever a machine changes state, so machines that were
+//! stopped outside of Vagrant (e.g. by the provider directly) may still be counted as running
+//! until `vagrant global-status --prune` is run.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $running "`
+//! `interval` | Update interval in seconds | `30`
+//! `index_path` | Path to the machine index. Supports path expansions e.g. `~`. | `"~/.vagrant.d/data/machine-index/index"`
+//! `hide_if_none` | Hide the block if no machine is running | `false`
+//!
+//! Placeholder | Value                                           | Type   | Unit
+//! ------------|-------------------------------------------------|--------|-----
+//! `icon`      | A static icon                                   | Icon   | -
+//! `running`   | The number of running machines                  | Number | -
+//! `total`     | The number of known machines                    | Number | -
+//! `names`     | A comma separated list of running machines      | Text   | -
+//!
+//! The block is set to the info state if any machine is running.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "vagrant"
+//! format = " $icon $running/$total "
+//! hide_if_none = true
+//! ```
+//!
+//! # Icons Used
+//! - `vm`
+
+use super::prelude::*;
+use crate::util::read_file;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(30.into())]
+    pub interval: Seconds,
+    #[default("~/.vagrant.d/data/machine-index/index".into())]
+    pub index_path: ShellString,
+    pub hide_if_none: bool,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct MachineIndex {
+    #[serde(default)]
+    machines: HashMap<String, Machine>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Machine {
+    name: String,
+    state: String,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $icon $running ")?;
+    let index_path = config.index_path.expand()?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        // The index does not exist until the first machine is created
+        let index = match read_file(&*index_path).await {
+            Ok(content) => serde_json::from_str(&content).error("Failed to parse machine index")?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => MachineIndex::default(),
+            Err(e) => return Err(Error::new(format!("Failed to read machine index: {e}"))),
+        };
+
+        let mut running: Vec<&str> = index
+            .machines
+            .values()
+            .filter(|m| m.state == "running")
+            .map(|m| m.name.as_str())
+            .collect();
+        running.sort_unstable();
+
+        if running.is_empty() && config.hide_if_none {
+            api.hide()?;
+        } else {
+            let mut widget = Widget::new().with_format(format.clone());
+            widget.state = if running.is_empty() {
+                State::Idle
+            } else {
+                State::Info
+            };
+            widget.set_values(map! {
+                "icon" => Value::icon("vm"),
+                "running" => Value::number(running.len()),
+                "total" => Value::number(index.machines.len()),
+                "names" => Value::text(running.join(", ")),
+            });
+            api.set_widget(widget)?;
+        }
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
@@ -6,12 +6,13 @@
 //!
 //! Key | Values | Default
 //! ----|--------|--------
-//! `driver` | Which vpn should be used . Available drivers are: `"nordvpn"` and `"mullvad"` | `"nordvpn"`
+//! `driver` | Which vpn should be used . Available drivers are: `"nordvpn"`, `"mullvad"` and `"generic"` | `"nordvpn"`
 //! `interval` | Update interval in seconds. | `10`
 //! `format_connected` | A string to customise the output in case the network is connected. See below for available placeholders. | `" VPN: $icon "`
 //! `format_disconnected` | A string to customise the output in case the network is disconnected. See below for available placeholders. | `" VPN: $icon "`
 //! `state_connected` | The widgets state if the vpn network is connected. | `info`
 //! `state_disconnected` | The widgets state if the vpn network is disconnected | `idle`
+//! `generic` | Configuration of the `generic` driver, see below | -
 //!
 //! Placeholder | Value                                                     | Type   | Unit
 //! ------------|-----------------------------------------------------------|--------|------
@@ -32,6 +33,21 @@
 //! ## Mullvad
 //! Behind the scenes the mullvad driver uses the `mullvad` command line binary. In order for this to work properly the binary should be executable and mullvad daemon should be running.
 //!
+//! ## generic
+//! The generic driver works with any VPN: it considers the VPN connected if a network interface
+//! whose name starts with one of `interfaces` exists. With `check_external_ip`, the country of the
+//! external IP address is looked up using <https://ipapi.co> whenever the set of VPN interfaces
+//! changes; if the lookup fails, the block goes critical.
+//!
+//! It is configured in the `generic` table of the block:
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `interfaces` | Name prefixes of the network interfaces that indicate a VPN connection | `["tun", "tap", "wg", "nordlynx", "proton"]`
+//! `check_external_ip` | If `true`, look up the country of the external IP address while connected | `false`
+//! `connect_command` | Shell command run by `toggle` while disconnected | `None`
+//! `disconnect_command` | Shell command run by `toggle` while connected | `None`
+//!
 //! # Example
 //!
 //! Shows the current vpn network state:
@@ -47,6 +63,22 @@
 //! state_disconnected = "warning"
 //! ```
 //!
+//! Make it hard to miss that the VPN is not connected:
+//!
+//! ```toml
+//! [[block]]
+//! block = "vpn"
+//! driver = "generic"
+//! format_connected = " VPN: $icon $flag "
+//! format_disconnected = " NO VPN "
+//! state_connected = "good"
+//! state_disconnected = "critical"
+//! [block.generic]
+//! check_external_ip = true
+//! connect_command = "nmcli connection up my-vpn"
+//! disconnect_command = "nmcli connection down my-vpn"
+//! ```
+//!
 //! Possible values for `state_connected` and `state_disconnected`:
 //!
 //! ```text
@@ -71,6 +103,8 @@ mod nordvpn;
 use nordvpn::NordVpnDriver;
 mod mullvad;
 use mullvad::MullvadDriver;
+mod generic;
+use generic::GenericDriver;
 
 use super::prelude::*;
 
@@ -80,6 +114,7 @@ pub enum DriverType {
     #[default]
     Nordvpn,
     Mullvad,
+    Generic,
 }
 
 #[derive(Deserialize, Debug, SmartDefault)]
@@ -92,6 +127,7 @@ pub struct Config {
     pub format_disconnected: FormatConfig,
     pub state_connected: State,
     pub state_disconnected: State,
+    pub generic: generic::Config,
 }
 
 enum Status {
@@ -123,6 +159,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let driver: Box<dyn Driver> = match config.driver {
         DriverType::Nordvpn => Box::new(NordVpnDriver::new().await),
         DriverType::Mullvad => Box::new(MullvadDriver::new().await),
+        DriverType::Generic => Box::new(GenericDriver::new(&config.generic).await),
     };
 
     loop {
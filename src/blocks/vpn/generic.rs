@@ -0,0 +1,125 @@
+use std::sync::Mutex;
+
+use tokio::fs::read_dir;
+
+use crate::blocks::prelude::*;
+use crate::subprocess::spawn_shell;
+use crate::util::country_flag_from_iso_code;
+
+use super::{Driver, Status};
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    #[default(vec!["tun".into(), "tap".into(), "wg".into(), "nordlynx".into(), "proton".into()])]
+    pub interfaces: Vec<String>,
+    pub check_external_ip: bool,
+    pub connect_command: Option<String>,
+    pub disconnect_command: Option<String>,
+}
+
+pub struct GenericDriver {
+    interfaces: Vec<String>,
+    check_external_ip: bool,
+    connect_command: Option<String>,
+    disconnect_command: Option<String>,
+    /// The VPN interfaces seen during the last successful lookup, and its result
+    last_lookup: Mutex<Option<(Vec<String>, IpInfo)>>,
+}
+
+#[derive(Deserialize, Clone)]
+struct IpInfo {
+    country_code: String,
+    country_name: String,
+}
+
+impl GenericDriver {
+    pub async fn new(config: &Config) -> GenericDriver {
+        GenericDriver {
+            interfaces: config.interfaces.clone(),
+            check_external_ip: config.check_external_ip,
+            connect_command: config.connect_command.clone(),
+            disconnect_command: config.disconnect_command.clone(),
+            last_lookup: Mutex::new(None),
+        }
+    }
+
+    /// Returns the sorted names of all network interfaces which indicate a VPN connection
+    async fn vpn_interfaces(&self) -> Result<Vec<String>> {
+        let mut entries = read_dir("/sys/class/net")
+            .await
+            .error("Failed to read /sys/class/net")?;
+        let mut vpn_interfaces = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .error("Failed to read /sys/class/net")?
+        {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if self.interfaces.iter().any(|i| name.starts_with(i.as_str())) {
+                vpn_interfaces.push(name);
+            }
+        }
+        vpn_interfaces.sort();
+        Ok(vpn_interfaces)
+    }
+}
+
+#[async_trait]
+impl Driver for GenericDriver {
+    async fn get_status(&self) -> Result<Status> {
+        let vpn_interfaces = self.vpn_interfaces().await?;
+        if vpn_interfaces.is_empty() {
+            return Ok(Status::Disconnected);
+        }
+        if !self.check_external_ip {
+            return Ok(Status::Connected {
+                country: String::new(),
+                country_flag: String::new(),
+            });
+        }
+
+        // The external IP address is only looked up again once the VPN interfaces change
+        let cached = self
+            .last_lookup
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|(interfaces, _)| *interfaces == vpn_interfaces)
+            .map(|(_, info)| info.clone());
+        let info = match cached {
+            Some(info) => info,
+            None => {
+                let info: IpInfo = match REQWEST_CLIENT
+                    .get("https://ipapi.co/json/")
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                {
+                    Ok(response) => response.json().await.error("Failed to parse JSON")?,
+                    // The interface is up, but there is no connectivity through it
+                    Err(_) => return Ok(Status::Error),
+                };
+                *self.last_lookup.lock().unwrap() = Some((vpn_interfaces, info.clone()));
+                info
+            }
+        };
+
+        Ok(Status::Connected {
+            country_flag: country_flag_from_iso_code(&info.country_code),
+            country: info.country_name,
+        })
+    }
+
+    async fn toggle_connection(&self, status: &Status) -> Result<()> {
+        let cmd = match status {
+            Status::Connected { .. } => &self.disconnect_command,
+            Status::Disconnected => &self.connect_command,
+            Status::Error => return Ok(()),
+        };
+        if let Some(cmd) = cmd {
+            spawn_shell(cmd).or_error(|| format!("Failed to run '{cmd}'"))?;
+        }
+        Ok(())
+    }
+}
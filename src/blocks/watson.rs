@@ -11,13 +11,21 @@
 //! `state_path` | Path to the Watson state file. Supports path expansions e.g. `~`. | `$XDG_CONFIG_HOME/watson/state`
 //! `interval` | Update interval, in seconds. | `60`
 //!
-//! Placeholder   | Value                   | Type   | Unit
-//! --------------|-------------------------|--------|-----
-//! `text`        | Current activity        | Text   | -
+//! Placeholder   | Value                          | Type   | Unit
+//! --------------|--------------------------------|--------|-----
+//! `text`        | Current activity               | Text   | -
+//! `project`     | The project being tracked      | Text   | -
+//! `tags`        | The tags of the current frame  | Text   | -
+//! `elapsed`     | The time tracked so far        | Number | s
 //!
-//! Action             | Description                     | Default button
-//! -------------------|---------------------------------|---------------
-//! `toggle_show_time` | Toggle the value of `show_time` | Left
+//! `project`, `tags` and `elapsed` are absent if nothing is tracked.
+//!
+//! Action             | Description                                      | Default button
+//! -------------------|--------------------------------------------------|---------------
+//! `toggle_show_time` | Toggle the value of `show_time`                  | Left
+//! `stop`             | Stop tracking (`watson stop`)                    | -
+//! `restart`          | Restart the last frame (`watson restart`)        | -
+//! `toggle`           | Stop tracking, or restart the last frame if idle | Right
 //!
 //! # Example
 //!
@@ -28,15 +36,21 @@
 //! state_path = "~/.config/watson/state"
 //! ```
 //!
-//! # TODO
-//! - Extend functionality: start / stop watson using this block
+//! ```toml
+//! [[block]]
+//! block = "watson"
+//! format = " {$project{ [$tags]|} $elapsed.eng(w:1)|} "
+//! interval = 10
+//! ```
 
 use chrono::{offset::Local, DateTime};
 use dirs::config_dir;
 use inotify::{Inotify, WatchMask};
 use serde::de::Deserializer;
 use std::path::PathBuf;
+use std::process::Stdio;
 use tokio::fs::read_to_string;
+use tokio::process::Command;
 
 use super::prelude::*;
 
@@ -52,7 +66,10 @@ pub struct Config {
 
 pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     let mut actions = api.get_actions()?;
-    api.set_default_actions(&[(MouseButton::Left, None, "toggle_show_time")])?;
+    api.set_default_actions(&[
+        (MouseButton::Left, None, "toggle_show_time"),
+        (MouseButton::Right, None, "toggle"),
+    ])?;
 
     let format = config.format.with_default(" $text |")?;
 
@@ -96,10 +113,17 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
         let mut widget = Widget::new().with_format(format.clone());
 
         match state {
-            state @ WatsonState::Active { .. } => {
+            WatsonState::Active {
+                ref project,
+                start,
+                ref tags,
+            } => {
                 widget.state = State::Good;
                 widget.set_values(map!(
-                  "text" => Value::text(state.format(show_time, "started", format_delta_past))
+                  "text" => Value::text(state.format(show_time, "started", format_delta_past)),
+                  "project" => Value::text(project.clone()),
+                  [if !tags.is_empty()] "tags" => Value::text(tags.join(" ")),
+                  "elapsed" => Value::seconds((Local::now() - start).num_seconds()),
                 ));
                 prev_state = Some(state);
             }
@@ -122,6 +146,7 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
             }
         }
 
+        let is_active = matches!(prev_state, Some(WatsonState::Active { .. }));
         api.set_widget(widget)?;
 
         loop {
@@ -139,6 +164,9 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
                         show_time = !show_time;
                         break;
                     }
+                    "stop" => run_watson("stop").await?,
+                    "restart" => run_watson("restart").await?,
+                    "toggle" => run_watson(if is_active { "stop" } else { "restart" }).await?,
                     _ => (),
                 }
             }
@@ -146,6 +174,20 @@ pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
     }
 }
 
+async fn run_watson(command: &str) -> Result<()> {
+    // The state file is watched, so there is no need to trigger an update
+    let status = Command::new("watson")
+        .arg(command)
+        .stdout(Stdio::null())
+        .status()
+        .await
+        .or_error(|| format!("Failed to run watson {command}"))?;
+    if !status.success() {
+        return Err(Error::new(format!("watson {command} failed")));
+    }
+    Ok(())
+}
+
 fn format_delta_past(delta: &chrono::Duration) -> String {
     let spans = &[
         ("week", delta.num_weeks()),
@@ -392,6 +392,27 @@ struct AutolocateResult {
     timestamp: Instant,
 }
 
+/// Returns the latitude and longitude to use for blocks sharing the weather geolocation options:
+/// the autolocated position if `autolocate` is enabled and succeeds, `coordinates` otherwise.
+pub(super) async fn resolve_coordinates(
+    autolocate: bool,
+    autolocate_interval: Duration,
+    coordinates: Option<&(String, String)>,
+) -> Result<(String, String)> {
+    if autolocate {
+        let fetch = || find_ip_location(autolocate_interval);
+        if let Ok(location) = fetch.retry(&ExponentialBuilder::default()).await {
+            return Ok((
+                location.latitude.to_string(),
+                location.longitude.to_string(),
+            ));
+        }
+    }
+    coordinates
+        .cloned()
+        .error("'coordinates' must be set when 'autolocate' is disabled or failed")
+}
+
 // TODO: might be good to allow for different geolocation services to be used, similar to how we have `service` for the weather API
 /// No-op if last API call was made in the last `interval` seconds.
 async fn find_ip_location(interval: Duration) -> Result<Coordinates> {
@@ -0,0 +1,129 @@
+//! The most recent message received over a WebSocket
+//!
+//! This block keeps a WebSocket connection open and displays the last text message it received.
+//! If the server sends JSON, `json_path` can be used to select a field of the message, using the
+//! same syntax as the `http` block. The block is hidden until the first message is received.
+//!
+//! If the connection can't be established or is lost, the block tries to reconnect with an
+//! exponential backoff, which starts over once a message has been received.
+//!
+//! This block requires the `websocket` feature to be enabled at compile time.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `url` | The `ws://` or `wss://` URL to connect to | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $value "`
+//! `init_message` | A text message sent after connecting, e.g. to subscribe to a topic | `None`
+//! `json_path` | Path of the value in a JSON message | `None`
+//!
+//! Placeholder | Value                  | Type           | Unit
+//! ------------|------------------------|----------------|-----
+//! `value`     | The (extracted) value  | Number or Text | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "websocket"
+//! url = "wss://stream.binance.com:9443/ws/btcusdt@trade"
+//! json_path = "$.p"
+//! format = " BTC $value.eng(w:5) "
+//! ```
+
+use super::http::{parse_json_path, select_json, PathSegment};
+use super::prelude::*;
+use crate::formatting::Format;
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub url: String,
+    #[serde(default)]
+    pub format: FormatConfig,
+    pub init_message: Option<String>,
+    pub json_path: Option<String>,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $value ")?;
+
+    let json_path = config
+        .json_path
+        .as_deref()
+        .map(parse_json_path)
+        .transpose()?;
+
+    api.hide()?;
+
+    loop {
+        let session = || run_session(config, api, &format, json_path.as_deref());
+        session
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_max_delay(Duration::from_secs(60))
+                    .with_max_times(10),
+            )
+            .await?;
+    }
+}
+
+/// Connects and displays the received messages until the connection is lost. This is only
+/// considered a success if at least one message was received.
+async fn run_session(
+    config: &Config,
+    api: &CommonApi,
+    format: &Format,
+    json_path: Option<&[PathSegment]>,
+) -> Result<()> {
+    let (mut stream, _) = tokio_tungstenite::connect_async(&config.url)
+        .await
+        .error("Failed to connect")?;
+
+    if let Some(message) = &config.init_message {
+        futures::SinkExt::send(&mut stream, Message::Text(message.clone()))
+            .await
+            .error("Failed to send init message")?;
+    }
+
+    let mut received = false;
+    while let Some(message) = stream.next().await {
+        let text = match message {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) | Err(_) => break,
+            Ok(_) => continue,
+        };
+        received = true;
+
+        let text = match json_path {
+            Some(path) => {
+                let json: serde_json::Value =
+                    serde_json::from_str(&text).error("Failed to parse JSON")?;
+                match select_json(&json, path) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                    // Not every message has to contain the value
+                    None => continue,
+                }
+            }
+            None => text,
+        };
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.set_values(map! {
+            "value" => match text.parse::<f64>() {
+                Ok(number) => Value::number(number),
+                Err(_) => Value::text(text),
+            },
+        });
+        api.set_widget(widget)?;
+    }
+
+    if received {
+        Ok(())
+    } else {
+        Err(Error::new("Connection lost before receiving a message"))
+    }
+}
@@ -0,0 +1,234 @@
+//! WireGuard tunnel status
+//!
+//! This block shows whether a WireGuard interface is up, the endpoint of its peer and how long
+//! ago the last handshake happened. If the last handshake is older than `stale_after`, the block
+//! is set to the warning state, since the tunnel probably does not work anymore.
+//!
+//! Requires root privileges or the `CAP_NET_ADMIN` capability to read the peers with `wg show`.
+//! Unless i3status-rs has them, set `use_sudo` to run `wg` (and `wg-quick` or `networkctl` when
+//! toggling the tunnel) with `sudo -n`, which requires a matching `sudoers` entry.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `interface` | The WireGuard interface | **Required**
+//! `driver` | How the tunnel is brought up and down: `"wg_quick"` or `"networkd"` | `"wg_quick"`
+//! `use_sudo` | Whether to run the commands with `sudo -n`. Required unless i3status-rs runs with `CAP_NET_ADMIN`. | `false`
+//! `stale_after` | Age of the last handshake, in seconds, after which the block is set to the warning state | `180`
+//! `format` | A string to customise the output of this block while the interface is up. See below for available placeholders. | <code>\" $icon $interface{ $handshake\|} \"</code>
+//! `format_down` | Same as `format` but for when the interface is down | `" $icon $interface "`
+//! `interval` | Update interval in seconds | `10`
+//!
+//! Placeholder | Value                                                     | Type   | Unit
+//! ------------|-----------------------------------------------------------|--------|--------
+//! `icon`      | `net_vpn` when up, `net_down` when down                   | Icon   | -
+//! `interface` | The name of the interface                                 | Text   | -
+//! `endpoint`  | The endpoint of the first peer (absent if unknown)        | Text   | -
+//! `handshake` | Time since the most recent handshake with any peer        | Number | Seconds
+//! `stale`     | Present if the last handshake is older than `stale_after` | Flag   | -
+//!
+//! Action   | Description                 | Default button
+//! ---------|-----------------------------|---------------
+//! `toggle` | Bring the tunnel up or down | Left
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "wireguard"
+//! interface = "wg0"
+//! use_sudo = true
+//! format = " $icon $endpoint{ $handshake|} "
+//! ```
+//!
+//! # Icons Used
+//! - `net_vpn`
+//! - `net_down`
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::prelude::*;
+use crate::util::{command_maybe_sudo, read_file};
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub interface: String,
+    pub driver: Driver,
+    pub use_sudo: bool,
+    #[default(180.into())]
+    pub stale_after: Seconds<false>,
+    pub format: FormatConfig,
+    pub format_down: FormatConfig,
+    #[default(10.into())]
+    pub interval: Seconds,
+}
+
+#[derive(Deserialize, Debug, SmartDefault, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Driver {
+    #[default]
+    WgQuick,
+    Networkd,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Peer {
+    endpoint: Option<String>,
+    /// Unix timestamp of the latest handshake, zero if there was none
+    latest_handshake: u64,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let mut actions = api.get_actions()?;
+    api.set_default_actions(&[(MouseButton::Left, None, "toggle")])?;
+
+    let format = config
+        .format
+        .with_default(" $icon $interface{ $handshake|} ")?;
+    let format_down = config.format_down.with_default(" $icon $interface ")?;
+    let mut timer = config.interval.timer();
+
+    if config.interface.is_empty() {
+        return Err(Error::new("`interface` is required"));
+    }
+    let operstate_path = Path::new("/sys/class/net")
+        .join(&config.interface)
+        .join("operstate");
+
+    loop {
+        // WireGuard interfaces report `unknown` while they are up. Interfaces managed by
+        // wg-quick disappear when they are down, those managed by networkd report `down`.
+        let up = read_file(&operstate_path)
+            .await
+            .is_ok_and(|state| state != "down");
+
+        let mut widget = Widget::new();
+        if up {
+            let output = command_maybe_sudo("wg", config.use_sudo)
+                .args(["show", &config.interface, "dump"])
+                .output()
+                .await
+                .error("Failed to run wg")?;
+            if !output.status.success() {
+                let hint = if config.use_sudo {
+                    ""
+                } else {
+                    " (it needs root, see `use_sudo`)"
+                };
+                return Err(Error::new(format!(
+                    "wg show failed: {}{hint}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            let peers = parse_dump(&String::from_utf8_lossy(&output.stdout));
+
+            let endpoint = peers.iter().find_map(|p| p.endpoint.clone());
+            let latest_handshake = peers.iter().map(|p| p.latest_handshake).max();
+            let handshake_age = latest_handshake.filter(|&t| t != 0).map(|t| {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                now.saturating_sub(t)
+            });
+            let stale = handshake_age.is_none_or(|age| age > config.stale_after.seconds());
+
+            widget.set_format(format.clone());
+            widget.state = if stale { State::Warning } else { State::Good };
+            widget.set_values(map! {
+                "icon" => Value::icon("net_vpn"),
+                "interface" => Value::text(config.interface.clone()),
+                [if let Some(e) = endpoint] "endpoint" => Value::text(e),
+                [if let Some(age) = handshake_age] "handshake" => Value::seconds(age as f64),
+                [if stale] "stale" => Value::flag(),
+            });
+        } else {
+            widget.set_format(format_down.clone());
+            widget.set_values(map! {
+                "icon" => Value::icon("net_down"),
+                "interface" => Value::text(config.interface.clone()),
+            });
+        }
+        api.set_widget(widget)?;
+
+        loop {
+            select! {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(action) = actions.recv() => match action.as_ref() {
+                    "toggle" => {
+                        toggle(config, up).await?;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+async fn toggle(config: &Config, up: bool) -> Result<()> {
+    let direction = if up { "down" } else { "up" };
+    let program = match config.driver {
+        Driver::WgQuick => "wg-quick",
+        Driver::Networkd => "networkctl",
+    };
+    let output = command_maybe_sudo(program, config.use_sudo)
+        .args([direction, &config.interface])
+        .output()
+        .await
+        .or_error(|| format!("Failed to run {program}"))?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "{program} {direction} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Parses the output of `wg show <interface> dump`. The first line describes the interface
+/// itself, every other line describes a peer.
+fn parse_dump(dump: &str) -> Vec<Peer> {
+    dump.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let endpoint = *fields.get(2)?;
+            Some(Peer {
+                endpoint: (endpoint != "(none)").then(|| endpoint.to_owned()),
+                latest_handshake: fields.get(4)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dump() {
+        let dump = "\
+cHJpdmF0ZQ==\tcHVibGlj\t51820\toff
+cGVlcjE=\t(none)\t203.0.113.7:51820\t0.0.0.0/0\t1718000000\t1234\t5678\t25
+cGVlcjI=\t(none)\t(none)\t10.0.0.2/32\t0\t0\t0\toff
+";
+        assert_eq!(
+            parse_dump(dump),
+            [
+                Peer {
+                    endpoint: Some("203.0.113.7:51820".into()),
+                    latest_handshake: 1718000000,
+                },
+                Peer {
+                    endpoint: None,
+                    latest_handshake: 0,
+                },
+            ]
+        );
+    }
+}
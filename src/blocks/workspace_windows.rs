@@ -0,0 +1,84 @@
+//! Windows on the focused workspace of sway or i3
+//!
+//! This block shows how many windows are on the focused workspace, and is set to the warning state
+//! when there are more than `warning`, as a gentle nudge to spread them over more workspaces.
+//! Floating windows are counted as well.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $count "`
+//! `warning` | Number of windows above which the state is set to warning | `6`
+//!
+//! Placeholder | Value                                  | Type   | Unit
+//! ------------|----------------------------------------|--------|-----
+//! `count`     | The number of windows on the workspace | Number | -
+//! `workspace` | The name of the focused workspace      | Text   | -
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "workspace_windows"
+//! format = " $workspace: $count "
+//! warning = 4
+//! ```
+
+use swayipc_async::{Connection, EventType, NodeType};
+
+use super::prelude::*;
+use super::sway_tree::windows;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub format: FormatConfig,
+    #[default(6)]
+    pub warning: usize,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config.format.with_default(" $count ")?;
+
+    let mut connection = Connection::new()
+        .await
+        .error("Failed to open swayipc connection")?;
+    let mut events = Connection::new()
+        .await
+        .error("Failed to open swayipc connection")?
+        .subscribe([EventType::Window, EventType::Workspace])
+        .await
+        .error("Failed to subscribe to events")?;
+
+    loop {
+        let tree = connection
+            .get_tree()
+            .await
+            .error("Failed to get the tree")?;
+        let workspace = tree.find_focused_as_ref(|n| n.node_type == NodeType::Workspace);
+
+        match workspace {
+            None => api.hide()?,
+            Some(workspace) => {
+                let count = windows(workspace).len();
+                let mut widget = Widget::new().with_format(format.clone());
+                if count > config.warning {
+                    widget.state = State::Warning;
+                }
+                widget.set_values(map! {
+                    "count" => Value::number(count),
+                    [if let Some(name) = &workspace.name] "workspace" => Value::text(name.clone()),
+                });
+                api.set_widget(widget)?;
+            }
+        }
+
+        select! {
+            event = events.next() => {
+                event.error("swayipc channel closed")?.error("bad event")?;
+            }
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
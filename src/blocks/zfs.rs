@@ -0,0 +1,171 @@
+//! ZFS pool health
+//!
+//! This block uses the `zpool` command to show the health, capacity and scrub progress of a ZFS
+//! pool. The block is set to the critical state if the pool is not `ONLINE`.
+//!
+//! # Configuration
+//!
+//! Key | Values | Default
+//! ----|--------|--------
+//! `pool` | The name of the pool | **Required**
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $pool $health $capacity{ scrub $scrub_progress\|} "`
+//! `interval` | Update interval in seconds | `60`
+//! `warning` | Capacity, in percents, above which the state is set to warning | `80.0`
+//! `critical` | Capacity, in percents, above which the state is set to critical | `90.0`
+//!
+//! Placeholder      | Value                                                        | Type   | Unit
+//! -----------------|--------------------------------------------------------------|--------|------
+//! `icon`           | A static icon                                                | Icon   | -
+//! `pool`           | The name of the pool                                         | Text   | -
+//! `health`         | The health of the pool, e.g. `ONLINE` or `DEGRADED`          | Text   | -
+//! `capacity`       | The used share of the pool                                   | Number | %
+//! `size`           | The size of the pool                                         | Number | Bytes
+//! `free`           | The free space of the pool                                   | Number | Bytes
+//! `scrub_progress` | The progress of a running scrub or resilver (absent if none) | Number | %
+//!
+//! # Example
+//!
+//! ```toml
+//! [[block]]
+//! block = "zfs"
+//! pool = "tank"
+//! format = " $icon $health $free.eng(w:3,u:B,p:Gi){ ($scrub_progress)|} "
+//! ```
+//!
+//! # Icons Used
+//! - `disk_drive`
+
+use super::prelude::*;
+use tokio::process::Command;
+
+#[derive(Deserialize, Debug, SmartDefault)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    pub pool: String,
+    pub format: FormatConfig,
+    #[default(60.into())]
+    pub interval: Seconds,
+    #[default(80.0)]
+    pub warning: f64,
+    #[default(90.0)]
+    pub critical: f64,
+}
+
+pub async fn run(config: &Config, api: &CommonApi) -> Result<()> {
+    let format = config
+        .format
+        .with_default(" $icon $pool $health $capacity{ scrub $scrub_progress|} ")?;
+    let mut timer = config.interval.timer();
+
+    loop {
+        let list = zpool(&["list", "-Hp", "-o", "health,size,free", &config.pool]).await?;
+        let (health, size, free) = parse_list(&list).error("Failed to parse zpool list output")?;
+        let status = zpool(&["status", &config.pool]).await?;
+        let scrub_progress = parse_scan_progress(&status);
+        let capacity = if size > 0.0 {
+            (size - free) / size * 100.0
+        } else {
+            0.0
+        };
+
+        let mut widget = Widget::new().with_format(format.clone());
+        widget.state = if health != "ONLINE" || capacity >= config.critical {
+            State::Critical
+        } else if capacity >= config.warning {
+            State::Warning
+        } else if scrub_progress.is_some() {
+            State::Info
+        } else {
+            State::Idle
+        };
+        widget.set_values(map! {
+            "icon" => Value::icon("disk_drive"),
+            "pool" => Value::text(config.pool.clone()),
+            "health" => Value::text(health.to_string()),
+            "capacity" => Value::percents(capacity),
+            "size" => Value::bytes(size),
+            "free" => Value::bytes(free),
+            [if let Some(p) = scrub_progress] "scrub_progress" => Value::percents(p),
+        });
+        api.set_widget(widget)?;
+
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
+    }
+}
+
+async fn zpool(args: &[&str]) -> Result<String> {
+    let output = Command::new("zpool")
+        .args(args)
+        .output()
+        .await
+        .error("Failed to run zpool")?;
+    if !output.status.success() {
+        return Err(Error::new(format!(
+            "zpool failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).error("zpool produced non-UTF8 output")
+}
+
+/// Parses the output of `zpool list -Hp -o health,size,free`
+fn parse_list(output: &str) -> Option<(&str, f64, f64)> {
+    let mut fields = output.trim().split('\t');
+    let health = fields.next()?;
+    let size = fields.next()?.parse().ok()?;
+    let free = fields.next()?.parse().ok()?;
+    Some((health, size, free))
+}
+
+/// Finds the progress of a running scrub or resilver in the output of `zpool status`
+fn parse_scan_progress(output: &str) -> Option<f64> {
+    let mut lines = output
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with("scan:"));
+    if !lines.next()?.contains("in progress") {
+        return None;
+    }
+    // e.g. "    1.23T scanned at 1.00G/s, 600G issued at 500M/s, 2.00T total"
+    //      "    0B repaired, 29.29% done, 00:48:12 to go"
+    lines
+        .take(3)
+        .find_map(|l| l.split(", ").find_map(|f| f.strip_suffix("% done")))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            parse_list("DEGRADED\t1000\t250\n"),
+            Some(("DEGRADED", 1000.0, 250.0))
+        );
+        assert_eq!(parse_list("ONLINE\n"), None);
+    }
+
+    #[test]
+    fn test_parse_scan_progress() {
+        let status = "  pool: tank
+ state: ONLINE
+  scan: scrub in progress since Sun Oct 11 00:24:01 2026
+\t1.23T scanned at 1.00G/s, 600G issued at 500M/s, 2.00T total
+\t0B repaired, 29.29% done, 00:48:12 to go
+config:
+";
+        assert_eq!(parse_scan_progress(status), Some(29.29));
+        let status = "  pool: tank
+ state: ONLINE
+  scan: scrub repaired 0B in 01:02:03 with 0 errors on Sun Oct 11 01:26:04 2026
+config:
+";
+        assert_eq!(parse_scan_progress(status), None);
+    }
+}
@@ -35,6 +35,7 @@
 //! `width` or `w`         | Text will be exactly this length by padding or truncating as needed | N/A
 //! `rot_interval`         | if text is longer than `max_width` it will be rotated every `rot_interval` seconds, if set | None
 //! `rot_separator`        | if text is longer than `max_width` it will be rotated with this seporator | <code>\"\|\"</code>
+//! `align`                | where to place the text if it is padded to `min_width`: `left`, `center` or `right` | `left`
 //!
 //! Note: width just changes the values of both min_width and max_width to be the same. Use width
 //! if you want the values to be the same, or the other two otherwise. Don't mix width with
@@ -55,6 +56,21 @@
 //! `pad_with`      | the character that is used to pad the number to be `width` long                                  | ` ` (a space)
 //! `range`         | a range of allowed values, in the format `<start>..<end>`, inclusive. Both start and end are optional. Can be used to, for example, hide the block when the value is not in a given range. | `..`
 //!
+//! ## `fix` - Format numbers using fixed-point notation
+//!
+//! Argument        | Description                                                                                      |Default value
+//! ----------------|--------------------------------------------------------------------------------------------------|-------------
+//! `width` or `w`  | the resulting text (without prefix and unit) will be at least `width` characters long           | `0`
+//! `digits` or `d` | the number of digits after the decimal point                                                     | `0`
+//! `unit` or `u`   | some values have a [unit](unit::Unit), and it is possible to convert them by setting this option | N/A
+//! `hide_unit`     | hide the unit symbol                                                                             | `false`
+//! `unit_space`    | have a whitespace before unit symbol                                                             | `false`
+//! `prefix` or `p` | the [SI prefix](prefix::Prefix) to scale the value to. Unlike `eng`, the prefix is never chosen automatically | `1`
+//! `hide_prefix`   | hide the prefix symbol                                                                           | `false`
+//! `prefix_space`  | have a whitespace before prefix symbol                                                           | `false`
+//! `pad_with`      | the character that is used to pad the number to be `width` long. Zeros are placed after the sign | ` ` (a space)
+//! `range`         | a range of allowed values, in the format `<start>..<end>`, inclusive. Both start and end are optional. | `..`
+//!
 //! ## `bar` - Display numbers as progress bars
 //!
 //! Argument               | Description                                                                     |Default value
@@ -74,6 +90,18 @@
 //! `format` or `f`        | [chrono docs](https://docs.rs/chrono/0.3.0/chrono/format/strftime/index.html#specifiers) for all options. | `'%a %d/%m %R'`
 //! `locale` or `l`        | Locale to apply when formatting the time                                                                  | System locale
 //!
+//! ## `dur` or `duration` - Format numbers of seconds as durations
+//!
+//! Numbers without a unit are treated as seconds. With the default arguments, `5013` is shown as
+//! `1h 23m`; with `hms:true` it is shown as `01:23:33`.
+//!
+//! Argument             | Description                                                                                                 |Default value
+//! ---------------------|-------------------------------------------------------------------------------------------------------------|-------------
+//! `max_units`          | the maximum number of units (days, hours, minutes, seconds) to show, starting from the largest non-zero one | `2` (`3` for `hms`)
+//! `separator` or `sep` | the text between two units                                                                                  | `" "` (`":"` for `hms`)
+//! `leading_zeroes`     | pad all but the first unit with zeroes to two digits (all units for `hms`)                                  | `false` (`true` for `hms`)
+//! `hms`                | show the last `max_units` of hours, minutes and seconds without unit symbols, like a clock                  | `false`
+//!
 //! # Handling missing placeholders and incorrect types
 //!
 //! Some blocks allow missing placeholders, for example [bluetooth](crate::blocks::bluetooth)'s
@@ -13,8 +13,12 @@ mod bar;
 pub use bar::BarFormatter;
 mod datetime;
 pub use datetime::{DatetimeFormatter, DEFAULT_DATETIME_FORMATTER};
+mod duration;
+pub use duration::DurationFormatter;
 mod eng;
 pub use eng::{EngFormatter, DEFAULT_NUMBER_FORMATTER};
+mod fix;
+pub use fix::FixFormatter;
 mod flag;
 pub use flag::{FlagFormatter, DEFAULT_FLAG_FORMATTER};
 mod pango;
@@ -34,7 +38,9 @@ pub fn new_formatter(name: &str, args: &[Arg]) -> Result<Box<dyn Formatter>> {
     match name {
         "bar" => Ok(Box::new(BarFormatter::from_args(args)?)),
         "datetime" => Ok(Box::new(DatetimeFormatter::from_args(args)?)),
+        "dur" | "duration" => Ok(Box::new(DurationFormatter::from_args(args)?)),
         "eng" => Ok(Box::new(EngFormatter::from_args(args)?)),
+        "fix" => Ok(Box::new(FixFormatter::from_args(args)?)),
         "pango-str" => Ok(Box::new(PangoStrFormatter::from_args(args)?)),
         "str" => Ok(Box::new(StrFormatter::from_args(args)?)),
         _ => Err(Error::new(format!("Unknown formatter: '{name}'"))),
@@ -0,0 +1,202 @@
+use crate::formatting::unit::Unit;
+
+use std::fmt::Write;
+
+use super::*;
+
+const UNITS: [(&str, u64); 4] = [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)];
+
+const DEFAULT_DURATION_MAX_UNITS: usize = 2;
+const DEFAULT_DURATION_HMS_MAX_UNITS: usize = 3;
+
+#[derive(Debug)]
+pub struct DurationFormatter {
+    max_units: usize,
+    separator: String,
+    leading_zeroes: bool,
+    hms: bool,
+}
+
+impl DurationFormatter {
+    pub(super) fn from_args(args: &[Arg]) -> Result<Self> {
+        let mut max_units = None;
+        let mut separator = None;
+        let mut leading_zeroes = None;
+        let mut hms = false;
+        for arg in args {
+            match arg.key {
+                "max_units" => {
+                    max_units = Some(
+                        arg.val
+                            .parse()
+                            .error("max_units must be a positive integer")?,
+                    );
+                }
+                "separator" | "sep" => {
+                    separator = Some(arg.val.to_string());
+                }
+                "leading_zeroes" => {
+                    leading_zeroes = Some(
+                        arg.val
+                            .parse()
+                            .ok()
+                            .error("leading_zeroes must be true or false")?,
+                    );
+                }
+                "hms" => {
+                    hms = arg.val.parse().ok().error("hms must be true or false")?;
+                }
+                other => {
+                    return Err(Error::new(format!(
+                        "Unknown argument for 'duration': '{other}'"
+                    )));
+                }
+            }
+        }
+
+        let max_units = max_units.unwrap_or(if hms {
+            DEFAULT_DURATION_HMS_MAX_UNITS
+        } else {
+            DEFAULT_DURATION_MAX_UNITS
+        });
+        // `hms` can't show days
+        let limit = if hms { UNITS.len() - 1 } else { UNITS.len() };
+        if !(1..=limit).contains(&max_units) {
+            return Err(Error::new(format!(
+                "max_units must be between 1 and {limit}"
+            )));
+        }
+
+        Ok(Self {
+            max_units,
+            separator: separator.unwrap_or_else(|| if hms { ":" } else { " " }.into()),
+            leading_zeroes: leading_zeroes.unwrap_or(hms),
+            hms,
+        })
+    }
+}
+
+impl Formatter for DurationFormatter {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String, FormatError> {
+        match val {
+            &Value::Number { mut val, unit } => {
+                if unit != Unit::None {
+                    val = unit.convert(val, Unit::Seconds)?;
+                }
+                let sign = if val <= -1.0 { "-" } else { "" };
+                let mut rest = val.abs() as u64;
+
+                // In `hms` mode the first shown unit absorbs all larger ones, otherwise the
+                // largest non-zero unit comes first
+                let first = if self.hms {
+                    UNITS.len() - self.max_units
+                } else {
+                    UNITS
+                        .iter()
+                        .position(|&(_, secs)| rest >= secs)
+                        .unwrap_or(UNITS.len() - 1)
+                };
+
+                let mut retval = sign.to_string();
+                for (i, &(symbol, secs)) in
+                    UNITS.iter().enumerate().skip(first).take(self.max_units)
+                {
+                    let count = rest / secs;
+                    rest %= secs;
+                    if i != first {
+                        retval.push_str(&self.separator);
+                    }
+                    if self.leading_zeroes && (i != first || self.hms) {
+                        write!(retval, "{count:02}").unwrap();
+                    } else {
+                        write!(retval, "{count}").unwrap();
+                    }
+                    if !self.hms {
+                        retval.push_str(symbol);
+                    }
+                }
+
+                Ok(retval)
+            }
+            other => Err(FormatError::IncompatibleFormatter {
+                ty: other.type_name(),
+                fmt: "duration",
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! fmt {
+        ($name:ident $(, $key:ident : $value:tt)*) => {
+            new_formatter(stringify!($name), &[
+                $( Arg { key: stringify!($key), val: stringify!($value) } ),*
+            ]).unwrap()
+        };
+    }
+
+    fn seconds(val: f64) -> Value {
+        Value::Number {
+            val,
+            unit: Unit::Seconds,
+        }
+    }
+
+    #[test]
+    fn duration_units() {
+        let config = SharedConfig::default();
+
+        let fmt = fmt!(dur);
+        assert_eq!(fmt.format(&seconds(5013.0), &config).unwrap(), "1h 23m");
+        assert_eq!(fmt.format(&seconds(446_400.0), &config).unwrap(), "5d 4h");
+        assert_eq!(fmt.format(&seconds(65.9), &config).unwrap(), "1m 5s");
+        assert_eq!(fmt.format(&seconds(42.0), &config).unwrap(), "42s");
+        assert_eq!(fmt.format(&seconds(0.0), &config).unwrap(), "0s");
+        assert_eq!(fmt.format(&seconds(-90.0), &config).unwrap(), "-1m 30s");
+
+        let fmt = fmt!(duration, max_units: 3, leading_zeroes: true);
+        assert_eq!(
+            fmt.format(&seconds(3_605.0), &config).unwrap(),
+            "1h 00m 05s"
+        );
+
+        let fmt = fmt!(dur, max_units: 1);
+        let val = Value::Number {
+            val: 7_200.0,
+            unit: Unit::None,
+        };
+        assert_eq!(fmt.format(&val, &config).unwrap(), "2h");
+    }
+
+    #[test]
+    fn duration_hms() {
+        let config = SharedConfig::default();
+
+        let fmt = fmt!(dur, hms: true);
+        assert_eq!(fmt.format(&seconds(8_133.0), &config).unwrap(), "02:15:33");
+        assert_eq!(fmt.format(&seconds(93_600.0), &config).unwrap(), "26:00:00");
+        assert_eq!(fmt.format(&seconds(59.0), &config).unwrap(), "00:00:59");
+
+        let fmt = fmt!(dur, hms: true, max_units: 2, leading_zeroes: false);
+        assert_eq!(fmt.format(&seconds(8_133.0), &config).unwrap(), "135:33");
+    }
+
+    #[test]
+    fn duration_invalid() {
+        let config = SharedConfig::default();
+
+        let fmt = fmt!(dur);
+        let val = Value::Number {
+            val: 1.0,
+            unit: Unit::Bytes,
+        };
+        assert!(fmt.format(&val, &config).is_err());
+
+        let arg = |key, val| Arg { key, val };
+        assert!(new_formatter("dur", &[arg("max_units", "0")]).is_err());
+        assert!(new_formatter("dur", &[arg("hms", "true"), arg("max_units", "4")]).is_err());
+    }
+}
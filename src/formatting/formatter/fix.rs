@@ -0,0 +1,262 @@
+use crate::formatting::prefix::Prefix;
+use crate::formatting::unit::Unit;
+
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+
+use super::*;
+
+type PadWith = Cow<'static, str>;
+
+const DEFAULT_FIX_WIDTH: usize = 0;
+const DEFAULT_FIX_DIGITS: usize = 0;
+const DEFAULT_FIX_PAD_WITH: PadWith = Cow::Borrowed(" ");
+
+#[derive(Debug)]
+pub struct FixFormatter {
+    width: usize,
+    digits: usize,
+    unit: Option<Unit>,
+    unit_has_space: bool,
+    unit_hidden: bool,
+    prefix: Option<Prefix>,
+    prefix_has_space: bool,
+    prefix_hidden: bool,
+    pad_with: PadWith,
+    range: RangeInclusive<f64>,
+}
+
+impl FixFormatter {
+    pub(super) fn from_args(args: &[Arg]) -> Result<Self> {
+        let mut result = Self {
+            width: DEFAULT_FIX_WIDTH,
+            digits: DEFAULT_FIX_DIGITS,
+            unit: None,
+            unit_has_space: false,
+            unit_hidden: false,
+            prefix: None,
+            prefix_has_space: false,
+            prefix_hidden: false,
+            pad_with: DEFAULT_FIX_PAD_WITH,
+            range: f64::NEG_INFINITY..=f64::INFINITY,
+        };
+
+        for arg in args {
+            match arg.key {
+                "width" | "w" => {
+                    result.width = arg.val.parse().error("Width must be a positive integer")?;
+                }
+                "digits" | "d" => {
+                    result.digits = arg.val.parse().error("Digits must be a positive integer")?;
+                }
+                "unit" | "u" => {
+                    result.unit = Some(arg.val.parse()?);
+                }
+                "hide_unit" => {
+                    result.unit_hidden = arg
+                        .val
+                        .parse()
+                        .ok()
+                        .error("hide_unit must be true or false")?;
+                }
+                "unit_space" => {
+                    result.unit_has_space = arg
+                        .val
+                        .parse()
+                        .ok()
+                        .error("unit_space must be true or false")?;
+                }
+                "prefix" | "p" => {
+                    result.prefix = Some(arg.val.parse()?);
+                }
+                "hide_prefix" => {
+                    result.prefix_hidden = arg
+                        .val
+                        .parse()
+                        .ok()
+                        .error("hide_prefix must be true or false")?;
+                }
+                "prefix_space" => {
+                    result.prefix_has_space = arg
+                        .val
+                        .parse()
+                        .ok()
+                        .error("prefix_space must be true or false")?;
+                }
+                "pad_with" => {
+                    if arg.val.graphemes(true).count() < 2 {
+                        result.pad_with = Cow::Owned(arg.val.into());
+                    } else {
+                        return Err(Error::new(
+                            "pad_with must be an empty string or a single character",
+                        ));
+                    }
+                }
+                "range" => {
+                    let (start, end) = arg.val.split_once("..").error("invalid range")?;
+                    if !start.is_empty() {
+                        result.range = start.parse::<f64>().error("invalid range start")?
+                            ..=*result.range.end();
+                    }
+                    if !end.is_empty() {
+                        result.range = *result.range.start()
+                            ..=end.parse::<f64>().error("invalid range end")?;
+                    }
+                }
+                other => {
+                    return Err(Error::new(format!("Unknown argument for 'fix': '{other}'")));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl Formatter for FixFormatter {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String, FormatError> {
+        match val {
+            Value::Number { mut val, mut unit } => {
+                if !self.range.contains(&val) {
+                    return Err(FormatError::NumberOutOfRange(val));
+                }
+
+                if let Some(new_unit) = self.unit {
+                    val = unit.convert(val, new_unit)?;
+                    unit = new_unit;
+                }
+
+                // Unlike `eng`, the prefix is never chosen automatically
+                let prefix = unit.clamp_prefix(self.prefix.unwrap_or(Prefix::One));
+                val = prefix.apply(val);
+
+                let digits = format!("{:.*}", self.digits, val.abs());
+                // Don't show "-0" for small negative numbers
+                let sign = if val.is_sign_negative() && digits.bytes().any(|b| b > b'0') {
+                    "-"
+                } else {
+                    ""
+                };
+
+                let padding = self
+                    .pad_with
+                    .repeat(self.width.saturating_sub(sign.len() + digits.len()));
+                // Zeros go between the sign and the digits
+                let mut retval = if self.pad_with == "0" {
+                    format!("{sign}{padding}{digits}")
+                } else {
+                    format!("{padding}{sign}{digits}")
+                };
+
+                let display_prefix =
+                    !self.prefix_hidden && prefix != Prefix::One && prefix != Prefix::OneButBinary;
+                let display_unit = !self.unit_hidden && unit != Unit::None;
+
+                if display_prefix {
+                    if self.prefix_has_space {
+                        retval.push(' ');
+                    }
+                    retval.push_str(&prefix.to_string());
+                }
+                if display_unit {
+                    if self.unit_has_space || (self.prefix_has_space && !display_prefix) {
+                        retval.push(' ');
+                    }
+                    retval.push_str(&unit.to_string());
+                }
+
+                Ok(retval)
+            }
+            other => Err(FormatError::IncompatibleFormatter {
+                ty: other.type_name(),
+                fmt: "fix",
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! fmt {
+        ($name:ident, $($key:ident : $value:tt),*) => {
+            new_formatter(stringify!($name), &[
+                $( Arg { key: stringify!($key), val: stringify!($value) } ),*
+            ]).unwrap()
+        };
+    }
+
+    fn number(val: f64, unit: Unit) -> Value {
+        Value::Number { val, unit }
+    }
+
+    #[test]
+    fn fix_digits_and_width() {
+        let config = SharedConfig::default();
+
+        let fmt = fmt!(fix, w: 5);
+        let result = fmt.format(&number(1.23456, Unit::None), &config).unwrap();
+        assert_eq!(result, "    1");
+
+        let fmt = fmt!(fix, w: 6, d: 2);
+        let result = fmt.format(&number(1.23456, Unit::None), &config).unwrap();
+        assert_eq!(result, "  1.23");
+        let result = fmt.format(&number(-1.23456, Unit::None), &config).unwrap();
+        assert_eq!(result, " -1.23");
+        let result = fmt.format(&number(12345.678, Unit::None), &config).unwrap();
+        assert_eq!(result, "12345.68");
+
+        let fmt = fmt!(fix, d: 1);
+        let result = fmt.format(&number(-0.01, Unit::None), &config).unwrap();
+        assert_eq!(result, "0.0");
+        let result = fmt.format(&number(9.96, Unit::Percents), &config).unwrap();
+        assert_eq!(result, "10.0%");
+    }
+
+    #[test]
+    fn fix_padding() {
+        let config = SharedConfig::default();
+
+        let fmt = fmt!(fix, w: 5, pad_with: 0);
+        let result = fmt.format(&number(-42.0, Unit::None), &config).unwrap();
+        assert_eq!(result, "-0042");
+
+        let fmt = new_formatter(
+            "fix",
+            &[
+                Arg { key: "w", val: "5" },
+                Arg {
+                    key: "pad_with",
+                    val: "",
+                },
+            ],
+        )
+        .unwrap();
+        let result = fmt.format(&number(42.0, Unit::None), &config).unwrap();
+        assert_eq!(result, "42");
+    }
+
+    #[test]
+    fn fix_units_and_prefixes() {
+        let config = SharedConfig::default();
+        // 14.96 GiB
+        let val = number(14.96 * 1024. * 1024. * 1024., Unit::Bytes);
+
+        let fmt = fmt!(fix, d: 1, p: Gi);
+        let result = fmt.format(&val, &config).unwrap();
+        assert_eq!(result, "15.0GiB");
+
+        let fmt = fmt!(fix, w: 6, p: Mi, prefix_space: true);
+        let result = fmt.format(&val, &config).unwrap();
+        assert_eq!(result, " 15319 MiB");
+
+        let fmt = fmt!(fix, p: Mi, u: b, hide_unit: true);
+        let result = fmt.format(&val, &config).unwrap();
+        assert_eq!(result, "122552Mi");
+
+        let fmt = new_formatter("fix", &[]).unwrap();
+        let result = fmt.format(&number(1500.0, Unit::Bytes), &config).unwrap();
+        assert_eq!(result, "1500B");
+    }
+}
@@ -1,4 +1,4 @@
-use std::iter::repeat;
+use std::iter::repeat_n;
 use std::time::Instant;
 
 use crate::escape::CollectEscaped;
@@ -9,6 +9,7 @@ const DEFAULT_STR_MIN_WIDTH: usize = 0;
 const DEFAULT_STR_MAX_WIDTH: usize = usize::MAX;
 const DEFAULT_STR_ROT_INTERVAL: Option<f64> = None;
 const DEFAULT_STR_ROT_SEP: Option<String> = None;
+const DEFAULT_STR_ALIGN: Align = Align::Left;
 
 pub const DEFAULT_STRING_FORMATTER: StrFormatter = StrFormatter {
     min_width: DEFAULT_STR_MIN_WIDTH,
@@ -16,8 +17,17 @@ pub const DEFAULT_STRING_FORMATTER: StrFormatter = StrFormatter {
     rot_interval_ms: None,
     init_time: None,
     rot_separator: None,
+    align: DEFAULT_STR_ALIGN,
 };
 
+/// Where the text is placed if it is shorter than `min_width`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
 #[derive(Debug)]
 pub struct StrFormatter {
     min_width: usize,
@@ -25,6 +35,7 @@ pub struct StrFormatter {
     rot_interval_ms: Option<u64>,
     init_time: Option<Instant>,
     rot_separator: Option<String>,
+    align: Align,
 }
 
 impl StrFormatter {
@@ -33,6 +44,7 @@ impl StrFormatter {
         let mut max_width = DEFAULT_STR_MAX_WIDTH;
         let mut rot_interval = DEFAULT_STR_ROT_INTERVAL;
         let mut rot_separator = DEFAULT_STR_ROT_SEP;
+        let mut align = DEFAULT_STR_ALIGN;
         for arg in args {
             match arg.key {
                 "min_width" | "min_w" => {
@@ -55,6 +67,14 @@ impl StrFormatter {
                 "rot_separator" => {
                     rot_separator = Some(arg.val.to_string());
                 }
+                "align" => {
+                    align = match arg.val {
+                        "left" => Align::Left,
+                        "center" => Align::Center,
+                        "right" => Align::Right,
+                        _ => return Err(Error::new("align must be left, center or right")),
+                    };
+                }
                 other => {
                     return Err(Error::new(format!("Unknown argument for 'str': '{other}'")));
                 }
@@ -76,6 +96,7 @@ impl StrFormatter {
             rot_interval_ms: rot_interval.map(|x| (x * 1e3) as u64),
             init_time: Some(Instant::now()),
             rot_separator,
+            align,
         })
     }
 }
@@ -107,11 +128,19 @@ impl Formatter for StrFormatter {
                             .take(self.max_width)
                             .collect_pango_escaped()
                     }
-                    _ => text
-                        .iter()
-                        .chain(repeat(&" ").take(self.min_width.saturating_sub(width)))
-                        .take(self.max_width)
-                        .collect_pango_escaped(),
+                    _ => {
+                        let padding = self.min_width.saturating_sub(width);
+                        let (left, right) = match self.align {
+                            Align::Left => (0, padding),
+                            Align::Center => (padding / 2, padding - padding / 2),
+                            Align::Right => (padding, 0),
+                        };
+                        repeat_n(&" ", left)
+                            .chain(text.iter())
+                            .chain(repeat_n(&" ", right))
+                            .take(self.max_width)
+                            .collect_pango_escaped()
+                    }
                 })
             }
             Value::Icon(icon, value) => config.get_icon(icon, *value).map_err(Into::into),
@@ -126,3 +155,44 @@ impl Formatter for StrFormatter {
         self.rot_interval_ms.map(Duration::from_millis)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn str_align() {
+        let config = SharedConfig::default();
+        let val = Value::Text("abc".into());
+        let format = |align| {
+            new_formatter(
+                "str",
+                &[
+                    Arg {
+                        key: "min_w",
+                        val: "8",
+                    },
+                    Arg {
+                        key: "align",
+                        val: align,
+                    },
+                ],
+            )
+            .unwrap()
+            .format(&val, &config)
+            .unwrap()
+        };
+
+        assert_eq!(format("left"), "abc     ");
+        assert_eq!(format("center"), "  abc   ");
+        assert_eq!(format("right"), "     abc");
+        assert!(new_formatter(
+            "str",
+            &[Arg {
+                key: "align",
+                val: "top"
+            }]
+        )
+        .is_err());
+    }
+}
@@ -30,7 +30,9 @@ impl Default for Icons {
     fn default() -> Self {
         // "none" icon set
         Self(map! {
+            "air_quality" => "AQI",
             "backlight" => "BRIGHT",
+            "backup" => "BACKUP",
             "bat" => "BAT",
             "bat_charging" => "CHG",
             "bat_not_available" => "BAT N/A",
@@ -38,17 +40,30 @@ impl Default for Icons {
             "bell-slash" => "OFF",
             "bluetooth" => "BT",
             "calendar" => "CAL",
+            "clipboard" => "CLIP",
+            "cloud" => "CLOUD",
             "cogs" => "LOAD",
             "cpu" => "CPU",
             "cpu_boost_on" => "BOOST ON",
             "cpu_boost_off" => "BOOST OFF",
+            "crypto" => "CRYPTO",
+            "currency" => "CURRENCY",
             "disk_drive" => "DISK",
+            "dock" => "DOCK",
             "docker" => "DOCKER",
+            "electricity" => "ELEC",
             "github" => "GITHUB",
+            "gitlab" => "GITLAB",
+            "glucose" => "BG",
             "gpu" => "GPU",
             "headphones" => "HEAD",
+            "home" => "HOME",
+            "idle_inhibit_off" => "IDLE",
+            "idle_inhibit_on" => "AWAKE",
             "joystick" => "JOY",
             "keyboard" => "KBD",
+            "kubernetes" => "K8S",
+            "laptop" => "LAPTOP",
             "mail" => "MAIL",
             "memory_mem" => "MEM",
             "memory_swap" => "SWAP",
@@ -83,20 +98,38 @@ impl Default for Icons {
             "pomodoro_paused" => "PAUSED",
             "pomodoro_started" => "STARTED",
             "pomodoro_stopped" => "STOPPED",
+            "printer" => "PRN",
+            "process" => "PROC",
+            "profile_balanced" => "BAL",
+            "profile_performance" => "PERF",
+            "profile_power_saver" => "SAVE",
+            "random" => "RNG",
+            "recording" => "REC",
             "resolution" => "RES",
+            "rss" => "RSS",
+            "scratchpad" => "SCRATCH",
+            "ssh" => "SSH",
+            "stocks" => "STOCKS",
+            "stopwatch" => "STOPWATCH",
+            "sync" => "SYNC",
             "tasks" => "TSK",
             "tea" => "TEA",
             "thermometer" => "TEMP",
             "time" => "TIME",
             "toggle_off" => "OFF",
             "toggle_on" => "ON",
+            "torrent" => "TORRENT",
+            "transit" => "TRANSIT",
             "unknown" => "??",
             "update" => "UPD",
             "uptime" => "UP",
+            "user" => "USERS",
+            "vm" => "VM",
             "volume" => "VOL",
             "volume_muted" => "VOL MUTED",
             "microphone" => "MIC",
             "microphone_muted" => "MIC MUTED",
+            "warning" => "WARN",
             "weather_clouds_night" => "CLOUDY",
             "weather_clouds" => "CLOUDY",
             "weather_default" => "WEATHER",
@@ -6,6 +6,8 @@ use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
 use crate::errors::*;
+use crate::widget::State;
+use crate::wrappers::ShellString;
 
 /// Tries to find a file in standard locations:
 /// - Fist try to find a file by full path (only if path is absolute)
@@ -122,6 +124,37 @@ pub async fn read_file(path: impl AsRef<Path>) -> std::io::Result<String> {
     Ok(content.trim_end().to_string())
 }
 
+/// Returns the configured `token`, or else the content of `token_file`, or else the value of the
+/// environment variable `env_var`
+pub async fn get_token(
+    token: Option<&str>,
+    token_file: Option<&ShellString>,
+    env_var: &str,
+) -> Result<String> {
+    match (token, token_file) {
+        (Some(token), _) => Ok(token.to_string()),
+        (None, Some(path)) => Ok(read_file(&*path.expand()?)
+            .await
+            .error("Failed to read token file")?
+            .trim()
+            .to_string()),
+        (None, None) => std::env::var(env_var)
+            .ok()
+            .or_error(|| format!("No token configured and {env_var} is not set")),
+    }
+}
+
+/// Creates a command running `program`, with `sudo -n` if `use_sudo` is set
+pub fn command_maybe_sudo(program: &str, use_sudo: bool) -> Command {
+    if use_sudo {
+        let mut cmd = Command::new("sudo");
+        cmd.args(["-n", program]);
+        cmd
+    } else {
+        Command::new(program)
+    }
+}
+
 pub async fn has_command(command: &str) -> Result<bool> {
     Command::new("sh")
         .args([
@@ -134,6 +167,34 @@ pub async fn has_command(command: &str) -> Result<bool> {
         .map(|status| status.success())
 }
 
+/// Sends a GET request over a unix socket and deserializes the JSON response
+pub async fn get_over_unix_socket<T: DeserializeOwned>(
+    socket_path: impl AsRef<Path>,
+    uri: &str,
+) -> Result<T> {
+    let socket = tokio::net::UnixStream::connect(socket_path)
+        .await
+        .error("Failed to connect to socket")?;
+    let (mut request_sender, connection) = hyper::client::conn::handshake(socket)
+        .await
+        .error("Failed to create request sender")?;
+    tokio::spawn(connection);
+    let request = hyper::Request::builder()
+        .header("Host", "localhost")
+        .uri(format!("http://api{uri}"))
+        .method("GET")
+        .body(hyper::Body::empty())
+        .error("Failed to create request")?;
+    let response = request_sender
+        .send_request(request)
+        .await
+        .error("Failed to get response")?;
+    let bytes = hyper::body::to_bytes(response.into_body())
+        .await
+        .error("Failed to get response bytes")?;
+    serde_json::from_slice::<T>(&bytes).error("Failed to deserialize JSON")
+}
+
 /// # Example
 ///
 /// ```ignore
@@ -245,6 +306,28 @@ pub fn country_flag_from_iso_code(country_code: &str) -> String {
     String::from_utf8(vec![0xf0, 0x9f, 0x87, b1, 0xf0, 0x9f, 0x87, b2]).unwrap()
 }
 
+/// Returns the state of the highest of the `info`, `good`, `warning` and `critical` thresholds
+/// which `value` reaches, or `State::Idle` if it reaches none of them
+pub fn state_from_thresholds(
+    value: f64,
+    info: Option<f64>,
+    good: Option<f64>,
+    warning: Option<f64>,
+    critical: Option<f64>,
+) -> State {
+    for (threshold, state) in [
+        (critical, State::Critical),
+        (warning, State::Warning),
+        (good, State::Good),
+        (info, State::Info),
+    ] {
+        if threshold.is_some_and(|t| value >= t) {
+            return state;
+        }
+    }
+    State::Idle
+}
+
 /// A shortcut for `Default::default()`
 /// See <https://github.com/rust-lang/rust/issues/73014>
 #[inline]